@@ -32,6 +32,10 @@ pub fn write(target: &Path) {
 		).unwrap();
 	}
 
+	// Constants loaded at runtime.
+	// See `userconstant.rs`.
+	writeln!(file, "\tUser(usize),").unwrap();
+
 	writeln!(file, "}}\n").unwrap();
 
 	// ToString
@@ -59,6 +63,8 @@ pub fn write(target: &Path) {
 		}
 	}
 
+	writeln!(file, "\t\t\tConstant::User(i) => return user_constant_name(*i),").unwrap();
+
 	writeln!(file, "\t\t}})\n\t}}\n}}\n").unwrap();
 
 
@@ -88,7 +94,7 @@ pub fn write(target: &Path) {
 		}
 	}
 
-	writeln!(file, "\t\t\t_ => None\n\t\t}}\n\t}}\n").unwrap();
+	writeln!(file, "\t\t\t_ => user_constant_from_string(s)\n\t\t}}\n\t}}\n").unwrap();
 
 
 
@@ -150,6 +156,10 @@ pub fn write(target: &Path) {
 		).unwrap();
 	}
 
+	// Loaded constants aren't in all_consts(),
+	// see Context::get_constants().
+	writeln!(file, "\t\t\tConstant::User(_) => &[],").unwrap();
+
 	writeln!(file, "\t\t}}\n\t}}\n").unwrap();
 
 
@@ -190,11 +200,13 @@ pub fn write(target: &Path) {
 
 	for c in constants {
 		writeln!(file,
-			"\t\t\tConstant::{e} => parse_no_context(&String::from(\"{s}\")).unwrap(),",
+			"\t\t\tConstant::{e} => with_all_unit_sets(|| parse_no_context(&String::from(\"{s}\")).unwrap()),",
 			e = c["enum_name"].as_str().unwrap(),
 			s = c["value"].as_str().unwrap()
 		).unwrap();
 	}
 
+	writeln!(file, "\t\t\tConstant::User(i) => user_constant_value(*i),").unwrap();
+
 	writeln!(file, "\t\t}}\n\t}}\n}}").unwrap();
 }
\ No newline at end of file
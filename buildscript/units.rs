@@ -27,6 +27,10 @@ fn write_wholeunit_main(mut file: &File, units: &Vec<Value>) {
 		).unwrap();
 	}
 
+	// Units defined at runtime.
+	// See `userunit.rs`.
+	writeln!(file, "\tUser(usize),").unwrap();
+
 	writeln!(file, "}}\n").unwrap();
 
 	// ToString
@@ -45,6 +49,7 @@ fn write_wholeunit_main(mut file: &File, units: &Vec<Value>) {
 			e = u["enum_name"].as_str().unwrap()
 		).unwrap();
 	}
+	writeln!(file, "\t\t\tWholeUnit::User(i) => return user_unit_name(*i),").unwrap();
 
 	writeln!(file, "\t\t}})\n\t}}\n}}\n").unwrap();
 
@@ -69,7 +74,63 @@ fn write_wholeunit_main(mut file: &File, units: &Vec<Value>) {
 		}
 	}
 
-	writeln!(file, "\t\t\t_ => false\n\t\t}}\n\t}}\n}}").unwrap();
+	writeln!(file, "\t\t\tWholeUnit::User(i) => user_unit_no_space(*i),").unwrap();
+	writeln!(file, "\t\t\t_ => false\n\t\t}}\n\t}}\n").unwrap();
+
+
+	// Base units by enum name.
+	// Used to check definitions loaded at runtime.
+	writeln!(file,
+		concat!(
+			"\tpub fn base_from_enum_name(s: &str) -> Option<WholeUnit> {{\n",
+			"\t\tmatch s {{"
+		)
+	).unwrap();
+
+	for u in units {
+		if {
+			u.as_table().unwrap().contains_key("base") &&
+			u["base"].as_bool().unwrap() &&
+			!u.as_table().unwrap().contains_key("log")
+		} {
+			writeln!(file,
+				"\t\t\t\"{e}\" => Some(WholeUnit::{e}),",
+				e = u["enum_name"].as_str().unwrap()
+			).unwrap();
+		}
+	}
+
+	writeln!(file, "\t\t\t_ => None\n\t\t}}\n\t}}\n").unwrap();
+
+
+	// Unit sets
+	writeln!(file,
+		concat!(
+			"\tpub fn unit_set(&self) -> Option<UnitSet> {{\n",
+			"\t\tmatch self {{"
+		)
+	).unwrap();
+
+	for u in units {
+		if u.as_table().unwrap().contains_key("set") {
+			let s = match u["set"].as_str().unwrap() {
+				"imperial" => "Imperial",
+				"us" => "USCustomary",
+				"nautical" => "Nautical",
+				"data" => "Data",
+				"obscure" => "Obscure",
+				x => panic!("Unknown unit set `{x}`")
+			};
+
+			writeln!(file,
+				"\t\t\tWholeUnit::{} => Some(UnitSet::{s}),",
+				u["enum_name"].as_str().unwrap()
+			).unwrap();
+		}
+	}
+
+	writeln!(file, "\t\t\tWholeUnit::User(i) => user_unit_set(*i),").unwrap();
+	writeln!(file, "\t\t\t_ => None\n\t\t}}\n\t}}\n}}").unwrap();
 }
 
 
@@ -84,6 +145,8 @@ fn write_wholeunit_base_factor(mut file: &File, units: &Vec<Value>) {
 		)
 	).unwrap();
 
+	writeln!(file, "\t\t\tWholeUnit::User(i) => user_unit_base_factor(*i),").unwrap();
+
 	for u in units {
 
 
@@ -120,6 +183,15 @@ fn write_wholeunit_base_factor(mut file: &File, units: &Vec<Value>) {
 				).unwrap();
 			},
 
+			"pi_fract" => {
+				writeln!(file,
+					"\t\t\t\tscalar: Scalar::new_pi_from_frac({}, {}, {}).unwrap(),",
+					u["base_value"].as_array().unwrap()[0].as_integer().unwrap(),
+					u["base_value"].as_array().unwrap()[1].as_integer().unwrap(),
+					u["base_value"].as_array().unwrap()[2].as_integer().unwrap(),
+				).unwrap();
+			},
+
 			"approx" => {
 				writeln!(file,
 					"\t\t\t\tscalar: Scalar::new_float_from_string(\"{}\").unwrap(),",
@@ -148,7 +220,8 @@ fn write_wholeunit_base_factor(mut file: &File, units: &Vec<Value>) {
 
 		writeln!(file,
 			concat!(
-				"\t\t\t\t])\n",
+				"\t\t\t\t]),\n",
+				"\t\t\t\tformat: None\n",
 				"\t\t\t}}),"
 			),
 		).unwrap();
@@ -158,6 +231,78 @@ fn write_wholeunit_base_factor(mut file: &File, units: &Vec<Value>) {
 }
 
 
+/// Create WholeUnit::log_scale() and WholeUnit::log_reference().
+/// Should only be run once.
+fn write_wholeunit_log(mut file: &File, units: &Vec<Value>) {
+	writeln!(file,
+		concat!(
+			"impl WholeUnit {{\n",
+			"\tpub fn log_scale(&self) -> Option<LogScale> {{\n",
+			"\t\tmatch self {{"
+		)
+	).unwrap();
+
+	for u in units {
+		if u.as_table().unwrap().contains_key("log") {
+			let s = match u["log"].as_str().unwrap() {
+				"ratio" => "Ratio",
+				"power" => "Power",
+				"field" => "Field",
+				x => panic!("Unknown log scale `{x}`")
+			};
+
+			writeln!(file,
+				"\t\t\tWholeUnit::{} => Some(LogScale::{s}),",
+				u["enum_name"].as_str().unwrap()
+			).unwrap();
+		}
+	}
+
+	writeln!(file, "\t\t\t_ => None\n\t\t}}\n\t}}\n").unwrap();
+
+
+	// Reference quantities of levels, in base units
+	writeln!(file,
+		concat!(
+			"\tpub fn log_reference(&self) -> Option<Quantity> {{\n",
+			"\t\tmatch self {{"
+		)
+	).unwrap();
+
+	for u in units {
+		if !u.as_table().unwrap().contains_key("reference_value") { continue; }
+
+		writeln!(file,
+			concat!(
+				"\t\t\tWholeUnit::{} => Some(Quantity{{\n",
+				"\t\t\t\tscalar: Scalar::new_rational_from_string(\"{}\").unwrap(),\n",
+				"\t\t\t\tunit: Unit::from_array(&["
+			),
+			u["enum_name"].as_str().unwrap(),
+			u["reference_value"].as_str().unwrap()
+		).unwrap();
+
+		for b in u["reference_units"].as_array().unwrap() {
+			writeln!(file,
+				"\t\t\t\t\t(FreeUnit{{whole: WholeUnit::{u}, prefix: Prefix::None}}, Scalar::new_rational({p}f64).unwrap()),",
+				u = b.as_table().unwrap()["u"].as_str().unwrap(),
+				p = b.as_table().unwrap()["p"].as_integer().unwrap(),
+			).unwrap();
+		}
+
+		writeln!(file,
+			concat!(
+				"\t\t\t\t]),\n",
+				"\t\t\t\tformat: None\n",
+				"\t\t\t}}),"
+			),
+		).unwrap();
+	}
+
+	writeln!(file, "\t\t\t_ => None\n\t\t}}\n\t}}\n}}").unwrap();
+}
+
+
 /// Create freeunit_from_string().
 /// Should only be run once.
 fn write_freeunit_from_string(mut file: &File, units: &Vec<Value>) {
@@ -165,7 +310,7 @@ fn write_freeunit_from_string(mut file: &File, units: &Vec<Value>) {
 		concat!(
 			"#[inline(always)]\n",
 			"pub fn freeunit_from_string(s: &str) -> Option<FreeUnit> {{\n",
-			"\tmatch s {{"
+			"\tlet u = match s {{"
 		),
 	).unwrap();
 
@@ -196,11 +341,92 @@ fn write_freeunit_from_string(mut file: &File, units: &Vec<Value>) {
 		writeln!(file, "").unwrap();
 	}
 
-	writeln!(file, "\t\t_ => None\n\t}}\n}}").unwrap();
+	writeln!(file, "\t\t_ => user_freeunit_from_string(s)\n\t}};\n").unwrap();
+
+	// Units in disabled sets can't be parsed
+	writeln!(file, "\treturn u.filter(|u| u.whole.is_enabled());\n}}").unwrap();
+}
+
+/// Create WholeUnit::all_units(), WholeUnit::strings(),
+/// and named_dimensions(). Should only be run once.
+fn write_unit_lists(mut file: &File, units: &Vec<Value>, dimensions: &Vec<Value>) {
+	writeln!(file,
+		concat!(
+			"impl WholeUnit {{\n",
+			"\t// Every built-in unit, in the order they are defined.\n",
+			"\tpub fn all_units() -> &'static [WholeUnit] {{\n",
+			"\t\treturn &["
+		)
+	).unwrap();
+
+	for u in units {
+		writeln!(file,
+			"\t\t\tWholeUnit::{},",
+			u["enum_name"].as_str().unwrap()
+		).unwrap();
+	}
+
+	writeln!(file, "\t\t]\n\t}}\n").unwrap();
+
+
+	writeln!(file,
+		concat!(
+			"\t// Strings that are parsed as this unit, and the prefixes each may take.\n",
+			"\tpub fn strings(&self) -> Vec<(String, Vec<Prefix>)> {{\n",
+			"\t\tmatch self {{"
+		)
+	).unwrap();
+
+	for u in units {
+		write!(file,
+			"\t\t\tWholeUnit::{} => vec![",
+			u["enum_name"].as_str().unwrap()
+		).unwrap();
+
+		for s in u["strings"].as_array().unwrap() {
+			let s = s.as_array().unwrap();
+			write!(file, "(String::from(\"{}\"), vec![", s[0].as_str().unwrap()).unwrap();
+
+			if s.len() == 1 {
+				write!(file, "Prefix::None").unwrap();
+			} else {
+				for p in &s[1..] {
+					write!(file, "str_to_prefix!(\"{}\"),", p.as_str().unwrap()).unwrap();
+				}
+			}
+
+			write!(file, "]),").unwrap();
+		}
+
+		writeln!(file, "],").unwrap();
+	}
+
+	writeln!(file, "\t\t\tWholeUnit::User(i) => user_unit_strings(*i),").unwrap();
+	writeln!(file, "\t\t}}\n\t}}\n}}\n").unwrap();
+
+
+	writeln!(file,
+		concat!(
+			"// Named dimensions, each with a unit of that dimension.\n",
+			"pub fn named_dimensions() -> &'static [(&'static str, WholeUnit)] {{\n",
+			"\treturn &["
+		)
+	).unwrap();
+
+	for d in dimensions {
+		writeln!(file,
+			"\t\t(\"{}\", WholeUnit::{}),",
+			d["name"].as_str().unwrap(),
+			d["unit"].as_str().unwrap()
+		).unwrap();
+	}
+
+	writeln!(file, "\t]\n}}").unwrap();
 }
 
 pub fn write(target: &Path) {
 	let units = include_str!("units.toml").parse::<Table>().unwrap();
+	let toml::Value::Array(dimensions) = &units["dimension"] else {panic!()};
 	let toml::Value::Array(units) = &units["unit"] else {panic!()};
 
 	let mut file = OpenOptions::new()
@@ -217,5 +443,11 @@ pub fn write(target: &Path) {
 	write_wholeunit_base_factor(&file, units);
 	writeln!(file, "\n\n").unwrap();
 
+	write_wholeunit_log(&file, units);
+	writeln!(file, "\n\n").unwrap();
+
 	write_freeunit_from_string(&file, units);
+	writeln!(file, "\n\n").unwrap();
+
+	write_unit_lists(&file, units, dimensions);
 }
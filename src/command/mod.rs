@@ -1,8 +1,24 @@
 use crate::context::Context;
 use crate::parser::Constant;
 use crate::parser::substitute;
+use crate::quantity::DisplayFormat;
+use crate::quantity::Notation;
+use crate::quantity::Unit;
+use crate::quantity::FreeUnit;
+use crate::quantity::WholeUnit;
+use crate::quantity::Prefix;
+use crate::quantity::UnitSet;
+use crate::quantity::freeunit_from_string;
+use crate::quantity::named_dimensions;
 use crate::formattedtext::FormattedText;
 
+// Bounds for the `precision` command, in bits.
+const MIN_PRECISION: usize = 32;
+const MAX_PRECISION: usize = 16384;
+
+// Most significant digits and fixed decimals we'll show
+const MAX_DIGITS: usize = 100;
+
 pub fn is_command(
 	s: &String
 ) -> bool {
@@ -15,8 +31,21 @@ pub fn is_command(
 		| "fns" | "functions"
 		| "vars"
 		| "consts" | "constants"
+		| "units"
+		| "unitset"
 		| "del" | "delete"
 		| "flags"
+		| "polar"
+		| "precision"
+		| "base"
+		| "display"
+		| "digits"
+		| "notation"
+		| "prefix"
+		| "group"
+		| "factlimit"
+		| "seed"
+		| "tolerance"
 		=> true,
 		_ => false
 	}
@@ -43,11 +72,73 @@ fn greeter() -> FormattedText {
 }
 
 
+// Every unit we know, grouped by dimension.
+// Each group has a title, the base units of its dimension, and its units.
+// The base units a unit is grouped by.
+// Levels like dBm are listed with the quantity they measure.
+fn unit_dimension(w: WholeUnit) -> Unit {
+	return match w.log_reference() {
+		Some(r) => r.unit,
+		None => FreeUnit::from_whole(w).to_base().unit().clone()
+	};
+}
+
+fn unit_groups(context: &Context) -> Vec<(String, Unit, Vec<WholeUnit>)> {
+	let mut groups: Vec<(String, Unit, Vec<WholeUnit>)> = named_dimensions().iter()
+		.map(|(n, w)| {
+			let mut c = n.chars();
+			let n = c.next().unwrap().to_uppercase().chain(c).collect();
+			(n, FreeUnit::from_whole(*w).to_base().unit().clone(), Vec::new())
+		})
+		.collect();
+
+	let user = (0..context.get_units().len()).map(WholeUnit::User);
+	for w in WholeUnit::all_units().iter().copied().chain(user) {
+		if !w.is_enabled() { continue; }
+		let d = unit_dimension(w);
+
+		match groups.iter_mut().find(|g| g.1 == d) {
+			Some(g) => { g.2.push(w); },
+			None => {
+				// Dimensions without a name are titled by their base units
+				let n = if d.unitless() { "Dimensionless".to_string() } else { d.display(context) };
+				groups.push((n, d, vec![w]));
+			}
+		}
+	}
+
+	groups.retain(|g| !g.2.is_empty());
+	return groups;
+}
+
+// Describe the prefixes a unit takes, like `SI prefixes`.
+fn describe_prefixes(p: &[Prefix]) -> String {
+	if p.iter().all(|x| *x == Prefix::None) { return String::new(); }
+
+	let si = Prefix::si_prefixes().iter().all(|x| p.contains(x));
+	let binary = p.iter().any(|x| x.exponent().is_none());
+
+	return match (si, binary) {
+		(true, true) => "SI and binary prefixes".to_string(),
+		(true, false) => "SI prefixes".to_string(),
+		_ => {
+			let v: Vec<String> = p.iter()
+				.filter(|x| **x != Prefix::None)
+				.map(|x| x.to_string())
+				.collect();
+			format!("prefixes {}", v.join(", "))
+		}
+	};
+}
+
+
 #[inline(always)]
 pub fn do_command(
 	context: &mut Context,
 	s: &String,
 ) -> FormattedText {
+	context.activate();
+
 	let args: Vec<&str> = s.split(" ").collect();
 	let first = args[0];
 
@@ -64,18 +155,34 @@ pub fn do_command(
 					" - Use Ctrl-C or Ctrl-D to quit.\n",
 					" - Use [c]ans[n] to reference the last result.\n",
 					" - Use [c]var = 1337[n] to define varibles.\n",
+					" - Use [c]unit smoot = 1.7018 m[n] to define units.\n",
+					" - Use [c]0x[n], [c]0b[n], and [c]0o[n] for hex, binary, and octal.\n",
+					" - Use [c]255 to hex[n] to print in another base.\n",
+					" - Use [c]0.75 to frac[n] to print a fraction.\n",
 					"\n",
 					"╞═══════════════ [t]Commands[n] ═══════════════╡\n",
-					"      [c]help[n]   Show this help\n",
-					"      [c]flags[n]  Show command-line options\n",
-					"      [c]clear[n]  Clear the terminal\n",
-					"      [c]quit[n]   Exit daisy\n",
-					//"      [c]units[n]  List available units\n",
-					"      [c]consts[n] List built-in constants\n",
-					"      [c]ops[n]    List built-in operators\n",
-					"      [c]fns[n]    List built-in functions\n",
-					"      [c]vars[n]   List user-defined variables\n",
-					"      [c]del[n]    Delete a variable\n",
+					"      [c]help[n]       Show this help\n",
+					"      [c]flags[n]      Show command-line options\n",
+					"      [c]clear[n]      Clear the terminal\n",
+					"      [c]quit[n]       Exit daisy\n",
+					"      [c]units[n]      List available units\n",
+					"      [c]unitset[n]    Turn unit sets on or off\n",
+					"      [c]consts[n]     List built-in constants\n",
+					"      [c]ops[n]        List built-in operators\n",
+					"      [c]fns[n]        List built-in functions\n",
+					"      [c]vars[n]       List user-defined variables\n",
+					"      [c]del[n]        Delete a variable\n",
+					"      [c]polar[n]      Toggle polar complex output\n",
+					"      [c]precision[n]  Set float precision\n",
+					"      [c]base[n]       Set output base\n",
+					"      [c]display[n]    Show decimals or fractions\n",
+					"      [c]digits[n]     Set significant digits\n",
+					"      [c]notation[n]   Set fixed, sci, or eng notation\n",
+					"      [c]prefix[n]     Toggle automatic SI prefixes\n",
+					"      [c]group[n]      Set digit separator\n",
+					"      [c]factlimit[n]  Set largest exact factorial\n",
+					"      [c]seed[n]       Seed the random number generator\n",
+					"      [c]tolerance[n]  Set numerical tolerance\n",
 					"\n\n",
 				)
 			);
@@ -99,11 +206,342 @@ pub fn do_command(
 					"  [c]--nosub[n]       Disable inline substitution\n",
 					"  [c]--nosuper[n]     Disable superscript powers\n",
 					"  [c]--nooneover[n]   Disable \"one-over\" fractions as -1 power\n",
+					"  [c]--polar[n]       Print complex numbers in polar form\n",
+					"  [c]--units FILE[n]  Load unit and constant definitions\n",
 					"\n\n"
 				).to_string()
 			);
 		},
 
+		"polar" => {
+			context.config.enable_polar = !context.config.enable_polar;
+			return FormattedText::new(
+				if context.config.enable_polar {
+					"Complex numbers will be shown in polar form.\n\n"
+				} else {
+					"Complex numbers will be shown in rectangular form.\n\n"
+				}.to_string()
+			);
+		},
+
+		"precision" => {
+			if args.len() == 1 {
+				return FormattedText::new(
+					match context.config.float_precision {
+						None => "Using f64 floats (53 bits)\n\n".to_string(),
+						Some(p) => format!("Using {p}-bit floats\n\n")
+					}
+				);
+			}
+
+			if args.len() != 2 {
+				return FormattedText::new(
+					format!(
+						"[c]{first}[n] [e]takes exactly one argument.[n]\n\n",
+					)
+				);
+			}
+
+			if args[1] == "f64" {
+				context.config.float_precision = None;
+				context.config.check();
+				return FormattedText::new("Using f64 floats (53 bits)\n\n".to_string());
+			}
+
+			let p = args[1].parse::<usize>();
+			match p {
+				Ok(p) if (MIN_PRECISION..=MAX_PRECISION).contains(&p) => {
+					context.config.float_precision = Some(p);
+					context.config.check();
+					return FormattedText::new(format!("Using {p}-bit floats\n\n"));
+				},
+
+				_ => {
+					return FormattedText::new(
+						format!(
+							"[e]Precision must be [c]f64[e] or a number of bits between {MIN_PRECISION} and {MAX_PRECISION}.[n]\n\n",
+						)
+					);
+				}
+			}
+		},
+
+		"base" => {
+			if args.len() == 1 {
+				let r = match context.config.output_format {
+					DisplayFormat::Radix(r) => r,
+					_ => 10
+				};
+				return FormattedText::new(format!("Printing numbers in base {r}\n\n"));
+			}
+
+			if args.len() != 2 {
+				return FormattedText::new(
+					format!(
+						"[c]{first}[n] [e]takes exactly one argument.[n]\n\n",
+					)
+				);
+			}
+
+			let r = match args[1] {
+				"bin" | "2" => 2,
+				"oct" | "8" => 8,
+				"dec" | "10" => 10,
+				"hex" | "16" => 16,
+				_ => {
+					return FormattedText::new(
+						"[e]Base must be one of [c]bin[e], [c]oct[e], [c]dec[e], or [c]hex[e].[n]\n\n".to_string()
+					);
+				}
+			};
+
+			context.config.output_format = DisplayFormat::Radix(r);
+			return FormattedText::new(format!("Printing numbers in base {r}\n\n"));
+		},
+
+		"display" => {
+			let describe = |f: DisplayFormat| match f {
+				DisplayFormat::Fraction => "Printing exact numbers as fractions\n\n".to_string(),
+				DisplayFormat::Mixed => "Printing exact numbers as mixed numbers\n\n".to_string(),
+				DisplayFormat::Radix(10) => "Printing numbers as decimals\n\n".to_string(),
+				DisplayFormat::Radix(r) => format!("Printing numbers in base {r}\n\n")
+			};
+
+			if args.len() == 1 {
+				return FormattedText::new(describe(context.config.output_format));
+			}
+
+			if args.len() != 2 {
+				return FormattedText::new(
+					format!(
+						"[c]{first}[n] [e]takes exactly one argument.[n]\n\n",
+					)
+				);
+			}
+
+			let f = match args[1] {
+				"dec" => DisplayFormat::Radix(10),
+				"frac" | "fraction" => DisplayFormat::Fraction,
+				"mixed" => DisplayFormat::Mixed,
+				_ => {
+					return FormattedText::new(
+						"[e]Display mode must be one of [c]dec[e], [c]frac[e], or [c]mixed[e].[n]\n\n".to_string()
+					);
+				}
+			};
+
+			context.config.output_format = f;
+			return FormattedText::new(describe(f));
+		},
+
+		"digits" => {
+			if args.len() == 1 {
+				return FormattedText::new(
+					match context.config.sig_digits {
+						None => "Using default significant digits\n\n".to_string(),
+						Some(n) => format!("Showing {n} significant digits\n\n")
+					}
+				);
+			}
+
+			if args.len() != 2 {
+				return FormattedText::new(
+					format!(
+						"[c]{first}[n] [e]takes exactly one argument.[n]\n\n",
+					)
+				);
+			}
+
+			if args[1] == "auto" {
+				context.config.sig_digits = None;
+				context.config.check();
+				return FormattedText::new("Using default significant digits\n\n".to_string());
+			}
+
+			match args[1].parse::<usize>() {
+				Ok(n) if (1..=MAX_DIGITS).contains(&n) => {
+					context.config.sig_digits = Some(n);
+					context.config.check();
+					return FormattedText::new(format!("Showing {n} significant digits\n\n"));
+				},
+
+				_ => {
+					return FormattedText::new(
+						format!(
+							"[e]Digits must be [c]auto[e] or a number between 1 and {MAX_DIGITS}.[n]\n\n",
+						)
+					);
+				}
+			}
+		},
+
+		"notation" => {
+			let describe = |n: Notation| match n {
+				Notation::Auto => "Using scientific notation for large and small numbers\n\n".to_string(),
+				Notation::Fixed(d) => format!("Showing {d} digits after the decimal point\n\n"),
+				Notation::Scientific => "Using scientific notation\n\n".to_string(),
+				Notation::Engineering => "Using engineering notation\n\n".to_string()
+			};
+
+			if args.len() == 1 {
+				return FormattedText::new(describe(context.config.notation));
+			}
+
+			let n = match (args[1], args.len()) {
+				("auto", 2) => Some(Notation::Auto),
+				("sci", 2) => Some(Notation::Scientific),
+				("eng", 2) => Some(Notation::Engineering),
+				("fix", 3) => {
+					args[2].parse::<usize>().ok()
+						.filter(|d| *d <= MAX_DIGITS)
+						.map(Notation::Fixed)
+				},
+				_ => None
+			};
+
+			let Some(n) = n else {
+				return FormattedText::new(
+					format!(
+						"[e]Notation must be [c]auto[e], [c]sci[e], [c]eng[e], or [c]fix <n>[e], with n at most {MAX_DIGITS}.[n]\n\n",
+					)
+				);
+			};
+
+			context.config.notation = n;
+			context.config.check();
+			return FormattedText::new(describe(n));
+		},
+
+		"prefix" => {
+			context.config.enable_si_prefix = !context.config.enable_si_prefix;
+			return FormattedText::new(
+				if context.config.enable_si_prefix {
+					"Results will use SI prefixes.\n\n"
+				} else {
+					"Results will keep their units.\n\n"
+				}.to_string()
+			);
+		},
+
+		"group" => {
+			let describe = |c: Option<char>| match c {
+				None => "Digits will not be grouped\n\n".to_string(),
+				Some(' ') => "Grouping digits with spaces\n\n".to_string(),
+				Some(c) => format!("Grouping digits with [c]{c}[n]\n\n")
+			};
+
+			if args.len() == 1 {
+				return FormattedText::new(describe(context.config.digit_separator));
+			}
+
+			if args.len() != 2 {
+				return FormattedText::new(
+					format!(
+						"[c]{first}[n] [e]takes exactly one argument.[n]\n\n",
+					)
+				);
+			}
+
+			let mut chars = args[1].chars();
+			let c = match args[1] {
+				"off" => None,
+				"space" => Some(' '),
+				_ => match (chars.next(), chars.next()) {
+					(Some(c), None) if !c.is_ascii_digit() && !".[]".contains(c) => Some(c),
+					_ => {
+						return FormattedText::new(
+							"[e]Separator must be [c]off[e], [c]space[e], or a single character like [c],[e].[n]\n\n".to_string()
+						);
+					}
+				}
+			};
+
+			context.config.digit_separator = c;
+			context.config.check();
+			return FormattedText::new(describe(c));
+		},
+
+		"factlimit" => {
+			if args.len() == 1 {
+				return FormattedText::new(
+					format!("Exact factorials are limited to {}!\n\n", context.config.max_factorial)
+				);
+			}
+
+			if args.len() != 2 {
+				return FormattedText::new(
+					format!(
+						"[c]{first}[n] [e]takes exactly one argument.[n]\n\n",
+					)
+				);
+			}
+
+			let Ok(n) = args[1].parse::<usize>() else {
+				return FormattedText::new(
+					"[e]Factorial limit must be a non-negative integer.[n]\n\n".to_string()
+				);
+			};
+
+			context.config.max_factorial = n;
+			return FormattedText::new(format!("Exact factorials are limited to {n}!\n\n"));
+		},
+
+		"seed" => {
+			if args.len() == 1 {
+				return FormattedText::new(
+					format!("Random seed is {}\n\n", context.rng().get_seed())
+				);
+			}
+
+			if args.len() != 2 {
+				return FormattedText::new(
+					format!(
+						"[c]{first}[n] [e]takes exactly one argument.[n]\n\n",
+					)
+				);
+			}
+
+			let Ok(n) = args[1].parse::<u64>() else {
+				return FormattedText::new(
+					"[e]Seed must be a non-negative integer.[n]\n\n".to_string()
+				);
+			};
+
+			context.seed(n);
+			return FormattedText::new(format!("Random seed is {n}\n\n"));
+		},
+
+		"tolerance" => {
+			if args.len() == 1 {
+				return FormattedText::new(
+					format!("Numerical tolerance is {:e}\n\n", context.config.tolerance)
+				);
+			}
+
+			if args.len() != 2 {
+				return FormattedText::new(
+					format!(
+						"[c]{first}[n] [e]takes exactly one argument.[n]\n\n",
+					)
+				);
+			}
+
+			let Ok(t) = args[1].parse::<f64>() else {
+				return FormattedText::new(
+					"[e]Tolerance must be a number.[n]\n\n".to_string()
+				);
+			};
+
+			if !(t > 0f64 && t < 1f64) {
+				return FormattedText::new(
+					"[e]Tolerance must be between 0 and 1.[n]\n\n".to_string()
+				);
+			}
+
+			context.config.tolerance = t;
+			return FormattedText::new(format!("Numerical tolerance is {t:e}\n\n"));
+		},
+
 		"clear" => {
 			return FormattedText::new("[clear]".to_string());
 		},
@@ -117,16 +555,27 @@ pub fn do_command(
 					"╞═════ [t]Operator[n] ═════╪═════ [t]Syntax[n] ═════╡\n",
 					"  function             [c]sin, cos, etc[n]\n",
 					"  factorial            [c]![n]\n",
+					"  double factorial     [c]!![n]\n",
 					"  powers               [c]^, **[n]\n",
 					"  implicit multiply    [c]3π, 3(2+1), etc[n]\n",
 					"  square root          [c]sqrt, rt, √[n]\n",
+					"  bitwise not          [c]not[n]\n",
 					"  negate               [c]-3, -(1 + 2)[n]\n",
 					"  modulo (short)       [c]%[n]\n",
 					"  multiply, divide     [c]*, /, ×, ÷[n]\n",
 					"  add, subtract        [c]+, -[n]\n",
+					"  uncertainty          [c]±, +/-, +-[n]\n",
+					"  bit shift            [c]<<, >>[n]\n",
+					"  bitwise and          [c]and[n]\n",
+					"  bitwise xor          [c]xor[n]\n",
+					"  bitwise or           [c]or[n]\n",
 					"  unit conversion      [c]to[n]\n",
 					"  division (long)      [c]per[n]\n",
 					"  modulo (long)        [c]mod[n]\n",
+					"  comparison           [c]==, !=, <, <=, >, >=[n]\n",
+					"  logical not          [c]!x[n]\n",
+					"  logical and          [c]&&[n]\n",
+					"  logical or           [c]||[n]\n",
 					"\n\n"
 				).to_string()
 			);
@@ -140,12 +589,69 @@ pub fn do_command(
 					"  floor, ceiling, round    [c]floor, ceil, round[n]\n",
 					"  log base e               [c]ln[n]\n",
 					"  log base 10              [c]log[n]\n",
+					"  log base b               [c]log(x, b)[n]\n",
+					"  nth root                 [c]root(x, n)[n]\n",
 					"  sin, arcsin, cosecant    [c]sin, asin, csc[n]\n",
 					"  cos, arccos, secant      [c]cos, acos, secant[n]\n",
 					"  tan, arctan, cotan       [c]tan, atan, cot[n]\n",
 					"  hyperbolic sin, etc      [c]sinh, asinh, csch[n]\n",
 					"  hyperbolic cos, etc      [c]cosh, acosh, sech[n]\n",
 					"  hyperbolic tan, etc      [c]tanh, atanh, coth[n]\n",
+					"  two-argument arctan      [c]atan2(y, x)[n]\n",
+					"  hypotenuse               [c]hypot(a, b)[n]\n",
+					"\n",
+					"  minimum, maximum         [c]min, max[n]\n",
+					"  clamp to a range         [c]clamp(x, lo, hi)[n]\n",
+					"  interval from lo to hi   [c][lo, hi], interval(lo, hi)[n]\n",
+					"  choose a or b            [c]if(cond, a, b)[n]\n",
+					"\n",
+					"  sum, product             [c]sum, product[n]\n",
+					"  sum, product over k      [c]sum(k, a, b, f), prod(...)[n]\n",
+					"  mean, median, mode       [c]mean, median, mode[n]\n",
+					"  sample variance, stdev   [c]variance, stdev[n]\n",
+					"  range                    [c]range[n]\n",
+					"  percentile               [c]percentile(p, ...)[n]\n",
+					"\n",
+					"  integral of f            [c]integrate(f, a, b)[n]\n",
+					"  integral over x          [c]integrate(x, a, b, f)[n]\n",
+					"  derivative of f          [c]derive(f, x)[n]\n",
+					"  derivative over x        [c]derive(x, x0, f)[n]\n",
+					"  root of f                [c]solve(f, x0), solve(f, a, b)[n]\n",
+					"  root over x              [c]solve(f, x, x0), solve(f, x, a, b)[n]\n",
+					"\n",
+					"  combinations             [c]nCr(n, r)[n]\n",
+					"  permutations             [c]nPr(n, r)[n]\n",
+					"  multinomial coefficient  [c]multinomial(...)[n]\n",
+					"  binomial pmf, cdf        [c]binompmf, binomcdf(k, n, p)[n]\n",
+					"  roll n s-sided dice      [c]dice(n, s), 3d6[n]\n",
+					"  random number in [0, 1)  [c]rand()[n]\n",
+					"\n",
+					"  gamma, log of |gamma|    [c]gamma, lgamma[n]\n",
+					"  beta                     [c]beta(a, b)[n]\n",
+					"  error function           [c]erf, erfc[n]\n",
+					"\n",
+					"  gcd, lcm                 [c]gcd, lcm[n]\n",
+					"  inverse of a mod m       [c]modinv(a, m)[n]\n",
+					"  b^e mod m                [c]modpow(b, e, m)[n]\n",
+					"  closest fraction         [c]rationalize(x, maxden)[n]\n",
+					"  primality test           [c]isprime[n]\n",
+					"  prime factors            [c]factor[n]\n",
+					"  totient                  [c]totient[n]\n",
+					"  next prime               [c]nextprime[n]\n",
+					"\n",
+					"  real, imaginary part     [c]re, im[n]\n",
+					"  complex argument         [c]arg[n]\n",
+					"  complex conjugate        [c]conj[n]\n",
+					"\n",
+					"  determinant, inverse     [c]det, inv[n]\n",
+					"  transpose                [c]transpose[n]\n",
+					"  dot, cross product       [c]dot(a, b), cross(a, b)[n]\n",
+					"  vector length            [c]norm[n]\n",
+					"\n",
+					"  polynomial roots         [c]polyroots(1, -3, 2)[n]\n",
+					"  evaluate a polynomial    [c]polyval(p, x)[n]\n",
+					"  multiply polynomials     [c]polymul(p, q)[n]\n",
+					"  divide polynomials       [c]polydiv(p, q), polyrem(p, q)[n]\n",
 					"\n",
 					"  Celsius to Kelvin        [c]fromC, fromCelsius[n]\n",
 					"  Kelvin to Celsius        [c]toC,   toCelsius[n]\n",
@@ -165,10 +671,11 @@ pub fn do_command(
 		"vars" => {
 			let v = context.get_variables();
 			let f = context.get_functions();
+			let u = context.get_units();
 
-			if v.len() + f.len() == 0 {
+			if v.len() + f.len() + u.len() == 0 {
 				return FormattedText::new(
-					"You have not defined any variables\n\n".to_string()
+					"You have not defined any variables or units\n\n".to_string()
 				);
 			}
 
@@ -186,6 +693,11 @@ pub fn do_command(
 					longest = s.len();
 				}
 			}
+			for x in u {
+				if x.name.len() > longest {
+					longest = x.name.len();
+				}
+			}
 
 
 			if v.len() != 0 {
@@ -215,6 +727,21 @@ pub fn do_command(
 				}
 			}
 
+			if u.len() != 0 {
+				t.push("\n╞═══ [t]User-Defined Units[n] ═══╡\n");
+
+				for x in u {
+					let padding = " ".repeat(longest - x.name.len());
+
+					t.push(&format!(
+						"  {n}{padding} = [c]{v}[n]{p}\n",
+						n = x.name,
+						v = x.value.display(context),
+						p = if x.takes_prefixes() { " (takes prefixes)" } else { "" }
+					));
+				}
+			}
+
 			t.push("\n\n");
 			return t;
 		},
@@ -242,10 +769,163 @@ pub fn do_command(
 				t.push(&"\n");
 			}
 
+			let l = context.get_constants();
+			if l.iter().any(|c| c.pretty_name.is_some()) {
+				t.push("\n╞═══ [t]Loaded Constants[n] ═══╡\n");
+
+				for c in l {
+					let Some(p) = &c.pretty_name else { continue };
+					let padding = " ".repeat(25usize.saturating_sub(p.chars().count()));
+
+					t.push(&format!(
+						"  {p}{padding}: [c]{s}[n]\n",
+						s = c.strings.join(", "),
+					));
+				}
+			}
+
 			t.push(&"\n\n");
 			return t;
 		},
 
+		"units" => {
+			let mut groups = unit_groups(context);
+
+			// `units length` or `units ft` show one dimension
+			if args.len() > 1 {
+				let a = args[1..].join(" ");
+				let d = freeunit_from_string(&a).map(|u| unit_dimension(u.get_whole()));
+				groups.retain(|g| g.0.to_lowercase() == a.to_lowercase() || Some(&g.1) == d.as_ref());
+
+				if groups.is_empty() {
+					return FormattedText::new(
+						format!(
+							"[c]{a}[n] [e]isn't a dimension or a unit.[n]\n\n",
+						)
+					);
+				}
+			}
+
+			let mut t = FormattedText::new("".to_string());
+			for (n, _, v) in groups {
+				t.push(&format!("\n╞═══ [t]{n}[n] ═══╡\n"));
+
+				// Name, base value, other strings and prefixes
+				let mut rows: Vec<(String, String, String)> = Vec::new();
+				for w in v {
+					let f = FreeUnit::from_whole(w);
+					let name = w.to_string();
+
+					let mut other: Vec<String> = Vec::new();
+					let mut prefixes: Vec<Prefix> = Vec::new();
+					for (s, p) in w.strings() {
+						if s != name && !other.contains(&s) { other.push(s); }
+						for x in p { if !prefixes.contains(&x) { prefixes.push(x); } }
+					}
+
+					let mut other = other.join(", ");
+					let p = describe_prefixes(&prefixes);
+					if !p.is_empty() { other = format!("{other} ({p})").trim_start().to_string(); }
+
+					let value = match w.log_reference() {
+						Some(r) => format!("0 {name} = {}", r.display_outer(context)),
+						None if f.is_base() => "base unit".to_string(),
+						None => f.to_base().display(context)
+					};
+
+					rows.push((name, value, other));
+				}
+
+				let a = rows.iter().map(|r| r.0.chars().count()).max().unwrap();
+				let b = rows.iter().map(|r| r.1.chars().count()).max().unwrap();
+				for (name, value, other) in rows {
+					let r = format!(
+						"  [c]{name}[n]{} {value}{}  {other}",
+						" ".repeat(a - name.chars().count()),
+						" ".repeat(b - value.chars().count())
+					);
+					t.push(&format!("{}\n", r.trim_end()));
+				}
+			}
+
+			t.push("\n\n");
+			return t;
+		},
+
+		"unitset" => {
+			if args.len() == 1 {
+				let mut t = FormattedText::new(
+					"\n╞═══ [t]Unit Sets[n] ═══╡\n".to_string()
+				);
+
+				let user = (0..context.get_units().len()).map(WholeUnit::User);
+				let units: Vec<WholeUnit> = WholeUnit::all_units().iter().copied().chain(user).collect();
+
+				for x in UnitSet::all() {
+					let n = x.to_string();
+					let v: Vec<String> = units.iter()
+						.filter(|w| w.unit_set() == Some(*x))
+						.map(|w| w.to_string())
+						.collect();
+
+					t.push(&format!(
+						"  {n}{}  {}  [c]{}[n]\n",
+						" ".repeat(9 - n.len()),
+						if context.config.disabled_unit_sets.contains(x) { "off" } else { "on " },
+						v.join(", ")
+					));
+				}
+
+				t.push("\n\n");
+				return t;
+			}
+
+			let set = UnitSet::from_string(args[1]);
+			let (Some(set), 3, Some(&("on" | "off"))) = (set, args.len(), args.get(2)) else {
+				let v: Vec<String> = UnitSet::all().iter().map(|x| x.to_string()).collect();
+				return FormattedText::new(
+					format!(
+						"[e]Usage: [c]unitset <set> on|off[e], where set is one of [c]{}[e].[n]\n\n",
+						v.join("[e], [c]")
+					)
+				);
+			};
+
+			// Units take priority over variables with the same name.
+			// This uses the unit sets that are enabled when it's called.
+			let hidden = |context: &Context| -> Vec<String> {
+				let mut v: Vec<String> = context.get_variables().keys()
+					.filter(|k| freeunit_from_string(k).is_some())
+					.cloned()
+					.collect();
+				v.sort();
+				return v;
+			};
+			let before = hidden(context);
+
+			context.config.disabled_unit_sets.retain(|x| *x != set);
+			if args[2] == "off" { context.config.disabled_unit_sets.push(set); }
+			context.config.check();
+
+			let mut t = FormattedText::new(format!(
+				"Unit set [c]{}[n] is {}.\n",
+				set.to_string(), args[2]
+			));
+
+			let v: Vec<String> = hidden(context).into_iter()
+				.filter(|x| !before.contains(x))
+				.collect();
+			if !v.is_empty() {
+				t.push(&format!(
+					"[e]These variables are now hidden by units:[n] [c]{}[n]\n",
+					v.join("[n], [c]")
+				));
+			}
+
+			t.push("\n");
+			return t;
+		},
+
 		"del" | "delete" => {
 			if args.len() != 2 {
 				return FormattedText::new(
@@ -1,6 +1,33 @@
 use crate::parser::{Expression, Function, Constant};
+use crate::parser::UserConstant;
+use crate::parser::set_user_constants;
 use crate::quantity::freeunit_from_string;
+use crate::quantity::UserUnit;
+use crate::quantity::set_user_units;
+use crate::quantity::UnitSet;
+use crate::quantity::set_disabled_unit_sets;
+use crate::quantity::DisplayFormat;
+use crate::quantity::set_float_precision;
+use crate::quantity::set_number_format;
+use crate::quantity::Notation;
+use crate::rng::Rng;
 use std::collections::HashMap;
+use std::cell::Cell;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+
+// Some state (float precision, number format, user units and constants,
+// and unit sets) is read deep inside arithmetic and unit code, so it is
+// copied into thread-locals. The Context owns this state, and we copy it
+// whenever we start working with a different Context. See Context::activate().
+static NEXT_CONTEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local!(
+	// The id of the Context whose state is in the thread-locals.
+	// None if they may not match any Context.
+	static ACTIVE_CONTEXT: Cell<Option<usize>> = const { Cell::new(None) }
+);
 
 #[derive(Debug)]
 #[derive(Clone)]
@@ -32,6 +59,45 @@ pub struct Config {
 	//
 	// Automatically disabled if enable_super_powers is off.
 	pub enable_one_over_power: bool,
+
+	// Should we print complex numbers
+	// in polar form?
+	pub enable_polar: bool,
+
+	// Precision of floats, in bits.
+	// None means we use f64 floats.
+	pub float_precision: Option<usize>,
+
+	// How we print numbers: the base, or as fractions.
+	// This can be overridden with `to`, like `255 to hex`.
+	pub output_format: DisplayFormat,
+
+	// Significant digits to show.
+	// None means we pick based on float precision.
+	pub sig_digits: Option<usize>,
+
+	// How we write decimal numbers
+	pub notation: Notation,
+
+	// Should we pick SI prefixes for results,
+	// like `4.7 mF` instead of `0.0047 F`?
+	pub enable_si_prefix: bool,
+
+	// Character between groups of digits, like `1,000,000`.
+	// None means we don't group digits.
+	pub digit_separator: Option<char>,
+
+	// The largest integer we'll take the
+	// exact factorial of.
+	pub max_factorial: usize,
+
+	// Relative error we accept in
+	// integrate(), derive(), and solve()
+	pub tolerance: f64,
+
+	// Unit sets we won't parse, so that
+	// their names can be used for variables.
+	pub disabled_unit_sets: Vec<UnitSet>,
 }
 
 impl Config {
@@ -41,10 +107,35 @@ impl Config {
 			enable_substituion: true,
 			//enable_unicode: true,
 			enable_super_powers: true,
-			enable_one_over_power: true
+			enable_one_over_power: true,
+			enable_polar: false,
+			float_precision: None,
+			output_format: DisplayFormat::Radix(10),
+			sig_digits: None,
+			notation: Notation::Auto,
+			enable_si_prefix: false,
+			digit_separator: None,
+			max_factorial: 50_000,
+			tolerance: 1e-10,
+			disabled_unit_sets: Vec::new()
 		}
 	}
 
+	// A copy of this config with default output settings.
+	// Used to print input, since settings like fraction mode
+	// and notation should only change how results look.
+	pub fn for_input(&self) -> Config {
+		let d = Config::new();
+		let mut c = self.clone();
+		c.output_format = d.output_format;
+		c.sig_digits = d.sig_digits;
+		c.notation = d.notation;
+		c.enable_si_prefix = d.enable_si_prefix;
+		c.digit_separator = d.digit_separator;
+		c.enable_polar = d.enable_polar;
+		return c;
+	}
+
 	pub fn check(&mut self) {
 		//if !self.enable_unicode {
 		//	self.enable_substituion = false;
@@ -54,6 +145,14 @@ impl Config {
 		if !self.enable_super_powers {
 			self.enable_one_over_power = false
 		}
+
+		set_float_precision(self.float_precision);
+		set_number_format(self.sig_digits, self.notation, self.digit_separator);
+		set_disabled_unit_sets(&self.disabled_unit_sets);
+
+		// We don't know which Context this config belongs to,
+		// so the next Context we use must copy all its state.
+		ACTIVE_CONTEXT.with(|x| x.set(None));
 	}
 }
 
@@ -69,24 +168,103 @@ pub struct Context {
 	variables: HashMap<String, Expression>,
 	functions: HashMap<String, (Vec<String>, Expression)>,
 
+	// Units defined with `unit`.
+	// A unit's index here never changes, see `WholeUnit::User`.
+	units: Vec<UserUnit>,
+
+	// Constants loaded from definition files.
+	// As with units, indices here never change.
+	constants: Vec<UserConstant>,
+
 	// Shadow variables, for function evaluation.
-	shadow: HashMap<String, Option<Expression>>
+	shadow: HashMap<String, Option<Expression>>,
+
+	// Random numbers for `rand` and dice.
+	rng: Rng,
+
+	// How many user functions we're inside of,
+	// and the stack position of the outermost one.
+	call_depth: usize,
+	call_stack_base: usize,
+
+	// Unique id, see activate()
+	id: usize
+}
+
+// Names of constants that variables and functions may replace.
+// `j` is the imaginary unit unless the user defines it.
+const SHADOWABLE_CONSTANTS: &[&str] = &["j"];
+
+// The address of a local variable, which tells us
+// roughly where we are on the stack.
+#[inline(never)]
+fn stack_position() -> usize {
+	let x = 0u8;
+	return std::hint::black_box(&x) as *const u8 as usize;
 }
 
 // General functions
 impl Context {
 	pub fn new() -> Context {
+		let c = Context::new_inactive();
+		c.activate();
+		return c;
+	}
+
+	// Make a new context without touching the thread-locals.
+	// Used to parse built-in constants, which should use the
+	// float precision of the context we're evaluating in.
+	pub(crate) fn new_inactive() -> Context {
 		Context {
 			config: Config::new(),
 			history: Vec::new(),
 			variables: HashMap::new(),
 			functions: HashMap::new(),
-			shadow: HashMap::new()
+			units: Vec::new(),
+			constants: Vec::new(),
+			shadow: HashMap::new(),
+			rng: Rng::from_time(),
+			call_depth: 0,
+			call_stack_base: 0,
+			id: NEXT_CONTEXT_ID.fetch_add(1, Ordering::Relaxed)
 		}
 	}
 
+	// Copy this context's state into the thread-locals used by
+	// arithmetic and unit code, if it isn't already there.
+	// This must be called before we parse or evaluate anything.
+	pub fn activate(&self) {
+		if ACTIVE_CONTEXT.with(|x| x.get()) == Some(self.id) { return; }
+
+		let mut config = self.config.clone();
+		config.check();
+		set_user_units(&self.units);
+		set_user_constants(&self.constants);
+
+		ACTIVE_CONTEXT.with(|x| x.set(Some(self.id)));
+	}
+
+	pub fn rng(&mut self) -> &mut Rng { &mut self.rng }
+	pub fn seed(&mut self, s: u64) { self.rng = Rng::new(s); }
+
 	pub fn push_hist(&mut self, t: Expression) { self.history.push(t); }
 
+	pub fn call_depth(&self) -> usize { self.call_depth }
+	pub fn exit_call(&mut self) { self.call_depth -= 1; }
+
+	pub fn enter_call(&mut self) {
+		if self.call_depth == 0 { self.call_stack_base = stack_position(); }
+		self.call_depth += 1;
+	}
+
+	// Roughly how much stack we've used since we entered the outermost user function.
+	// Evaluation is recursive, so deep calls can overflow the stack before
+	// they hit any reasonable depth limit.
+	pub fn call_stack_used(&self) -> usize {
+		if self.call_depth == 0 { return 0; }
+		return stack_position().abs_diff(self.call_stack_base);
+	}
+
 
 	pub fn delete(&mut self, s: &String) -> Result<(), ()> {
 		if !(self.is_varible(s) || self.is_function(s)) { return Err(()) };
@@ -131,8 +309,9 @@ impl Context {
 	pub fn valid_varible(&self, s: &str) -> bool {
 		if {
 			Function::from_string(s).is_some() ||
-			Constant::from_string(s).is_some() ||
-			freeunit_from_string(s).is_some()
+			(Constant::from_string(s).is_some() && !SHADOWABLE_CONSTANTS.contains(&s)) ||
+			freeunit_from_string(s).is_some() ||
+			DisplayFormat::from_string(s).is_some()
 		} { return false }
 
 		for c in s.to_lowercase().chars() {
@@ -143,6 +322,7 @@ impl Context {
 
 		return match s {
 			"ans" => false,
+			"true" | "false" => false,
 			_ => true
 		}
 	}
@@ -174,6 +354,18 @@ impl Context {
 		self.shadow = HashMap::new();
 	}
 
+	// Used to save and restore a single shadow variable.
+	pub fn get_shadow(&self, s: &str) -> Option<Option<Expression>> {
+		return self.shadow.get(s).cloned();
+	}
+
+	pub fn restore_shadow(&mut self, s: &str, old: Option<Option<Expression>>) {
+		match old {
+			Some(v) => { self.shadow.insert(s.to_string(), v); },
+			None => { self.shadow.remove(s); }
+		}
+	}
+
 }
 
 
@@ -201,4 +393,57 @@ impl Context {
 	pub fn get_functions(&self) -> &HashMap<String, (Vec<String>, Expression)> {
 		return &self.functions
 	}
-}
\ No newline at end of file
+}
+
+
+// Unit manipulation
+impl Context {
+	// Can we define a unit with this name?
+	// Units we defined can be redefined.
+	pub fn valid_unit(&self, s: &str) -> bool {
+		return self.units.iter().any(|u| u.name == s) || self.valid_varible(s);
+	}
+
+	pub fn push_unit(&mut self, u: UserUnit) -> Result<(), ()> {
+		if !self.valid_unit(&u.name) { return Err(()); }
+
+		self.variables.remove(&u.name);
+		self.functions.remove(&u.name);
+
+		match self.units.iter().position(|x| x.name == u.name) {
+			Some(i) => { self.units[i] = u; },
+			None => { self.units.push(u); }
+		}
+
+		self.activate();
+		set_user_units(&self.units);
+		return Ok(());
+	}
+
+	pub fn get_units(&self) -> &Vec<UserUnit> {
+		return &self.units
+	}
+}
+
+
+// Constant manipulation
+impl Context {
+	// Every string of `c` must be a valid variable name.
+	pub fn push_constant(&mut self, c: UserConstant) -> Result<(), ()> {
+		if !c.strings.iter().all(|s| self.valid_varible(s)) { return Err(()); }
+
+		for s in &c.strings {
+			self.variables.remove(s);
+			self.functions.remove(s);
+		}
+
+		self.constants.push(c);
+		self.activate();
+		set_user_constants(&self.constants);
+		return Ok(());
+	}
+
+	pub fn get_constants(&self) -> &Vec<UserConstant> {
+		return &self.constants
+	}
+}
@@ -0,0 +1,308 @@
+use toml::Table;
+use toml::Value;
+
+use crate::Context;
+use crate::parser;
+use crate::parser::UserConstant;
+use crate::evaluate::evaluate;
+use crate::quantity::Quantity;
+use crate::quantity::Scalar;
+use crate::quantity::FreeUnit;
+use crate::quantity::WholeUnit;
+use crate::quantity::Prefix;
+use crate::quantity::UserUnit;
+use crate::quantity::UnitSet;
+
+
+// Load unit and constant definitions from a toml string.
+// These use the same format as `buildscript/units.toml`
+// and `buildscript/constants.toml`, and one file may contain both.
+//
+// Every unit is loaded before any constant, wherever they appear
+// in the file. Constants may use units from the same file, but units
+// can't use constants from it. Within each table, definitions are
+// added in order. If we return an error, every definition loaded
+// before the bad one is kept.
+pub fn load_definitions(context: &mut Context, s: &str) -> Result<(), String> {
+	let t = match s.parse::<Table>() {
+		Ok(t) => t,
+		Err(e) => return Err(e.to_string().trim_end().to_string())
+	};
+
+	for k in t.keys() {
+		if k != "unit" && k != "constant" {
+			return Err(format!("unknown table `{k}`, expected `unit` or `constant`"));
+		}
+	}
+
+	if let Some(units) = t.get("unit") {
+		let Value::Array(units) = units else {
+			return Err("`unit` must be an array of tables, like `[[unit]]`".to_string());
+		};
+
+		for (i, u) in units.iter().enumerate() {
+			let (name, u) = entry("unit", i, u, &[
+				"enum_name", "base", "no_space", "set", "strings",
+				"base_value_type", "base_value", "base_units"
+			])?;
+
+			let u = load_unit(context, u).map_err(|e| format!("unit `{name}`: {e}"))?;
+			context.push_unit(u).unwrap();
+		}
+	}
+
+	if let Some(constants) = t.get("constant") {
+		let Value::Array(constants) = constants else {
+			return Err("`constant` must be an array of tables, like `[[constant]]`".to_string());
+		};
+
+		for (i, c) in constants.iter().enumerate() {
+			let (name, c) = entry("constant", i, c, &[
+				"enum_name", "strings", "value", "pretty_name"
+			])?;
+
+			let c = load_constant(context, c).map_err(|e| format!("constant `{name}`: {e}"))?;
+			context.push_constant(c).unwrap();
+		}
+	}
+
+	return Ok(());
+}
+
+
+// Check the shape of the ith entry of a `[[unit]]` or `[[constant]]` array.
+// Returns its enum_name and its table.
+fn entry<'a>(
+	kind: &str, i: usize, v: &'a Value, keys: &[&str]
+) -> Result<(&'a str, &'a Table), String> {
+	let Value::Table(t) = v else {
+		return Err(format!("{kind} {} must be a table", i + 1));
+	};
+
+	let name = match t.get("enum_name") {
+		Some(Value::String(s)) => s,
+		Some(_) => return Err(format!("{kind} {}: `enum_name` must be a string", i + 1)),
+		None => return Err(format!("{kind} {} has no `enum_name`", i + 1))
+	};
+
+	for k in t.keys() {
+		if !keys.contains(&&k[..]) {
+			return Err(format!("{kind} `{name}`: unknown key `{k}`"));
+		}
+	}
+
+	return Ok((name, t));
+}
+
+// Make sure `s` can be used as a new unit or constant,
+// and that we haven't used it already in this definition.
+fn check_name(context: &Context, seen: &mut Vec<String>, s: &str) -> Result<(), String> {
+	if s.is_empty() || !s.chars().all(|c| c.is_ascii_alphabetic() || c == '_') {
+		return Err(format!("`{s}` is not a valid name, names may only contain letters and `_`"));
+	}
+
+	if seen.iter().any(|x| x == s) {
+		return Err(format!("`{s}` is listed twice"));
+	}
+
+	if !context.valid_varible(s) {
+		return Err(format!("`{s}` is already defined"));
+	}
+
+	seen.push(s.to_string());
+	return Ok(());
+}
+
+fn get_bool(t: &Table, key: &str) -> Result<bool, String> {
+	return match t.get(key) {
+		None => Ok(false),
+		Some(Value::Boolean(b)) => Ok(*b),
+		Some(_) => Err(format!("`{key}` must be true or false"))
+	};
+}
+
+fn get_integers(v: &Value, n: usize) -> Option<Vec<i64>> {
+	let Value::Array(a) = v else { return None };
+	if a.len() != n { return None; }
+	return a.iter().map(|x| x.as_integer()).collect();
+}
+
+
+fn load_unit(context: &Context, t: &Table) -> Result<UserUnit, String> {
+	if get_bool(t, "base")? {
+		return Err("new base units can't be defined, use `base_units` instead".to_string());
+	}
+	let no_space = get_bool(t, "no_space")?;
+
+	let set = match t.get("set") {
+		None => None,
+		Some(Value::String(s)) => match UnitSet::from_string(s) {
+			Some(x) => Some(x),
+			None => {
+				let v: Vec<String> = UnitSet::all().iter().map(|x| format!("`{}`", x.to_string())).collect();
+				return Err(format!("`{s}` is not a unit set, expected one of {}", v.join(", ")));
+			}
+		},
+		Some(_) => return Err("`set` must be a string".to_string())
+	};
+
+
+	// Strings
+	let Some(Value::Array(a)) = t.get("strings") else {
+		return Err("`strings` must be an array of arrays, like `[[\"s\", \"\", \"k\"], [\"sec\"]]`".to_string());
+	};
+	if a.is_empty() { return Err("`strings` is empty".to_string()); }
+
+	let mut seen = Vec::new();
+	let mut strings = Vec::new();
+	for x in a {
+		let x: Option<Vec<&str>> = match x {
+			Value::Array(x) => x.iter().map(|y| y.as_str()).collect(),
+			_ => None
+		};
+		let Some(x) = x.filter(|x| !x.is_empty()) else {
+			return Err("each entry in `strings` must be a nonempty array of strings".to_string());
+		};
+
+		let s = x[0];
+		let prefixes = if x.len() == 1 {
+			vec![Prefix::None]
+		} else {
+			let mut v = Vec::new();
+			for p in &x[1..] {
+				let Some(p) = Prefix::from_string(p) else {
+					return Err(format!("`{p}` is not a prefix (in strings for `{s}`)"));
+				};
+				v.push(p);
+			}
+			v
+		};
+
+		for p in &prefixes {
+			check_name(context, &mut seen, &format!("{}{s}", p.to_string()))?;
+		}
+
+		// We print this string even if it can't be used without a prefix
+		if !context.valid_varible(s) {
+			return Err(format!("`{s}` is already defined"));
+		}
+
+		strings.push((s.to_string(), prefixes));
+	}
+
+
+	// Value
+	let scalar = match (t.get("base_value_type"), t.get("base_value")) {
+		(None, _) => return Err("missing `base_value_type`".to_string()),
+		(_, None) => return Err("missing `base_value`".to_string()),
+
+		(Some(Value::String(k)), Some(v)) if k == "exact" || k == "approx" => {
+			let Value::String(v) = v else {
+				return Err(format!("`base_value` must be a string for `{k}` units"));
+			};
+
+			let s = if k == "exact" {
+				Scalar::new_rational_from_string(v)
+			} else {
+				Scalar::new_float_from_string(v)
+			};
+
+			let Some(s) = s else {
+				return Err(format!("`{v}` is not a number"));
+			};
+			s
+		},
+
+		(Some(Value::String(k)), Some(v)) if k == "fract" => {
+			let Some(v) = get_integers(v, 2) else {
+				return Err("`base_value` must be an array of two integers for `fract` units".to_string());
+			};
+			if v[1] == 0 { return Err("`base_value` has a zero denominator".to_string()); }
+			Scalar::new_rational_from_frac(v[0], v[1]).unwrap()
+		},
+
+		(Some(Value::String(k)), Some(v)) if k == "pi_fract" => {
+			let Some(v) = get_integers(v, 3) else {
+				return Err("`base_value` must be an array of three integers for `pi_fract` units".to_string());
+			};
+			if v[1] == 0 { return Err("`base_value` has a zero denominator".to_string()); }
+			Scalar::new_pi_from_frac(v[0], v[1], v[2]).unwrap()
+		},
+
+		_ => return Err("`base_value_type` must be one of `exact`, `approx`, `fract`, or `pi_fract`".to_string())
+	};
+
+	let mut value = Quantity::from_scalar(scalar);
+	if !value.is_positive() {
+		return Err("`base_value` must be positive".to_string());
+	}
+
+
+	// Base units. Unlike units.toml, these may be omitted
+	// if this unit has no dimension.
+	let base_units = match t.get("base_units") {
+		None => &[][..],
+		Some(Value::Array(a)) => &a[..],
+		Some(_) => return Err("`base_units` must be an array of tables, like `[{u = \"Meter\", p = 1}]`".to_string())
+	};
+
+	for b in base_units {
+		let (u, p) = match b {
+			Value::Table(b) => (b.get("u").and_then(|x| x.as_str()), b.get("p").and_then(|x| x.as_integer())),
+			_ => (None, None)
+		};
+		let (Some(u), Some(p)) = (u, p) else {
+			return Err("each entry in `base_units` must look like `{u = \"Meter\", p = 1}`".to_string());
+		};
+
+		let Some(w) = WholeUnit::base_from_enum_name(u) else {
+			return Err(format!("`{u}` is not a base unit"));
+		};
+		value.insert_unit(FreeUnit::from_whole(w), Scalar::new_rational(p as f64).unwrap());
+	}
+
+	let mut u = UserUnit::from_strings(strings, value, no_space);
+	u.set = set;
+	return Ok(u);
+}
+
+
+fn load_constant(context: &mut Context, t: &Table) -> Result<UserConstant, String> {
+	let strings: Option<Vec<&str>> = match t.get("strings") {
+		Some(Value::String(s)) => Some(vec![s]),
+		Some(Value::Array(a)) if !a.is_empty() => a.iter().map(|x| x.as_str()).collect(),
+		_ => None
+	};
+	let Some(strings) = strings else {
+		return Err("`strings` must be a string or a nonempty array of strings".to_string());
+	};
+
+	let mut seen = Vec::new();
+	for s in &strings { check_name(context, &mut seen, s)?; }
+
+	let pretty_name = match t.get("pretty_name") {
+		None => None,
+		Some(Value::String(s)) => Some(s.clone()),
+		Some(_) => return Err("`pretty_name` must be a string".to_string())
+	};
+
+	let Some(Value::String(v)) = t.get("value") else {
+		return Err("`value` must be a string".to_string());
+	};
+
+	let g = match parser::parse(context, v) {
+		Ok(g) => g,
+		Err((_, e)) => return Err(format!("bad value `{v}`: {}", e.text().to_string()))
+	};
+
+	let value = match evaluate(context, &g) {
+		Ok(x) => x,
+		Err((_, e)) => return Err(format!("bad value `{v}`: {}", e.text().to_string()))
+	};
+
+	return Ok(UserConstant {
+		strings: strings.iter().map(|s| s.to_string()).collect(),
+		pretty_name,
+		value
+	});
+}
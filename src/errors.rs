@@ -1,4 +1,5 @@
 use crate::formattedtext::FormattedText;
+use crate::parser::Arity;
 
 #[derive(Debug)]
 pub enum DaisyError {
@@ -12,6 +13,7 @@ pub enum DaisyError {
 	BadVariable,
 	BadFunction,
 	BadTuple,
+	BadUnit,
 
 	// Evaluation errors
 	BadMath,
@@ -21,7 +23,19 @@ pub enum DaisyError {
 	IncompatibleUnits(String, String),
 	Undefined(String),
 	EvaluationError,
-	BadArguments(String, usize, usize)
+	BadArguments(String, Arity, usize),
+	BadFormat(String),
+	IntegerOnly(String),
+	ExpectedBool,
+	UnexpectedBool,
+	NotAFunction(String),
+	NoConvergence,
+	NoSignChange,
+	TooDeep,
+	BadDimensions,
+	BadTupleOperation,
+	ConstantPolynomial,
+	BadUnitValue
 }
 
 impl DaisyError {
@@ -60,6 +74,11 @@ impl DaisyError {
 					"[e]Syntax Error:[n] Bad function name".to_string()
 				);
 			},
+			DaisyError::BadUnit => {
+				return FormattedText::new(
+					"[e]Syntax Error:[n] Bad unit name".to_string()
+				);
+			},
 			DaisyError::BadTuple => {
 				return FormattedText::new(
 					"[e]Syntax Error:[n] Bad tuple syntax".to_string()
@@ -104,9 +123,70 @@ impl DaisyError {
 			},
 			DaisyError::BadArguments(s, want, got) => {
 				return FormattedText::new(format!(
-					"[e]Evaluation Error:[n] [c]{s}[n] takes {want} argument{}, but it got {got}",
-					if *want == 1 {""} else {"s"},
+					"[e]Evaluation Error:[n] [c]{s}[n] takes {} argument{}, but it got {got}",
+					want.to_string(),
+					if want.max() == Some(1) {""} else {"s"},
+				));
+			},
+			DaisyError::BadFormat(s) => {
+				return FormattedText::new(format!(
+					"[e]Evaluation Error:[n] [c]{s}[n] can only be used after [c]to[n]"
+				));
+			},
+			DaisyError::IntegerOnly(s) => {
+				return FormattedText::new(format!(
+					"[e]Evaluation Error:[n] [c]{s}[n] only works on unitless integers"
+				));
+			},
+			DaisyError::ExpectedBool => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] Expected [c]true[n] or [c]false[n]".to_string()
+				);
+			},
+			DaisyError::UnexpectedBool => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] Booleans can only be used with logical operators".to_string()
+				);
+			},
+			DaisyError::NotAFunction(s) => {
+				return FormattedText::new(format!(
+					"[e]Evaluation Error:[n] [c]{s}[n] is not a function"
 				));
+			},
+			DaisyError::NoConvergence => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] Did not converge".to_string()
+				);
+			},
+			DaisyError::NoSignChange => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] Function has the same sign at both bounds".to_string()
+				);
+			},
+			DaisyError::TooDeep => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] Too many nested function calls".to_string()
+				);
+			},
+			DaisyError::BadDimensions => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] Incompatible dimensions".to_string()
+				);
+			},
+			DaisyError::BadTupleOperation => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] This operator can't be used on tuples".to_string()
+				);
+			},
+			DaisyError::ConstantPolynomial => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] Constant polynomials have no roots".to_string()
+				);
+			},
+			DaisyError::BadUnitValue => {
+				return FormattedText::new(
+					"[e]Evaluation Error:[n] Units must be positive real numbers".to_string()
+				);
 			}
 		}
 	}
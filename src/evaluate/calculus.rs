@@ -0,0 +1,637 @@
+use crate::parser::Expression;
+use crate::parser::Operator;
+use crate::parser::Arity;
+use crate::parser::LineLocation;
+use crate::quantity::Quantity;
+use crate::quantity::Scalar;
+use crate::quantity::Unit;
+use crate::quantity::FreeUnit;
+use crate::quantity::WholeUnit;
+use crate::errors::DaisyError;
+use crate::context::Context;
+use super::evaluate;
+use super::function::unpack_args;
+use super::function::check_arity;
+
+// Numerical integration, differentiation, and root finding.
+// All of these work with real, unitless scalars.
+// Units are removed before we start and put back at the end.
+
+
+// The most times we'll evaluate a function in one integral or derivative
+const MAX_EVALS: usize = 100_000;
+
+// The most times we'll split an interval in adaptive quadrature
+const MAX_DEPTH: usize = 40;
+
+// The most steps we'll take when looking for a root
+const MAX_STEPS: usize = 200;
+
+// We start integrating with this many equal intervals.
+// Starting with one interval can miss functions that
+// happen to be zero at its ends and midpoint (like sin from 0 to 2π)
+const START_INTERVALS: usize = 8;
+
+
+fn float(f: f64) -> Scalar { Scalar::new_float(f).unwrap() }
+
+fn max(a: Scalar, b: Scalar) -> Scalar { if a > b { a } else { b } }
+
+// Our answers are only good to `tol`, so we round them.
+// This turns 5.99999999998 into 6.
+pub(super) fn round_to(x: Scalar, tol: &Scalar) -> Scalar {
+	// Dividing by an exact power of ten keeps 0.125 from becoming 0.12499...
+	let n = Scalar::new_rational(10f64).unwrap().pow(-tol.log10().floor());
+	let r = (x * n.clone()).round() / n;
+
+	// Don't return -0
+	if r.is_zero() { return float(0f64); }
+	return r;
+}
+
+
+// A function of one variable. This is either a user function,
+// like `f` in `integrate(f, 0, 1)`, or an expression with a bound
+// variable, like `x^2` in `integrate(x, 0, 1, x^2)`.
+struct UnaryFunction {
+	var: String,
+	body: Expression,
+
+	// The location of the whole call, used for most errors.
+	loc: LineLocation,
+
+	// A user function's body isn't part of the prompt,
+	// so errors inside it point to the function's name.
+	user_loc: Option<LineLocation>,
+
+	// The units of x and f(x).
+	// These are quantities with a value of one.
+	x_unit: Quantity,
+	f_unit: Option<Quantity>,
+
+	evals: usize
+}
+
+impl UnaryFunction {
+	// If `body` is None, `f` should be the name of a user function.
+	// Otherwise, `f` is the variable `body` depends on.
+	fn new(
+		context: &Context,
+		f: &Expression,
+		body: Option<&Expression>,
+		loc: LineLocation
+	) -> Result<UnaryFunction, (LineLocation, DaisyError)> {
+		let Expression::Variable(l, s) = f else {
+			return Err((f.get_linelocation(), match body {
+				None => DaisyError::NotAFunction(f.display(context)),
+				Some(_) => DaisyError::BadVariable
+			}));
+		};
+
+		let mut u = UnaryFunction {
+			var: s.clone(),
+			body: f.clone(),
+			loc,
+			user_loc: None,
+			x_unit: Quantity::new_rational(1f64).unwrap(),
+			f_unit: None,
+			evals: 0
+		};
+
+		if let Some(body) = body {
+			if !context.valid_varible(s) { return Err((*l, DaisyError::BadVariable)); }
+			u.body = body.clone();
+			return Ok(u);
+		}
+
+		if !context.is_function(s) {
+			return Err((*l, DaisyError::NotAFunction(s.clone())));
+		}
+
+		let (vars, body) = context.get_function(s).unwrap();
+		if vars.len() != 1 {
+			return Err((*l, DaisyError::BadArguments(s.clone(), Arity::Fixed(vars.len()), 1)));
+		}
+
+		u.var = vars[0].clone();
+		u.body = body;
+		u.user_loc = Some(*l);
+		return Ok(u);
+	}
+
+	// Evaluate f(x). x and the result are unitless.
+	// Returns None if f can't be evaluated yet.
+	fn try_call(&mut self, context: &mut Context, x: &Scalar) -> Result<Option<Scalar>, (LineLocation, DaisyError)> {
+		self.evals += 1;
+		if self.evals > MAX_EVALS { return Err((self.loc, DaisyError::NoConvergence)); }
+
+		let x = Quantity::from_scalar(x.clone()) * self.x_unit.clone();
+		context.add_shadow(self.var.clone(), Some(Expression::Quantity(self.loc, x)));
+
+		let r = match (evaluate(context, &self.body), self.user_loc) {
+			(Err((_, e)), Some(l)) => { return Err((l, e)); },
+			(r, _) => r?
+		};
+
+		let q = match r {
+			Expression::Quantity(_, q) => q,
+			Expression::Bool(l, _) => { return Err((self.user_loc.unwrap_or(l), DaisyError::UnexpectedBool)); },
+			_ => { return Ok(None); }
+		};
+
+		if q.is_complex() || q.is_nan() { return Err((self.loc, DaisyError::BadMath)); }
+
+		// The first value we see decides the units of f
+		if self.f_unit.is_none() {
+			let mut u = Quantity::new_rational(1f64).unwrap();
+			u.set_unit(q.unit.clone());
+			self.f_unit = Some(u);
+		}
+
+		let Some(q) = q.convert_to(self.f_unit.clone().unwrap()) else {
+			return Err((self.loc, DaisyError::IncompatibleUnit));
+		};
+
+		return Ok(Some(q.scalar));
+	}
+
+	fn call(&mut self, context: &mut Context, x: &Scalar) -> Result<Scalar, (LineLocation, DaisyError)> {
+		return match self.try_call(context, x)? {
+			Some(r) => Ok(r),
+			None => Err((self.loc, DaisyError::EvaluationError))
+		};
+	}
+}
+
+
+// Evaluate the points we integrate or differentiate at.
+// Returns None if they can't be evaluated yet.
+fn eval_points(
+	context: &mut Context,
+	args: &[&Expression]
+) -> Result<Option<Vec<(LineLocation, Quantity)>>, (LineLocation, DaisyError)> {
+	let mut v = Vec::with_capacity(args.len());
+	for a in args {
+		match evaluate(context, a)? {
+			Expression::Quantity(l, q) => {
+				if q.is_complex() || q.is_nan() { return Err((l, DaisyError::BadMath)); }
+				v.push((l, q));
+			},
+			Expression::Bool(l, _) => { return Err((l, DaisyError::UnexpectedBool)); },
+			_ => { return Ok(None); }
+		}
+	}
+	return Ok(Some(v));
+}
+
+// The location of a whole function call
+fn call_location(g: &Expression) -> LineLocation {
+	let Expression::Operator(loc, _, args) = g else { unreachable!() };
+	let mut l = *loc;
+	for a in unpack_args(args) { l += a.get_linelocation(); }
+	return l;
+}
+
+
+// The integrand of an adaptive rule.
+// This is usually a UnaryFunction, but the ends of an integral are transformed first.
+type Integrand<'a> = dyn FnMut(&mut Context, &Scalar) -> Result<Scalar, (LineLocation, DaisyError)> + 'a;
+
+// Simpson's rule on [a, b], given f at a, b, and their midpoint
+fn simpson(a: &Scalar, b: &Scalar, fa: &Scalar, fm: &Scalar, fb: &Scalar) -> Scalar {
+	return (b.clone() - a.clone()) / float(6f64) * (fa.clone() + float(4f64) * fm.clone() + fb.clone());
+}
+
+// Milne's rule on [a, b], given f at a + h, a + 2h, and a + 3h with h = (b - a) / 4.
+// This is an open rule, it never evaluates f at a or b.
+fn milne(a: &Scalar, b: &Scalar, f1: &Scalar, f2: &Scalar, f3: &Scalar) -> Scalar {
+	return (b.clone() - a.clone()) / float(3f64) * (float(2f64) * f1.clone() - f2.clone() + float(2f64) * f3.clone());
+}
+
+// Adaptive Simpson's rule.
+// `whole` is Simpson's rule on all of [a, b].
+fn adaptive_simpson(
+	context: &mut Context,
+	f: &mut Integrand,
+	loc: LineLocation,
+	(a, fa): (&Scalar, &Scalar),
+	(m, fm): (&Scalar, &Scalar),
+	(b, fb): (&Scalar, &Scalar),
+	whole: Scalar,
+	tol: Scalar,
+	depth: usize
+) -> Result<Scalar, (LineLocation, DaisyError)> {
+	let lm = (a.clone() + m.clone()) / float(2f64);
+	let rm = (m.clone() + b.clone()) / float(2f64);
+	let flm = f(context, &lm)?;
+	let frm = f(context, &rm)?;
+
+	let left = simpson(a, m, fa, &flm, fm);
+	let right = simpson(m, b, fm, &frm, fb);
+	let delta = left.clone() + right.clone() - whole;
+
+	if delta.abs() <= float(15f64) * tol.clone() {
+		return Ok(left + right + delta / float(15f64));
+	}
+
+	if depth >= MAX_DEPTH { return Err((loc, DaisyError::NoConvergence)); }
+
+	// We don't shrink our tolerance as we go deeper.
+	// Functions like sqrt(x) near zero would never converge if we did.
+	return Ok(
+		adaptive_simpson(context, f, loc, (a, fa), (&lm, &flm), (m, fm), left, tol.clone(), depth + 1)? +
+		adaptive_simpson(context, f, loc, (m, fm), (&rm, &frm), (b, fb), right, tol, depth + 1)?
+	);
+}
+
+// Like adaptive_simpson, but f is never evaluated at `a`.
+// The half of [a, b] next to `a` uses Milne's rule, the other half uses Simpson's.
+// `whole` is Milne's rule on all of [a, b], and `x1`, `x2`, and `x3` are its points.
+fn adaptive_open(
+	context: &mut Context,
+	f: &mut Integrand,
+	loc: LineLocation,
+	a: &Scalar,
+	(x1, f1): (&Scalar, &Scalar),
+	(x2, f2): (&Scalar, &Scalar),
+	(x3, f3): (&Scalar, &Scalar),
+	(b, fb): (&Scalar, &Scalar),
+	whole: Scalar,
+	tol: Scalar,
+	depth: usize
+) -> Result<Scalar, (LineLocation, DaisyError)> {
+	let lm = (a.clone() + x1.clone()) / float(2f64);
+	let rm = (x1.clone() + x2.clone()) / float(2f64);
+	let flm = f(context, &lm)?;
+	let frm = f(context, &rm)?;
+
+	let left = milne(a, x2, &flm, f1, &frm);
+	let right = simpson(x2, b, f2, f3, fb);
+
+	if (left.clone() + right.clone() - whole).abs() <= float(15f64) * tol.clone() {
+		return Ok(left + right);
+	}
+
+	if depth >= MAX_DEPTH { return Err((loc, DaisyError::NoConvergence)); }
+
+	return Ok(
+		adaptive_open(context, f, loc, a, (&lm, &flm), (x1, f1), (&rm, &frm), (x2, f2), left, tol.clone(), depth + 1)? +
+		adaptive_simpson(context, f, loc, (x2, f2), (x3, f3), (b, fb), right, tol, depth + 1)?
+	);
+}
+
+// Integrate f from `end` to `end + w` without evaluating f at `end`.
+// Integrals like 1/sqrt(x) from 0 are finite even though f(0) isn't,
+// so we substitute x = end + w·t² to smooth these out and use an open rule.
+fn integrate_end(
+	context: &mut Context,
+	f: &mut UnaryFunction,
+	end: &Scalar, w: &Scalar,
+	tol: Scalar
+) -> Result<Scalar, (LineLocation, DaisyError)> {
+	let loc = f.loc;
+	let mut g = |context: &mut Context, t: &Scalar| -> Result<Scalar, (LineLocation, DaisyError)> {
+		let x = end.clone() + w.clone() * t.clone() * t.clone();
+		return Ok(f.call(context, &x)? * float(2f64) * w.clone() * t.clone());
+	};
+
+	let zero = float(0f64);
+	let t: Vec<Scalar> = (1..=4).map(|i| float(i as f64 / 4f64)).collect();
+	let mut y = Vec::with_capacity(4);
+	for x in &t { y.push(g(context, x)?); }
+
+	let whole = milne(&zero, &t[3], &y[0], &y[1], &y[2]);
+	return adaptive_open(
+		context, &mut g, loc, &zero,
+		(&t[0], &y[0]), (&t[1], &y[1]), (&t[2], &y[2]), (&t[3], &y[3]),
+		whole, tol, 0
+	);
+}
+
+fn integrate(
+	context: &mut Context,
+	f: &mut UnaryFunction,
+	a: &Scalar, b: &Scalar
+) -> Result<Option<Scalar>, (LineLocation, DaisyError)> {
+	let n = START_INTERVALS * 2;
+	let point = |i: usize| a.clone() + (b.clone() - a.clone()) * float(i as f64) / float(n as f64);
+
+	// Make sure we can evaluate this function.
+	// f may not be defined at a or b, so we don't evaluate it there.
+	let Some(f1) = f.try_call(context, &point(1))? else { return Ok(None); };
+
+	// Sample f on equal intervals
+	let mut x = vec![a.clone(), point(1)];
+	let mut y = vec![float(0f64), f1];
+	for i in 2..n {
+		let t = point(i);
+		y.push(f.call(context, &t)?);
+		x.push(t);
+	}
+	x.push(b.clone());
+	y.push(float(0f64));
+
+	// Our tolerance is relative to the size of f,
+	// so that a very small integral doesn't need to be exact.
+	let mut scale = float(0f64);
+	for v in &y { scale = max(scale, v.abs()); }
+	let scale = scale * (b.clone() - a.clone()).abs();
+	if scale.is_zero() { return Ok(Some(float(0f64))); }
+	let tol = float(context.config.tolerance) * scale;
+	let tol_each = tol.clone() / float(START_INTERVALS as f64);
+
+	// The first and last intervals are integrated from a and b inwards.
+	// The last one runs backwards, so we subtract it.
+	let mut r = integrate_end(context, f, a, &(x[2].clone() - a.clone()), tol_each.clone())?;
+	r -= integrate_end(context, f, b, &(x[n-2].clone() - b.clone()), tol_each.clone())?;
+
+	let loc = f.loc;
+	let mut g = |context: &mut Context, x: &Scalar| f.call(context, x);
+	for i in 1..START_INTERVALS-1 {
+		let (j, k, l) = (2 * i, 2 * i + 1, 2 * i + 2);
+		let whole = simpson(&x[j], &x[l], &y[j], &y[k], &y[l]);
+		r += adaptive_simpson(
+			context, &mut g, loc,
+			(&x[j], &y[j]), (&x[k], &y[k]), (&x[l], &y[l]),
+			whole, tol_each.clone(), 0
+		)?;
+	}
+
+	return Ok(Some(round_to(r, &tol)));
+}
+
+// Units like W·s are replaced with the SI unit they
+// are equal to, like J. Other units are left alone.
+fn simplify_unit(q: Quantity) -> Quantity {
+	const SIMPLE: [WholeUnit; 12] = [
+		WholeUnit::Joule, WholeUnit::Newton, WholeUnit::Watt, WholeUnit::Pascal,
+		WholeUnit::Coulomb, WholeUnit::Volt, WholeUnit::Ohm, WholeUnit::Farad,
+		WholeUnit::Henry, WholeUnit::Weber, WholeUnit::Tesla, WholeUnit::Siemens
+	];
+
+	if q.unit.get_val().len() < 2 { return q; }
+
+	for w in SIMPLE {
+		let mut u = Quantity::new_rational(1f64).unwrap();
+		u.set_unit(Unit::from_free(FreeUnit::from_whole(w)));
+
+		// Only take exact matches, so that kW·h isn't turned into 3.6 MJ
+		if !q.unit.compatible_with(&u.unit) { continue; }
+		let Some(r) = q.convert_to(u) else { continue };
+		if r.scalar == q.scalar { return r; }
+	}
+	return q;
+}
+
+pub fn eval_integrate(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(fl, Operator::Function(func), args) = g else { unreachable!() };
+	let args = unpack_args(args);
+	check_arity(&func.to_string(), func.arity(), *fl, &args)?;
+	let loc = call_location(g);
+
+	let (mut f, points) = if args.len() == 3 {
+		(UnaryFunction::new(context, args[0], None, loc)?, &args[1..])
+	} else {
+		(UnaryFunction::new(context, args[0], Some(args[3]), loc)?, &args[1..3])
+	};
+	let Some(points) = eval_points(context, points)? else { return Ok(None); };
+	let (la, a) = &points[0];
+	let (lb, b) = &points[1];
+
+	// Both bounds are converted to the units of `a`
+	f.x_unit.set_unit(a.unit.clone());
+	let Some(b) = b.convert_to(f.x_unit.clone()) else {
+		return Err((*la + *lb, DaisyError::IncompatibleUnit));
+	};
+
+	let old = context.get_shadow(&f.var);
+	let r = integrate(context, &mut f, &a.scalar, &b.scalar);
+	context.restore_shadow(&f.var, old);
+	let Some(r) = r? else { return Ok(None); };
+
+	let r = Quantity::from_scalar(r) * f.f_unit.unwrap() * f.x_unit;
+	return Ok(Some(Expression::Quantity(loc, simplify_unit(r))));
+}
+
+
+// Ridders' method: central differences with shrinking steps,
+// extrapolated to a step of zero.
+fn derive(
+	context: &mut Context,
+	f: &mut UnaryFunction,
+	x: &Scalar
+) -> Result<Option<Scalar>, (LineLocation, DaisyError)> {
+	const STEPS: usize = 10;
+	let con = float(1.4);
+	let con2 = con.clone() * con.clone();
+
+	// Make sure we can evaluate this function
+	if f.try_call(context, x)?.is_none() { return Ok(None); };
+
+	let mut h = float(0.1) * (x.abs() + float(1f64));
+	let mut diff = |context: &mut Context, h: &Scalar| -> Result<Scalar, (LineLocation, DaisyError)> {
+		let a = f.call(context, &(x.clone() + h.clone()))?;
+		let b = f.call(context, &(x.clone() - h.clone()))?;
+		return Ok((a - b) / (float(2f64) * h.clone()));
+	};
+
+	// t[j][i] is the jth extrapolation of the ith step
+	let mut t: Vec<Vec<Scalar>> = vec![vec![float(0f64); STEPS]; STEPS];
+	t[0][0] = diff(context, &h)?;
+	let mut ans = t[0][0].clone();
+	let mut err: Option<Scalar> = None;
+
+	for i in 1..STEPS {
+		h = h / con.clone();
+		t[0][i] = diff(context, &h)?;
+
+		let mut fac = con2.clone();
+		for j in 1..=i {
+			t[j][i] = (t[j-1][i].clone() * fac.clone() - t[j-1][i-1].clone()) / (fac.clone() - float(1f64));
+			fac *= con2.clone();
+
+			let e = max(
+				(t[j][i].clone() - t[j-1][i].clone()).abs(),
+				(t[j][i].clone() - t[j-1][i-1].clone()).abs()
+			);
+			if err.is_none() || e <= *err.as_ref().unwrap() {
+				ans = t[j][i].clone();
+				err = Some(e);
+			}
+		}
+
+		// Stop if our error starts growing
+		let e = err.clone().unwrap();
+		if (t[i][i].clone() - t[i-1][i-1].clone()).abs() >= float(2f64) * e { break; }
+	}
+
+	let tol = float(context.config.tolerance) * max(ans.abs(), float(1f64));
+	if err.unwrap() > tol { return Err((f.loc, DaisyError::NoConvergence)); }
+
+	return Ok(Some(round_to(ans, &tol)));
+}
+
+pub fn eval_derive(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(fl, Operator::Function(func), args) = g else { unreachable!() };
+	let args = unpack_args(args);
+	check_arity(&func.to_string(), func.arity(), *fl, &args)?;
+	let loc = call_location(g);
+
+	let (mut f, points) = if args.len() == 2 {
+		(UnaryFunction::new(context, args[0], None, loc)?, &args[1..])
+	} else {
+		(UnaryFunction::new(context, args[0], Some(args[2]), loc)?, &args[1..2])
+	};
+	let Some(points) = eval_points(context, points)? else { return Ok(None); };
+	let (_, x) = &points[0];
+	f.x_unit.set_unit(x.unit.clone());
+
+	let old = context.get_shadow(&f.var);
+	let r = derive(context, &mut f, &x.scalar);
+	context.restore_shadow(&f.var, old);
+	let Some(r) = r? else { return Ok(None); };
+
+	let r = Quantity::from_scalar(r) * f.f_unit.unwrap() / f.x_unit;
+	return Ok(Some(Expression::Quantity(loc, simplify_unit(r))));
+}
+
+
+// A central difference, for Newton's method.
+// This doesn't need to be exact, so it's much cheaper than derive().
+fn slope(
+	context: &mut Context,
+	f: &mut UnaryFunction,
+	x: &Scalar
+) -> Result<Scalar, (LineLocation, DaisyError)> {
+	let h = if x.is_zero() { float(1e-6) } else { float(1e-6) * x.abs() };
+	let a = f.call(context, &(x.clone() + h.clone()))?;
+	let b = f.call(context, &(x.clone() - h.clone()))?;
+	return Ok((a - b) / (float(2f64) * h));
+}
+
+// Newton's method, starting at x.
+fn newton(
+	context: &mut Context,
+	f: &mut UnaryFunction,
+	x: &Scalar
+) -> Result<Option<Scalar>, (LineLocation, DaisyError)> {
+	let tol = float(context.config.tolerance);
+
+	// Make sure we can evaluate this function
+	let Some(mut fx) = f.try_call(context, x)? else { return Ok(None); };
+	let mut x = x.clone();
+
+	for _ in 0..MAX_STEPS {
+		if fx.is_zero() { return Ok(Some(x)); }
+
+		let d = slope(context, f, &x)?;
+		if d.is_zero() { break; }
+
+		let dx = fx / d;
+		x = x - dx.clone();
+		fx = f.call(context, &x)?;
+
+		let tol = tol.clone() * max(x.abs(), float(1f64));
+		if dx.abs() <= tol { return Ok(Some(round_to(x, &tol))); }
+	}
+
+	return Err((f.loc, DaisyError::NoConvergence));
+}
+
+// Newton's method with a bracket.
+// We bisect whenever a Newton step leaves the bracket or converges too slowly,
+// so this always finds a root if f changes sign between a and b.
+fn bracketed(
+	context: &mut Context,
+	f: &mut UnaryFunction,
+	a: &Scalar, b: &Scalar
+) -> Result<Option<Scalar>, (LineLocation, DaisyError)> {
+	let tol = float(context.config.tolerance);
+
+	// Make sure we can evaluate this function
+	let Some(fa) = f.try_call(context, a)? else { return Ok(None); };
+	let fb = f.call(context, b)?;
+
+	if fa.is_zero() { return Ok(Some(a.clone())); }
+	if fb.is_zero() { return Ok(Some(b.clone())); }
+	if fa.is_negative() == fb.is_negative() {
+		return Err((f.loc, DaisyError::NoSignChange));
+	}
+
+	// f(lo) < 0 < f(hi)
+	let (mut lo, mut hi) = if fa.is_negative() {
+		(a.clone(), b.clone())
+	} else { (b.clone(), a.clone()) };
+
+	let mut x = (a.clone() + b.clone()) / float(2f64);
+	let mut dx_old = (b.clone() - a.clone()).abs();
+
+	for _ in 0..MAX_STEPS {
+		let fx = f.call(context, &x)?;
+		if fx.is_zero() { return Ok(Some(x)); }
+		if fx.is_negative() { lo = x.clone(); } else { hi = x.clone(); }
+
+		let d = slope(context, f, &x)?;
+		let bisect = (lo.clone() + hi.clone()) / float(2f64);
+		let next = if d.is_zero() { bisect } else {
+			let n = x.clone() - fx / d;
+			let inside = (n > lo && n < hi) || (n > hi && n < lo);
+			if inside && (n.clone() - x.clone()).abs() * float(2f64) < dx_old { n } else { bisect }
+		};
+
+		let dx = (next.clone() - x.clone()).abs();
+		x = next;
+
+		let tol = tol.clone() * max(x.abs(), float(1f64));
+		if dx <= tol { return Ok(Some(round_to(x, &tol))); }
+		dx_old = dx;
+	}
+
+	return Err((f.loc, DaisyError::NoConvergence));
+}
+
+pub fn eval_solve(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(fl, Operator::Function(func), args) = g else { unreachable!() };
+	let args = unpack_args(args);
+	let loc = call_location(g);
+
+	// This is either `solve(f, a, [b])` with a user function,
+	// or `solve(expr, x, a, [b])` with an expression.
+	let user = matches!(args.first(), Some(Expression::Variable(_, s)) if context.is_function(s));
+	let arity = if args.is_empty() {
+		func.arity()
+	} else if user {
+		Arity::Optional(2, 3)
+	} else { Arity::Optional(3, 4) };
+	check_arity(&func.to_string(), arity, *fl, &args)?;
+
+	let (mut f, points) = if user {
+		(UnaryFunction::new(context, args[0], None, loc)?, &args[1..])
+	} else {
+		(UnaryFunction::new(context, args[1], Some(args[0]), loc)?, &args[2..])
+	};
+
+	let Some(points) = eval_points(context, points)? else { return Ok(None); };
+	let (la, a) = &points[0];
+	f.x_unit.set_unit(a.unit.clone());
+
+	// A bracket's ends are converted to the units of `a`
+	let b = match points.get(1) {
+		None => None,
+		Some((lb, b)) => match b.convert_to(f.x_unit.clone()) {
+			Some(b) => Some(b),
+			None => { return Err((*la + *lb, DaisyError::IncompatibleUnit)); }
+		}
+	};
+
+	let old = context.get_shadow(&f.var);
+	let r = match b {
+		None => newton(context, &mut f, &a.scalar),
+		Some(b) => bracketed(context, &mut f, &a.scalar, &b.scalar)
+	};
+	context.restore_shadow(&f.var, old);
+	let Some(r) = r? else { return Ok(None); };
+
+	let r = Quantity::from_scalar(r) * f.x_unit;
+	return Ok(Some(Expression::Quantity(loc, r)));
+}
@@ -1,11 +1,14 @@
 use crate::parser::Expression;
 use crate::parser::Operator;
+use crate::parser::Constant;
+use crate::quantity::Quantity;
+use crate::quantity::Scalar;
 use crate::context::Context;
 use crate::parser::LineLocation;
 use crate::errors::DaisyError;
 
 use super::operator::eval_operator;
-use super::function::eval_function;
+use super::function::{eval_function, is_lazy, eval_lazy};
 
 
 pub fn evaluate(
@@ -16,6 +19,8 @@ pub fn evaluate(
 	(LineLocation, DaisyError)
 > {
 
+	context.activate();
+
 	// Keeps track of our position in the expression tree.
 	// For example, the coordinates [0, 2, 1] are interpreted as follows:
 	// Start at the root node,
@@ -32,11 +37,49 @@ pub fn evaluate(
 	// Repeats while we have coordinates to parse.
 	// Exits when we finish parsing the root node.
 	loop {
+		// Formats (like `hex`) are only allowed on the right side of `to`,
+		// so we need to know where we are.
+		let n = coords.len();
+		let in_convert = n >= 2 && coords[n-2] == 1 && matches!(
+			root.get_at_coords(&coords[0 .. n - 2]),
+			Some(Expression::Operator(_, Operator::UnitConvert, _))
+		);
+
 		// Current position in the tree
 		let g = root.get_at_coords_mut(
 			&coords[0 .. coords.len() - 1]
 		).unwrap();
 
+		// Some functions (like `if`) evaluate their own arguments,
+		// so we handle them before moving down.
+		if *coords.last().unwrap() == 0 && is_lazy(context, g) {
+			if let Some(new) = eval_lazy(context, g)? {
+				*g = new;
+				continue;
+			}
+
+			// If we couldn't evaluate this function yet,
+			// leave it alone and move up.
+			coords.pop();
+			if coords.len() != 0 {
+				*coords.last_mut().unwrap() += 1;
+			} else { break; }
+			continue;
+		}
+
+		// `&&` and `||` short-circuit:
+		// we don't evaluate the right side if we already know the answer.
+		if let Expression::Operator(l, o @ (Operator::And | Operator::Or), args) = g {
+			if *coords.last().unwrap() == 1 {
+				if let Expression::Bool(la, a) = args[0] {
+					if a == matches!(o, Operator::Or) {
+						*g = Expression::Bool(la + *l, a);
+						continue;
+					}
+				}
+			}
+		}
+
 		// "Move up" step.
 		// We move up if we're at a leaf or if we're out of children to move down to.
 		if {
@@ -49,8 +92,25 @@ pub fn evaluate(
 			let mut move_up = true;
 
 			let new = match g {
-				Expression::Quantity(_, _) => None,
+				Expression::Quantity(l, q) => {
+					// Quantities made in another context may have units we don't know
+					if !q.unit.is_defined() {
+						return Err((*l, DaisyError::Undefined(String::from("unit"))));
+					}
+					None
+				},
+				Expression::Bool(_, _) => None,
 				Expression::Tuple(_, _) => None,
+				Expression::Format(l, f) => {
+					if !in_convert {
+						return Err((*l, DaisyError::BadFormat(f.to_string())));
+					}
+					None
+				},
+				// Pi is kept exact, so we don't expand it to a decimal
+				Expression::Constant(l, Constant::Pi) => {
+					Some(Expression::Quantity(*l, Quantity::from_scalar(Scalar::new_pi_from_frac(1, 1, 1).unwrap())))
+				},
 				Expression::Constant(_, c) => { Some(evaluate(context, &c.value()).unwrap()) },
 				Expression::Variable(l, s) => {
 					// Don't move up, re-evaluate
@@ -1,7 +1,13 @@
 use std::collections::VecDeque;
+use num::BigInt;
+use num::Signed;
+use num::Zero;
+use num::One;
+use num::ToPrimitive;
 
 use crate::parser::Expression;
 use crate::parser::Function;
+use crate::parser::Arity;
 use crate::parser::Operator;
 use crate::parser::LineLocation;
 use crate::quantity::FreeUnit;
@@ -9,9 +15,21 @@ use crate::quantity::Unit;
 use crate::quantity::WholeUnit;
 use crate::quantity::Quantity;
 use crate::quantity::Scalar;
+use crate::quantity::DisplayFormat;
 use crate::errors::DaisyError;
 use crate::context::Context;
 use super::evaluate;
+use super::numtheory;
+use super::calculus;
+use super::matrix;
+use super::polynomial;
+
+// Largest number of terms we'll evaluate in `sum(k, a, b, f)`
+const MAX_TERMS: usize = 100_000;
+
+// Exact sums like `sum(k, 1, n, 1/k^2)` get slow very quickly.
+// Rational results with more bits than this are turned into floats.
+const MAX_TERM_BITS: u64 = 4096;
 
 // If unitless, do nothing
 // If compatible with radians, convert to radians and return unitless
@@ -30,24 +48,494 @@ fn to_radians(q: Quantity) -> Result<Quantity, ()> {
 
 
 
-pub fn eval_function(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+// `f(a, b)` is parsed as `f` applied to the tuple `(a, b)`.
+// This flattens a function's arguments into one list.
+pub fn unpack_args(args: &VecDeque<Expression>) -> Vec<&Expression> {
+	let mut v = Vec::new();
+	for a in args {
+		if let Expression::Tuple(_, t) = a {
+			v.extend(t.iter());
+		} else {
+			v.push(a);
+		}
+	}
+	return v;
+}
 
+// Same as unpack_args, but also unpacks nested tuples.
+// Built-in functions never take tuples, so `mean(x, 5)`
+// with x = (1, 2) is the same as `mean(1, 2, 5)`.
+fn flatten_args(args: &VecDeque<Expression>) -> Vec<&Expression> {
+	let mut v = Vec::new();
+	for a in args {
+		if let Expression::Tuple(_, t) = a {
+			v.extend(flatten_args(t));
+		} else {
+			v.push(a);
+		}
+	}
+	return v;
+}
+
+// `sum` and `product` can also take an index variable,
+// like `sum(k, 1, 10, k^2)`. Constants are bad index names,
+// and eval_range() rejects them.
+// If the index is a variable with a value, like `sum(a, b, c, d)`
+// with all four defined, this is a normal sum.
+fn is_range(context: &Context, args: &[&Expression]) -> bool {
+	if args.len() != 4 { return false; }
+	return match args[0] {
+		Expression::Constant(_, _) => true,
+		Expression::Variable(_, s) => !context.get_variables().contains_key(s),
+		_ => false
+	};
+}
+
+// True if this is a function that decides how its own arguments are evaluated.
+// These are handled by eval_lazy() before we evaluate any arguments.
+pub fn is_lazy(context: &Context, g: &Expression) -> bool {
+	let Expression::Operator(_, Operator::Function(f), args) = g else { return false; };
+	return match f {
+		Function::If
+		| Function::Integrate
+		| Function::Derive
+		| Function::Solve
+		=> true,
+		Function::Sum | Function::Product => is_range(context, &unpack_args(args)),
+		_ => false
+	};
+}
+
+// Returns None if this function can't be evaluated yet.
+pub fn eval_lazy(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(_, Operator::Function(f), _) = g else {unreachable!()};
+	return match f {
+		Function::If => eval_if(context, g),
+		Function::Integrate => calculus::eval_integrate(context, g),
+		Function::Derive => calculus::eval_derive(context, g),
+		Function::Solve => calculus::eval_solve(context, g),
+		_ => eval_range(context, g)
+	};
+}
+
+// Picks a branch of `if(c, a, b)`, evaluating only the condition.
+fn eval_if(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
 	let Expression::Operator(loc, Operator::Function(f), args) = g else {unreachable!()};
 
-	if args.len() != 1 {panic!()};
-	let a = &args[0];
+	let args = unpack_args(args);
+	check_arity(&f.to_string(), f.arity(), *loc, &args)?;
+
+	match evaluate(context, args[0])? {
+		Expression::Bool(_, true) => { return Ok(Some(args[1].clone())); },
+		Expression::Bool(_, false) => { return Ok(Some(args[2].clone())); },
+		Expression::Operator(_, _, _) | Expression::Variable(_, _) => { return Ok(None); },
+		c => { return Err((c.get_linelocation(), DaisyError::ExpectedBool)); }
+	}
+}
 
-	// All the functions below take only one argument
-	if let Expression::Tuple(l, v) = a {
-		return Err((
-			*l + *loc,
-			DaisyError::BadArguments(f.to_string(), 1, v.len())
-		))
+// Evaluates `sum(k, a, b, f)` or `product(k, a, b, f)`
+fn eval_range(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(loc, Operator::Function(f), args) = g else {unreachable!()};
+	let args = unpack_args(args);
+	let (lk, k) = match args[0] {
+		Expression::Variable(l, k) => (l, k),
+		Expression::Constant(l, _) => { return Err((*l, DaisyError::BadVariable)); },
+		_ => unreachable!()
 	};
+	if !context.valid_varible(k) { return Err((*lk, DaisyError::BadVariable)); }
+
+	let mut bounds = Vec::with_capacity(2);
+	for a in &args[1..3] {
+		match evaluate(context, a)? {
+			Expression::Quantity(l, q) => { bounds.push((l, q)); },
+			Expression::Bool(l, _) => { return Err((l, DaisyError::UnexpectedBool)); },
+			_ => { return Ok(None); }
+		}
+	}
+	let v = integer_args(f, &bounds)?;
+
+	if &v[1] - &v[0] >= BigInt::from(MAX_TERMS) {
+		return Err((bounds[0].0 + bounds[1].0, DaisyError::TooBig));
+	}
+
+	// The index variable may already be a shadow
+	// (for example, an argument of a user function)
+	let old = context.get_shadow(k);
+	let r = range_value(context, f, (*lk, k), (&v[0], &v[1]), args[3]);
+	context.restore_shadow(k, old);
+
+	let Some(r) = r? else { return Ok(None); };
+
+	let mut lall = *loc;
+	for a in &args { lall += a.get_linelocation(); }
+	return Ok(Some(Expression::Quantity(lall, r)));
+}
+
+// Adds or multiplies every term of a range.
+// Returns None if a term can't be evaluated yet.
+fn range_value(
+	context: &mut Context,
+	f: &Function,
+	(lk, k): (LineLocation, &String),
+	(a, b): (&BigInt, &BigInt),
+	body: &Expression
+) -> Result<Option<Quantity>, (LineLocation, DaisyError)> {
+	let mut r: Option<Quantity> = None;
 
-	let Expression::Quantity(l, q) = a else { return Ok(None); };
+	let mut i = a.clone();
+	while i <= *b {
+		context.add_shadow(
+			k.clone(),
+			Some(Expression::Quantity(lk, Quantity::new_rational_from_integer(i.clone())))
+		);
+
+		let t = match evaluate(context, body)? {
+			Expression::Quantity(_, q) => q,
+			Expression::Bool(l, _) => { return Err((l, DaisyError::UnexpectedBool)); },
+			_ => { return Ok(None); }
+		};
+
+		r = Some(match (r, f) {
+			(None, _) => t,
+			(Some(r), Function::Sum) if r.unit().has_log() || t.unit().has_log() => {
+				let Some(s) = r.add_log(&t) else {
+					return Err((body.get_linelocation(), DaisyError::IncompatibleUnit));
+				};
+				s
+			},
+			(Some(r), Function::Sum) => {
+				let t = match_arg_units(context, &r, &t, body.get_linelocation())?;
+				r + t
+			},
+			(Some(r), _) if r.unit().has_log() || t.unit().has_log() => {
+				return Err((body.get_linelocation(), DaisyError::IncompatibleUnit));
+			},
+			(Some(r), _) => {
+				// Like `product(...)`, we match units but don't require them to be compatible.
+				let mut t = t;
+				t.match_units(&r);
+				r * t
+			}
+		}.limit_bits(MAX_TERM_BITS));
+
+		i += 1;
+	}
+
+	// Empty ranges
+	if r.is_none() {
+		r = Some(Quantity::new_rational(if let Function::Sum = f { 0f64 } else { 1f64 }).unwrap());
+	}
+
+	return Ok(r);
+}
+
+// Make sure a function got the right number of arguments.
+// If we got too many, point to the first extra argument.
+pub fn check_arity(
+	name: &str,
+	arity: Arity,
+	loc: LineLocation,
+	args: &Vec<&Expression>
+) -> Result<(), (LineLocation, DaisyError)> {
+	if arity.accepts(args.len()) { return Ok(()); }
+
+	let l = match arity.max() {
+		Some(m) if args.len() > m => {
+			let mut l = args[m].get_linelocation();
+			for a in &args[m..] { l += a.get_linelocation(); }
+			l
+		},
+		_ => {
+			let mut l = loc;
+			for a in args { l += a.get_linelocation(); }
+			l
+		}
+	};
+
+	return Err((l, DaisyError::BadArguments(name.to_string(), arity, args.len())));
+}
+
+// Convert `b` to the units of `a`.
+// `lb` is the location of `b`, used for errors.
+fn match_arg_units(
+	context: &Context,
+	a: &Quantity,
+	b: &Quantity,
+	lb: LineLocation
+) -> Result<Quantity, (LineLocation, DaisyError)> {
+	if !a.unit().compatible_with(b.unit()) {
+		let a_s = if a.unitless() { String::from("scalar") } else { a.convert_to_base().unit().display(context) };
+		let b_s = if b.unitless() { String::from("scalar") } else { b.convert_to_base().unit().display(context) };
+		return Err((lb, DaisyError::IncompatibleUnits(a_s, b_s)));
+	};
+
+	let mut r = b.clone();
+	r.match_units(a);
+	return Ok(r);
+}
+
+// Convert every quantity in `qs` to the units of the first one.
+fn match_all_units(
+	context: &Context,
+	qs: &[(LineLocation, Quantity)]
+) -> Result<Vec<Quantity>, (LineLocation, DaisyError)> {
+	let mut v = Vec::with_capacity(qs.len());
+	for (l, q) in qs {
+		v.push(match_arg_units(context, &qs[0].1, q, *l)?);
+	}
+	return Ok(v);
+}
+
+// Same as match_all_units, but also sorts the result.
+// Complex numbers can't be sorted, so we error if we find one.
+fn sorted_args(
+	context: &Context,
+	qs: &[(LineLocation, Quantity)]
+) -> Result<Vec<Quantity>, (LineLocation, DaisyError)> {
+	for (l, q) in qs {
+		if q.is_complex() || q.is_nan() { return Err((*l, DaisyError::BadMath)); }
+	}
+
+	let mut v = match_all_units(context, qs)?;
+	v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+	return Ok(v);
+}
+
+// Levels and ratios like dBm and dB aren't linear,
+// so we can't average or scale them.
+fn check_no_log(qs: &[(LineLocation, Quantity)]) -> Result<(), (LineLocation, DaisyError)> {
+	for (l, q) in qs {
+		if q.unit().has_log() { return Err((*l, DaisyError::IncompatibleUnit)); }
+	}
+	return Ok(());
+}
+
+// Like sum(), but for logarithmic units.
+// These follow the same rules as `+`, so `sum(10 dBm, 3 dB)` works
+// but two levels can't be added.
+fn sum_log(qs: &[(LineLocation, Quantity)]) -> Result<Quantity, (LineLocation, DaisyError)> {
+	let mut s = qs[0].1.clone();
+	for (l, q) in &qs[1..] {
+		let Some(r) = s.add_log(q) else { return Err((*l, DaisyError::IncompatibleUnit)); };
+		s = r;
+	}
+	return Ok(s);
+}
+
+fn sum(v: &[Quantity]) -> Quantity {
+	let mut s = v[0].clone();
+	for q in &v[1..] { s += q.clone(); }
+	return s;
+}
+
+fn mean(v: &[Quantity]) -> Quantity {
+	return sum(v) / Quantity::new_rational(v.len() as f64).unwrap();
+}
+
+// Sample variance
+fn variance(v: &[Quantity]) -> Quantity {
+	let m = mean(v);
+	let d: Vec<Quantity> = v.iter().map(|q| {
+		let d = q.clone() - m.clone();
+		d.clone() * d
+	}).collect();
+	return sum(&d) / Quantity::new_rational((v.len() - 1) as f64).unwrap();
+}
+
+// The most dice we'll roll at once
+const MAX_DICE: usize = 100_000;
+
+// Convert every argument to an integer.
+// Errors at the first argument that isn't one.
+fn integer_args(
+	f: &Function,
+	qs: &[(LineLocation, Quantity)]
+) -> Result<Vec<BigInt>, (LineLocation, DaisyError)> {
+	let mut v = Vec::with_capacity(qs.len());
+	for (l, q) in qs {
+		let Some(i) = q.to_integer() else {
+			return Err((*l, DaisyError::IntegerOnly(f.to_string())));
+		};
+		v.push(i);
+	}
+	return Ok(v);
+}
+
+
+// Roll `n` dice with `s` sides and add the results.
+// Used by `dice(n, s)` and `NdS`.
+pub fn roll_dice(
+	context: &mut Context,
+	name: &str,
+	(ln, n): (LineLocation, &Quantity),
+	(ls, s): (LineLocation, &Quantity)
+) -> Result<Quantity, (LineLocation, DaisyError)> {
+	let Some(n) = n.to_integer() else {
+		return Err((ln, DaisyError::IntegerOnly(String::from(name))));
+	};
+	let Some(s) = s.to_integer() else {
+		return Err((ls, DaisyError::IntegerOnly(String::from(name))));
+	};
+
+	if n.is_negative() { return Err((ln, DaisyError::BadMath)); }
+	if !s.is_positive() { return Err((ls, DaisyError::BadMath)); }
+
+	let n = n.to_usize().filter(|n| *n <= MAX_DICE);
+	let Some(n) = n else { return Err((ln, DaisyError::TooBig)); };
+	let Some(s) = s.to_u64() else { return Err((ls, DaisyError::TooBig)); };
+
+	let mut r: u128 = 0;
+	for _ in 0..n {
+		r += u128::from(context.rng().below(s)) + 1;
+	}
+
+	return Ok(Quantity::new_rational_from_integer(BigInt::from(r)));
+}
+
+// Checks the arguments of binompmf and binomcdf,
+// and returns n and p.
+fn binom_args(
+	context: &Context,
+	f: &Function,
+	qs: &[(LineLocation, Quantity)]
+) -> Result<(usize, Quantity), (LineLocation, DaisyError)> {
+	let v = integer_args(f, &qs[0..2])?;
+	let (ln, _) = &qs[1];
+	let (lp, p) = &qs[2];
+
+	if v[1].is_negative() { return Err((*ln, DaisyError::BadMath)); }
+	let n = v[1].to_usize().filter(|n| *n <= context.config.max_factorial);
+	let Some(n) = n else { return Err((*ln, DaisyError::TooBig)); };
+
+	if !p.unitless() { return Err((*lp, DaisyError::IncompatibleUnit)); }
+	if {
+		p.is_complex() || p.is_nan() || p.is_negative() ||
+		*p > Quantity::new_rational(1f64).unwrap()
+	} { return Err((*lp, DaisyError::BadMath)); }
+
+	return Ok((n, p.clone()));
+}
+
+// P(X = k) for a binomial distribution.
+// This is exact when the powers stay rational. Otherwise,
+// we work in log space so large n doesn't overflow.
+fn binom_pmf(n: usize, k: usize, p: &Quantity) -> Quantity {
+	let int = |x: usize| Quantity::new_rational(x as f64).unwrap();
+	let q = int(1) - p.clone();
+
+	if p.is_zero() { return int(if k == 0 {1} else {0}); }
+	if q.is_zero() { return int(if k == n {1} else {0}); }
+
+	let a = p.pow(int(k));
+	let b = q.pow(int(n - k));
+	if a.scalar.is_rational() && b.scalar.is_rational() {
+		let c = Quantity::new_rational_from_integer(numtheory::binomial(&BigInt::from(n), k));
+		return c * a * b;
+	}
+
+	let l = int(n + 1).lgamma() - int(k + 1).lgamma() - int(n - k + 1).lgamma()
+		+ int(k) * p.ln() + int(n - k) * q.ln();
+	return l.exp();
+}
+
+// P(X <= k) for a binomial distribution.
+// Terms shrink as we move away from the mode, so we start at k
+// and use p(i+1) = p(i) * (n-i)/(i+1) * p/q to walk down the lower tail.
+// If k is past the mean, we sum the upper tail instead.
+fn binom_cdf(n: usize, k: usize, p: &Quantity) -> Quantity {
+	let int = |x: usize| Quantity::new_rational(x as f64).unwrap();
+	let q = int(1) - p.clone();
+
+	if k >= n || p.is_zero() { return int(1); }
+	if q.is_zero() { return int(0); }
+	let r = p.clone() / q;
+
+	if int(k) < p.clone() * int(n) {
+		let mut t = binom_pmf(n, k, p);
+		let mut s = t.clone();
+		for i in (1..=k).rev() {
+			t = t * int(i) / (int(n - i + 1) * r.clone());
+			s += t.clone();
+		}
+		return s;
+	} else {
+		let mut t = binom_pmf(n, k + 1, p);
+		let mut s = t.clone();
+		for i in (k + 1)..n {
+			t = t * int(n - i) * r.clone() / int(i + 1);
+			s += t.clone();
+		}
+		return int(1) - s;
+	}
+}
+
+
+pub fn eval_function(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+
+	let Expression::Operator(loc, Operator::Function(f), args) = g else {unreachable!()};
+
+	// These take tuples, so we can't flatten their arguments
+	if let Function::Det
+		| Function::Inverse
+		| Function::Transpose
+		| Function::Dot
+		| Function::Cross
+		| Function::Norm
+	= f { return matrix::eval_matrix_function(g); }
+
+	if let Function::PolyRoots
+		| Function::PolyVal
+		| Function::PolyMul
+		| Function::PolyDiv
+		| Function::PolyRem
+	= f { return polynomial::eval_polynomial_function(context, g); }
+
+	let args = flatten_args(args);
+	check_arity(&f.to_string(), f.arity(), *loc, &args)?;
+
+	// Wait until all arguments are evaluated
+	let mut qs: Vec<(LineLocation, Quantity)> = Vec::with_capacity(args.len());
+	for a in &args {
+		if let Expression::Bool(l, _) = a { return Err((*l, DaisyError::UnexpectedBool)); }
+		let Expression::Quantity(l, q) = a else { return Ok(None); };
+		qs.push((*l, q.clone()));
+	}
+
+	// The location of this function call, including all arguments
+	let mut lall = *loc;
+	for (l, _) in &qs { lall += *l; }
+
+	// This is the only function without arguments
+	if let Function::Rand = f {
+		let r = Quantity::new_float(context.rng().next_f64()).unwrap();
+		return Ok(Some(Expression::Quantity(*loc, r)));
+	}
+
+	let (l, q) = &qs[0];
 
 	match f {
+		Function::Rand => unreachable!(),
+		Function::If
+		| Function::Integrate
+		| Function::Derive
+		| Function::Solve
+		=> unreachable!("Lazy functions are handled by evaluate()"),
+
+		Function::Det
+		| Function::Inverse
+		| Function::Transpose
+		| Function::Dot
+		| Function::Cross
+		| Function::Norm
+		=> unreachable!("Matrix functions are handled by eval_matrix_function()"),
+
+		Function::PolyRoots
+		| Function::PolyVal
+		| Function::PolyMul
+		| Function::PolyDiv
+		| Function::PolyRem
+		=> unreachable!("Polynomial functions are handled by eval_polynomial_function()"),
+
 		Function::NoUnit => { return Ok(Some(Expression::Quantity(*loc + *l, q.without_unit()))); }
 		Function::ToBase => { return Ok(Some(Expression::Quantity(*loc + *l, q.convert_to_base()))); }
 
@@ -75,7 +563,404 @@ pub fn eval_function(context: &mut Context, g: &Expression) -> Result<Option<Exp
 		},
 		Function::TenLog => {
 			if !q.unitless() { return Err((*loc + *l, DaisyError::IncompatibleUnit));}
-			return Ok(Some(Expression::Quantity(*loc + *l, q.log10())));
+			if qs.len() == 1 {
+				return Ok(Some(Expression::Quantity(*loc + *l, q.log10())));
+			}
+
+			let (lb, b) = &qs[1];
+			if !b.unitless() { return Err((*lb, DaisyError::IncompatibleUnit));}
+			if b.is_one() || b.is_zero() { return Err((*lb, DaisyError::BadMath));}
+
+			// Negative bases give complex results, like log(8, -2)
+			let r = q.log(b.clone());
+			if r.is_nan() { return Err((lall, DaisyError::BadMath)); }
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Root => {
+			let (lb, n) = &qs[1];
+			if !n.unitless() { return Err((*lb, DaisyError::IncompatibleUnit));}
+			if n.is_zero() { return Err((*lb, DaisyError::ZeroDivision));}
+			if !q.unitless() && n.is_complex() { return Err((lall, DaisyError::IncompatibleUnit));}
+
+			let p = Quantity::new_rational(1f64).unwrap() / n.clone();
+
+			// Odd roots of negative numbers are real.
+			let r = if {
+				q.is_negative() && n.is_odd_integer()
+			} { -(-q.clone()).pow(p) } else { q.pow(p) };
+
+			if r.is_nan() { return Err((lall, DaisyError::BadMath)); }
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Hypot => {
+			check_no_log(&qs)?;
+			let (lb, b) = &qs[1];
+			let b = match_arg_units(context, q, b, *lb)?;
+
+			let r = (q.clone() * q.clone() + b.clone() * b).pow(
+				Quantity::new_rational_from_frac(1, 2).unwrap()
+			);
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Atan2 => {
+			let (lx, x) = &qs[1];
+			let x = match_arg_units(context, q, x, *lx)?;
+
+			if q.is_complex() { return Err((*l, DaisyError::BadMath)); }
+			if x.is_complex() { return Err((*lx, DaisyError::BadMath)); }
+
+			return Ok(Some(Expression::Quantity(lall, q.without_unit().atan2(x.without_unit()))));
+		},
+
+		Function::Min => {
+			let v = sorted_args(context, &qs)?;
+			return Ok(Some(Expression::Quantity(lall, v[0].clone())));
+		},
+
+		Function::Max => {
+			let v = sorted_args(context, &qs)?;
+			return Ok(Some(Expression::Quantity(lall, v[v.len() - 1].clone())));
+		},
+
+		Function::Range => {
+			check_no_log(&qs)?;
+			let v = sorted_args(context, &qs)?;
+			return Ok(Some(Expression::Quantity(lall, v[v.len() - 1].clone() - v[0].clone())));
+		},
+
+		Function::Sum => {
+			if qs.iter().any(|(_, q)| q.unit().has_log()) {
+				return Ok(Some(Expression::Quantity(lall, sum_log(&qs)?)));
+			}
+
+			let v = match_all_units(context, &qs)?;
+			return Ok(Some(Expression::Quantity(lall, sum(&v))));
+		},
+
+		Function::Product => {
+			check_no_log(&qs)?;
+
+			// Units don't need to be compatible here,
+			// but we match them anyway so that (1 m, 50 cm) gives m^2.
+			let mut r = q.clone();
+			for (_, b) in &qs[1..] {
+				let mut b = b.clone();
+				b.match_units(q);
+				r *= b;
+			}
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Mean => {
+			check_no_log(&qs)?;
+			let v = match_all_units(context, &qs)?;
+			return Ok(Some(Expression::Quantity(lall, mean(&v))));
+		},
+
+		Function::Median => {
+			check_no_log(&qs)?;
+			let v = sorted_args(context, &qs)?;
+			let n = v.len();
+			let r = if n % 2 == 1 {
+				v[n / 2].clone()
+			} else {
+				mean(&v[n/2 - 1 ..= n/2])
+			};
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Mode => {
+			let v = match_all_units(context, &qs)?;
+
+			// If there are many modes, use the first one we find.
+			let mut best = 0;
+			let mut best_count = 0;
+			for i in 0..v.len() {
+				let c = v.iter().filter(|x| **x == v[i]).count();
+				if c > best_count { best = i; best_count = c; }
+			}
+			return Ok(Some(Expression::Quantity(lall, v[best].clone())));
+		},
+
+		Function::Variance
+		| Function::Stdev
+		=> {
+			check_no_log(&qs)?;
+			for (l, q) in &qs {
+				if q.is_complex() || q.is_nan() { return Err((*l, DaisyError::BadMath)); }
+			}
+
+			let v = match_all_units(context, &qs)?;
+			let r = variance(&v);
+
+			if let Function::Variance = f {
+				return Ok(Some(Expression::Quantity(lall, r)));
+			}
+			return Ok(Some(Expression::Quantity(lall, r.pow(Quantity::new_rational_from_frac(1, 2).unwrap()))));
+		},
+
+		Function::Percentile => {
+			let (lp, p) = &qs[0];
+			if !p.unitless() { return Err((*lp, DaisyError::IncompatibleUnit)); }
+			if {
+				p.is_complex() || p.is_nan() ||
+				p.is_negative() ||
+				*p > Quantity::new_rational(100f64).unwrap()
+			} { return Err((*lp, DaisyError::BadMath)); }
+
+			check_no_log(&qs[1..])?;
+			let v = sorted_args(context, &qs[1..])?;
+
+			// Linear interpolation between closest ranks
+			let rank = p.clone() / Quantity::new_rational(100f64).unwrap()
+				* Quantity::new_rational((v.len() - 1) as f64).unwrap();
+			let mut i = 0;
+			while i + 1 < v.len() && Quantity::new_rational((i + 1) as f64).unwrap() <= rank { i += 1; }
+			let t = rank - Quantity::new_rational(i as f64).unwrap();
+
+			let r = if t.is_zero() {
+				v[i].clone()
+			} else {
+				v[i].clone() + (v[i+1].clone() - v[i].clone()) * t
+			};
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Gamma => {
+			if !q.unitless() { return Err((*loc + *l, DaisyError::IncompatibleUnit));}
+			if q.is_complex() { return Err((*loc + *l, DaisyError::BadMath));}
+
+			// Gamma of a positive integer is an exact factorial
+			if let Some(n) = q.to_integer().and_then(|n| n.to_usize()) {
+				if n >= 1 && n - 1 <= context.config.max_factorial {
+					return Ok(Some(Expression::Quantity(*loc + *l, Quantity::new_rational_from_integer(numtheory::factorial(n - 1)))));
+				}
+			}
+
+			let r = q.gamma();
+			if r.is_nan() { return Err((*loc + *l, DaisyError::BadMath)); }
+			return Ok(Some(Expression::Quantity(*loc + *l, r)));
+		},
+
+		Function::LGamma
+		| Function::Erf
+		| Function::Erfc
+		=> {
+			if !q.unitless() { return Err((*loc + *l, DaisyError::IncompatibleUnit));}
+			if q.is_complex() { return Err((*loc + *l, DaisyError::BadMath));}
+
+			let r = match f {
+				Function::LGamma => q.lgamma(),
+				Function::Erf => q.erf(),
+				Function::Erfc => q.erfc(),
+				_ => unreachable!()
+			};
+
+			if r.is_nan() { return Err((*loc + *l, DaisyError::BadMath)); }
+			return Ok(Some(Expression::Quantity(*loc + *l, r)));
+		},
+
+		Function::Beta => {
+			let (lb, b) = &qs[1];
+			if !q.unitless() { return Err((*l, DaisyError::IncompatibleUnit));}
+			if !b.unitless() { return Err((*lb, DaisyError::IncompatibleUnit));}
+			if q.is_complex() { return Err((*l, DaisyError::BadMath));}
+			if b.is_complex() { return Err((*lb, DaisyError::BadMath));}
+
+			let r = q.beta(b.clone());
+			if r.is_nan() { return Err((lall, DaisyError::BadMath)); }
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::NCr
+		| Function::NPr
+		=> {
+			let v = integer_args(f, &qs)?;
+			let (lk, _) = &qs[1];
+			if v[1].is_negative() { return Err((*lk, DaisyError::BadMath)); }
+
+			let r = if let Function::NCr = f {
+				// Use the smaller of k and n - k
+				if !v[0].is_negative() && v[1] > v[0] {
+					BigInt::zero()
+				} else {
+					let k = if !v[0].is_negative() && &v[0] - &v[1] < v[1] { &v[0] - &v[1] } else { v[1].clone() };
+					let k = k.to_usize().filter(|k| *k <= context.config.max_factorial);
+					let Some(k) = k else { return Err((lall, DaisyError::TooBig)); };
+					numtheory::binomial(&v[0], k)
+				}
+			} else {
+				let k = v[1].to_usize().filter(|k| *k <= context.config.max_factorial);
+				let Some(k) = k else { return Err((lall, DaisyError::TooBig)); };
+				numtheory::permutations(&v[0], k)
+			};
+
+			return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(r))));
+		},
+
+		Function::Multinomial => {
+			let v = integer_args(f, &qs)?;
+
+			let mut r = BigInt::one();
+			let mut total: usize = 0;
+			for (i, k) in v.iter().enumerate() {
+				if k.is_negative() { return Err((qs[i].0, DaisyError::BadMath)); }
+				let k = k.to_usize().filter(|k| total + k <= context.config.max_factorial);
+				let Some(k) = k else { return Err((lall, DaisyError::TooBig)); };
+
+				total += k;
+				r *= numtheory::binomial(&BigInt::from(total), k);
+			}
+
+			return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(r))));
+		},
+
+		Function::BinomPmf => {
+			let (n, p) = binom_args(context, f, &qs)?;
+			let k = integer_args(f, &qs[0..1])?.pop().unwrap();
+
+			let k = k.to_usize().filter(|k| *k <= n);
+			let r = match k {
+				None => Quantity::new_rational(0f64).unwrap(),
+				Some(k) => binom_pmf(n, k, &p)
+			};
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::BinomCdf => {
+			let (n, p) = binom_args(context, f, &qs)?;
+			let k = integer_args(f, &qs[0..1])?.pop().unwrap();
+
+			let r = if k.is_negative() {
+				Quantity::new_rational(0f64).unwrap()
+			} else {
+				binom_cdf(n, k.to_usize().unwrap_or(n).min(n), &p)
+			};
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Dice => {
+			let r = roll_dice(context, "dice", (*l, q), (qs[1].0, &qs[1].1))?;
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Gcd
+		| Function::Lcm
+		=> {
+			let v = integer_args(f, &qs)?;
+			let mut r = v[0].abs();
+			for i in &v[1..] {
+				r = if let Function::Gcd = f { numtheory::gcd(&r, i) } else { numtheory::lcm(&r, i) };
+			}
+			return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(r))));
+		},
+
+		Function::ModInv => {
+			let v = integer_args(f, &qs)?;
+			if v[1].is_zero() { return Err((qs[1].0, DaisyError::ZeroDivision)); }
+			let Some(r) = numtheory::modinv(&v[0], &v[1]) else {
+				return Err((lall, DaisyError::BadMath));
+			};
+			return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(r))));
+		},
+
+		Function::ModPow => {
+			let v = integer_args(f, &qs)?;
+			if v[2].is_zero() { return Err((qs[2].0, DaisyError::ZeroDivision)); }
+			let Some(r) = numtheory::modpow(&v[0], &v[1], &v[2]) else {
+				return Err((lall, DaisyError::BadMath));
+			};
+			return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(r))));
+		},
+
+		Function::Rationalize => {
+			let (lb, b) = &qs[1];
+			let Some(maxden) = b.to_integer() else {
+				return Err((*lb, DaisyError::IntegerOnly(f.to_string())));
+			};
+			if !maxden.is_positive() { return Err((*lb, DaisyError::BadMath)); }
+
+			let Some(mut r) = q.rationalize(&maxden) else {
+				return Err((*l, DaisyError::BadMath));
+			};
+
+			// Show the fraction we found, unless we're printing in another base
+			if r.format.is_none() && context.config.output_format == DisplayFormat::Radix(10) {
+				r.format = Some(DisplayFormat::Fraction);
+			}
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::IsPrime => {
+			let v = integer_args(f, &qs)?;
+			let r = if numtheory::isprime(&v[0]) { 1 } else { 0 };
+			return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(BigInt::from(r)))));
+		},
+
+		Function::NextPrime => {
+			let v = integer_args(f, &qs)?;
+			return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(numtheory::nextprime(&v[0])))));
+		},
+
+		Function::Totient => {
+			let v = integer_args(f, &qs)?;
+			if !v[0].is_positive() { return Err((lall, DaisyError::BadMath)); }
+			let Some(r) = numtheory::totient(&v[0]) else {
+				return Err((lall, DaisyError::TooBig));
+			};
+			return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(r))));
+		},
+
+		Function::Factor => {
+			let v = integer_args(f, &qs)?;
+			if v[0].is_zero() { return Err((lall, DaisyError::BadMath)); }
+			let Some(mut r) = numtheory::factor(&v[0].abs()) else {
+				return Err((lall, DaisyError::TooBig));
+			};
+
+			if v[0].is_negative() { r.insert(0, BigInt::from(-1)); }
+
+			// factor(1) has no prime factors, but we need to return something.
+			if r.len() == 0 { r.push(BigInt::from(1)); }
+
+			if r.len() == 1 {
+				return Ok(Some(Expression::Quantity(lall, Quantity::new_rational_from_integer(r.pop().unwrap()))));
+			}
+
+			return Ok(Some(Expression::Tuple(
+				lall,
+				r.into_iter().map(|x| Expression::Quantity(lall, Quantity::new_rational_from_integer(x))).collect()
+			)));
+		},
+
+		Function::Interval => {
+			let (lb, b) = &qs[1];
+			let b = match_arg_units(context, q, b, *lb)?;
+
+			if q.is_complex() || q.is_nan() { return Err((*l, DaisyError::BadMath)); }
+			if b.is_complex() || b.is_nan() { return Err((*lb, DaisyError::BadMath)); }
+
+			let mut r = Quantity::from_scalar(Scalar::new_interval(q.scalar.clone(), b.scalar.clone()));
+			r.set_unit(q.unit.clone());
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::Clamp => {
+			let (llo, lo) = &qs[1];
+			let (lhi, hi) = &qs[2];
+			let lo = match_arg_units(context, q, lo, *llo)?;
+			let hi = match_arg_units(context, q, hi, *lhi)?;
+
+			if q.is_complex() || q.is_nan() { return Err((*l, DaisyError::BadMath)); }
+			if lo.is_complex() || lo.is_nan() { return Err((*llo, DaisyError::BadMath)); }
+			if hi.is_complex() || hi.is_nan() { return Err((*lhi, DaisyError::BadMath)); }
+			if lo > hi { return Err((*llo + *lhi, DaisyError::BadMath)); }
+
+			let r = if *q < lo { lo } else if *q > hi { hi } else { q.clone() };
+			return Ok(Some(Expression::Quantity(lall, r)));
 		},
 
 
@@ -154,6 +1039,15 @@ pub fn eval_function(context: &mut Context, g: &Expression) -> Result<Option<Exp
 		},
 
 
+		Function::Re => { return Ok(Some(Expression::Quantity(*loc + *l, q.re()))); },
+		Function::Im => { return Ok(Some(Expression::Quantity(*loc + *l, q.im()))); },
+		Function::Conj => { return Ok(Some(Expression::Quantity(*loc + *l, q.conj()))); },
+		Function::Arg => {
+			if !q.unitless() { return Err((*loc + *l, DaisyError::IncompatibleUnit));}
+			return Ok(Some(Expression::Quantity(*loc + *l, q.arg())));
+		},
+
+
 		Function::CtoF => {
 			return Ok(evaluate(context,
 				&Expression::Operator(
@@ -204,7 +1098,7 @@ pub fn eval_function(context: &mut Context, g: &Expression) -> Result<Option<Exp
 			};
 
 			let mut r = q.without_unit();
-			r += Quantity::new_rational(-273.15f64).unwrap();
+			r += Quantity::new_rational_from_string("-273.15").unwrap();
 
 			return Ok(Some(Expression::Quantity(*loc + *l, r)));
 		},
@@ -233,7 +1127,7 @@ pub fn eval_function(context: &mut Context, g: &Expression) -> Result<Option<Exp
 
 			let mut r = q.without_unit();
 			r *= Quantity::new_rational_from_frac(9i64, 5i64).unwrap();
-			r += Quantity::new_rational(-459.67).unwrap();
+			r += Quantity::new_rational_from_string("-459.67").unwrap();
 
 
 			return Ok(Some(Expression::Quantity(*loc + *l, r)));
@@ -251,7 +1145,7 @@ pub fn eval_function(context: &mut Context, g: &Expression) -> Result<Option<Exp
 				));
 			}
 
-			let mut r = Quantity::new_rational(273.15f64).unwrap();
+			let mut r = Quantity::new_rational_from_string("273.15").unwrap();
 			r += q.clone();
 			r.insert_unit(FreeUnit::from_whole(WholeUnit::Kelvin), Scalar::new_rational(1f64).unwrap());
 
@@ -271,7 +1165,7 @@ pub fn eval_function(context: &mut Context, g: &Expression) -> Result<Option<Exp
 			}
 
 			let mut r = q.clone();
-			r += Quantity::new_rational(459.67).unwrap();
+			r += Quantity::new_rational_from_string("459.67").unwrap();
 			r *= Quantity::new_rational_from_frac(5i64, 9i64).unwrap();
 			r.insert_unit(FreeUnit::from_whole(WholeUnit::Kelvin), Scalar::new_rational(1f64).unwrap());
 
@@ -0,0 +1,389 @@
+use std::collections::VecDeque;
+use num::Signed;
+use num::ToPrimitive;
+use crate::parser::Expression;
+use crate::parser::Operator;
+use crate::parser::Function;
+use crate::parser::Arity;
+use crate::parser::LineLocation;
+use crate::quantity::Quantity;
+use crate::errors::DaisyError;
+use crate::context::Context;
+use super::evaluate;
+use super::function::{unpack_args, check_arity};
+
+// Tuples are vectors, and tuples of equal-length tuples are matrices.
+// Most operators work on tuples element by element.
+
+
+// Largest matrix we'll invert.
+// We use cofactors, which take O(n^5) time.
+const MAX_INVERSE: usize = 16;
+
+// Largest power we'll raise a matrix to
+const MAX_POWER: usize = 1024;
+
+type Matrix = Vec<Vec<Quantity>>;
+
+
+pub(super) fn zero() -> Quantity { Quantity::new_rational(0f64).unwrap() }
+pub(super) fn one() -> Quantity { Quantity::new_rational(1f64).unwrap() }
+
+// Add two quantities, checking units.
+// Zero is compatible with every unit, so that
+// matrices like ((1 m, 0), (0, 1 m)) work.
+pub(super) fn add(a: Quantity, b: Quantity, loc: LineLocation) -> Result<Quantity, (LineLocation, DaisyError)> {
+	if b.is_zero() { return Ok(a); }
+	if a.is_zero() { return Ok(b); }
+	if !a.unit.compatible_with(&b.unit) { return Err((loc, DaisyError::IncompatibleUnit)); }
+	return Ok(a + b);
+}
+
+// True if this expression is fully evaluated.
+// Tuples can't be used until all their elements are.
+pub(super) fn is_evaluated(e: &Expression) -> Result<bool, (LineLocation, DaisyError)> {
+	return match e {
+		Expression::Quantity(_, _) | Expression::Format(_, _) => Ok(true),
+		Expression::Bool(l, _) => Err((*l, DaisyError::UnexpectedBool)),
+		Expression::Tuple(_, v) => {
+			for x in v { if !is_evaluated(x)? { return Ok(false); } }
+			Ok(true)
+		},
+		_ => Ok(false)
+	};
+}
+
+// Returns None if these aren't all quantities
+pub(super) fn to_vector<'a>(v: impl IntoIterator<Item = &'a Expression>) -> Option<Vec<Quantity>> {
+	let mut r = Vec::new();
+	for x in v {
+		let Expression::Quantity(_, q) = x else { return None; };
+		r.push(q.clone());
+	}
+	if r.len() == 0 { return None; }
+	return Some(r);
+}
+
+// Returns None if these aren't equal-length tuples of quantities
+fn to_matrix<'a>(rows: impl IntoIterator<Item = &'a Expression>) -> Option<Matrix> {
+	let mut m: Matrix = Vec::new();
+	for r in rows {
+		let Expression::Tuple(_, r) = r else { return None; };
+		let r = to_vector(r)?;
+		if m.len() > 0 && r.len() != m[0].len() { return None; }
+		m.push(r);
+	}
+	if m.len() == 0 { return None; }
+	return Some(m);
+}
+
+pub(super) fn from_vector(l: LineLocation, v: Vec<Quantity>) -> Expression {
+	return Expression::Tuple(l, v.into_iter().map(|x| Expression::Quantity(l, x)).collect());
+}
+
+fn from_matrix(l: LineLocation, m: Matrix) -> Expression {
+	return Expression::Tuple(l, m.into_iter().map(|r| from_vector(l, r)).collect());
+}
+
+// All quantities in a (possibly nested) tuple
+fn leaves(e: &Expression, v: &mut Vec<Quantity>) {
+	match e {
+		Expression::Quantity(_, q) => { v.push(q.clone()); },
+		Expression::Tuple(_, t) => { for x in t { leaves(x, v); } },
+		_ => {}
+	}
+}
+
+
+fn matmul(a: &Matrix, b: &Matrix, loc: LineLocation) -> Result<Matrix, (LineLocation, DaisyError)> {
+	if a[0].len() != b.len() { return Err((loc, DaisyError::BadDimensions)); }
+
+	let mut m = Vec::with_capacity(a.len());
+	for i in 0..a.len() {
+		let mut r = Vec::with_capacity(b[0].len());
+		for j in 0..b[0].len() {
+			let mut s = zero();
+			for k in 0..b.len() {
+				s = add(s, a[i][k].clone() * b[k][j].clone(), loc)?;
+			}
+			r.push(s);
+		}
+		m.push(r);
+	}
+	return Ok(m);
+}
+
+fn identity(n: usize) -> Matrix {
+	return (0..n).map(|i| (0..n).map(|j| if i == j { one() } else { zero() }).collect()).collect();
+}
+
+// Used to pick pivots
+fn magnitude(q: &Quantity) -> Quantity {
+	return q.convert_to_base().without_unit().abs();
+}
+
+// Gaussian elimination with partial pivoting
+fn det(m: &Matrix, loc: LineLocation) -> Result<Quantity, (LineLocation, DaisyError)> {
+	let n = m.len();
+	if m[0].len() != n { return Err((loc, DaisyError::BadDimensions)); }
+
+	let mut m = m.clone();
+	let mut d = one();
+	for i in 0..n {
+		let mut p = i;
+		for j in i+1..n {
+			if magnitude(&m[j][i]) > magnitude(&m[p][i]) { p = j; }
+		}
+
+		// This matrix is singular.
+		// Multiply what's left of the diagonal to keep our units.
+		if m[p][i].is_zero() {
+			for k in i..n { d = d * m[k][k].clone(); }
+			return Ok(d * zero());
+		}
+
+		if p != i {
+			m.swap(p, i);
+			d = -d;
+		}
+
+		let pivot = m[i][i].clone();
+		d = d * pivot.clone();
+
+		for j in i+1..n {
+			let f = m[j][i].clone() / pivot.clone();
+			if f.is_zero() { continue; }
+			for k in i..n {
+				m[j][k] = add(m[j][k].clone(), -(f.clone() * m[i][k].clone()), loc)?;
+			}
+		}
+	}
+
+	return Ok(d);
+}
+
+// The inverse is the transposed matrix of cofactors, divided by the determinant.
+// This is slow, but it keeps track of units.
+fn inverse(m: &Matrix, loc: LineLocation) -> Result<Matrix, (LineLocation, DaisyError)> {
+	let n = m.len();
+	if m[0].len() != n { return Err((loc, DaisyError::BadDimensions)); }
+	if n > MAX_INVERSE { return Err((loc, DaisyError::TooBig)); }
+
+	let d = det(m, loc)?;
+	if d.is_zero() { return Err((loc, DaisyError::ZeroDivision)); }
+	if n == 1 { return Ok(vec![vec![one() / m[0][0].clone()]]); }
+
+	let mut r = vec![vec![zero(); n]; n];
+	for i in 0..n {
+		for j in 0..n {
+			let minor: Matrix = (0..n).filter(|x| *x != i).map(|x| {
+				(0..n).filter(|y| *y != j).map(|y| m[x][y].clone()).collect()
+			}).collect();
+
+			let c = det(&minor, loc)? / d.clone();
+			r[j][i] = if (i + j) % 2 == 0 { c } else { -c };
+		}
+	}
+	return Ok(r);
+}
+
+fn transpose(m: &Matrix) -> Matrix {
+	return (0..m[0].len()).map(|j| m.iter().map(|r| r[j].clone()).collect()).collect();
+}
+
+fn power(m: &Matrix, lm: LineLocation, (lp, p): (LineLocation, &Quantity)) -> Result<Matrix, (LineLocation, DaisyError)> {
+	if m[0].len() != m.len() { return Err((lm, DaisyError::BadDimensions)); }
+	if !p.unitless() { return Err((lp, DaisyError::IncompatibleUnit)); }
+	let Some(p) = p.to_integer() else {
+		return Err((lp, DaisyError::IntegerOnly(String::from("matrix ^"))));
+	};
+
+	let Some(mut n) = p.abs().to_usize() else { return Err((lp, DaisyError::TooBig)); };
+	if n > MAX_POWER { return Err((lp, DaisyError::TooBig)); }
+
+	let mut b = if p.is_negative() { inverse(m, lm)? } else { m.clone() };
+	let mut r = identity(m.len());
+	while n > 0 {
+		if n % 2 == 1 { r = matmul(&r, &b, lm)?; }
+		n /= 2;
+		if n > 0 { b = matmul(&b, &b, lm)?; }
+	}
+	return Ok(r);
+}
+
+
+// Apply an operator to each element of a tuple,
+// or to each pair of elements in two tuples.
+fn elementwise(context: &mut Context, g: &Expression, lall: LineLocation) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(op_loc, op, args) = g else { unreachable!() };
+
+	let mut n: Option<usize> = None;
+	for a in args {
+		if let Expression::Tuple(_, t) = a {
+			if n.is_some() && n != Some(t.len()) { return Err((lall, DaisyError::BadDimensions)); }
+			n = Some(t.len());
+		}
+	}
+
+	let mut v = VecDeque::with_capacity(n.unwrap());
+	for i in 0..n.unwrap() {
+		let new_args = args.iter().map(|a| match a {
+			Expression::Tuple(_, t) => t[i].clone(),
+			_ => a.clone()
+		}).collect();
+
+		v.push_back(evaluate(context, &Expression::Operator(*op_loc, op.clone(), new_args))?);
+	}
+
+	return Ok(Some(Expression::Tuple(lall, v)));
+}
+
+// Called by eval_operator() when any argument is a tuple
+pub fn eval_tuple_operator(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(op_loc, op, args) = g else { unreachable!() };
+
+	for a in args { if !is_evaluated(a)? { return Ok(None); } }
+	let mut lall = *op_loc;
+	for a in args { lall += a.get_linelocation(); }
+
+	match op {
+		// Matrix products. Vectors are columns on the right and rows on the left.
+		// Two vectors are multiplied element by element.
+		Operator::Multiply |
+		Operator::ImplicitMultiply => {
+			let (Expression::Tuple(_, a), Expression::Tuple(_, b)) = (&args[0], &args[1]) else {
+				return elementwise(context, g, lall);
+			};
+
+			let r = match (to_matrix(a), to_matrix(b)) {
+				(Some(a), Some(b)) => from_matrix(lall, matmul(&a, &b, lall)?),
+
+				(Some(a), None) => {
+					let Some(b) = to_vector(b) else { return Err((lall, DaisyError::BadDimensions)); };
+					let b = b.into_iter().map(|x| vec![x]).collect();
+					from_vector(lall, matmul(&a, &b, lall)?.into_iter().map(|mut r| r.pop().unwrap()).collect())
+				},
+
+				(None, Some(b)) => {
+					let Some(a) = to_vector(a) else { return Err((lall, DaisyError::BadDimensions)); };
+					from_vector(lall, matmul(&vec![a], &b, lall)?.pop().unwrap())
+				},
+
+				(None, None) => { return elementwise(context, g, lall); }
+			};
+
+			return Ok(Some(r));
+		},
+
+		// Matrices are raised to integer powers by repeated multiplication.
+		// Vectors are raised to powers element by element.
+		Operator::Power => {
+			if let (Expression::Tuple(la, a), Expression::Quantity(lb, b)) = (&args[0], &args[1]) {
+				if let Some(a) = to_matrix(a) {
+					return Ok(Some(from_matrix(lall, power(&a, *la, (*lb, b))?)));
+				}
+			}
+			return elementwise(context, g, lall);
+		},
+
+		Operator::Negative
+		| Operator::Add
+		| Operator::Subtract
+		| Operator::Divide
+		| Operator::DivideLong
+		| Operator::Modulo
+		| Operator::ModuloLong
+		| Operator::Sqrt
+		| Operator::Factorial
+		| Operator::DoubleFactorial
+		| Operator::UnitConvert
+		| Operator::BitNot
+		| Operator::BitAnd
+		| Operator::BitOr
+		| Operator::BitXor
+		| Operator::ShiftLeft
+		| Operator::ShiftRight
+		| Operator::PlusMinus
+		=> { return elementwise(context, g, lall); },
+
+		_ => { return Err((lall, DaisyError::BadTupleOperation)); }
+	}
+}
+
+
+// Matrix and vector functions.
+// Unlike other functions, these take tuples as arguments.
+pub fn eval_matrix_function(g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(loc, Operator::Function(f), args) = g else { unreachable!() };
+
+	let args = unpack_args(args);
+	for a in &args { if !is_evaluated(a)? { return Ok(None); } }
+	let mut lall = *loc;
+	for a in &args { lall += a.get_linelocation(); }
+
+	match f {
+		Function::Dot | Function::Cross => {
+			check_arity(&f.to_string(), Arity::Fixed(2), *loc, &args)?;
+
+			let (Expression::Tuple(_, a), Expression::Tuple(_, b)) = (args[0], args[1]) else {
+				return Err((lall, DaisyError::BadDimensions));
+			};
+			let (Some(a), Some(b)) = (to_vector(a), to_vector(b)) else {
+				return Err((lall, DaisyError::BadDimensions));
+			};
+			if a.len() != b.len() { return Err((lall, DaisyError::BadDimensions)); }
+
+			if let Function::Dot = f {
+				let mut s = zero();
+				for (x, y) in a.into_iter().zip(b) { s = add(s, x * y, lall)?; }
+				return Ok(Some(Expression::Quantity(lall, s)));
+			}
+
+			if a.len() != 3 { return Err((lall, DaisyError::BadDimensions)); }
+			let c = |i: usize, j: usize| -> Result<Quantity, (LineLocation, DaisyError)> {
+				return add(a[i].clone() * b[j].clone(), -(a[j].clone() * b[i].clone()), lall);
+			};
+			return Ok(Some(from_vector(lall, vec![c(1, 2)?, c(2, 0)?, c(0, 1)?])));
+		},
+
+		Function::Norm => {
+			let mut v = Vec::new();
+			for a in &args { leaves(a, &mut v); }
+
+			let mut s = zero();
+			for x in v {
+				// abs() only works on unitless quantities
+				let mut a = x.without_unit().abs();
+				a.set_unit(x.unit.clone());
+				s = add(s, a.clone() * a, lall)?;
+			}
+			let r = s.pow(Quantity::new_rational_from_string("0.5").unwrap());
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		_ => {}
+	}
+
+	// `det(m)` with a matrix m is parsed the same way as `det((1, 2), (3, 4))`,
+	// so we take either one tuple or a list of rows.
+	let rows: Vec<&Expression> = match &args[..] {
+		[Expression::Tuple(_, t)] => t.iter().collect(),
+		_ => args.clone()
+	};
+
+	// Vectors have no orientation, so their transpose is themselves.
+	if let Function::Transpose = f {
+		if let Some(v) = to_vector(rows.iter().copied()) {
+			return Ok(Some(from_vector(lall, v)));
+		}
+	}
+
+	let Some(m) = to_matrix(rows) else { return Err((lall, DaisyError::BadDimensions)); };
+
+	return match f {
+		Function::Det => Ok(Some(Expression::Quantity(lall, det(&m, lall)?))),
+		Function::Inverse => Ok(Some(from_matrix(lall, inverse(&m, lall)?))),
+		Function::Transpose => Ok(Some(from_matrix(lall, transpose(&m)))),
+		_ => unreachable!()
+	};
+}
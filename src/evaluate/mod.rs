@@ -1,5 +1,9 @@
 mod operator;
 mod function;
+mod numtheory;
+mod calculus;
+mod matrix;
+mod polynomial;
 mod evaluate;
 
 pub use self::evaluate::evaluate;
\ No newline at end of file
@@ -0,0 +1,244 @@
+use num::BigInt;
+use num::Integer;
+use num::Signed;
+use num::Zero;
+use num::One;
+
+// Exact integer algorithms used by number theory functions.
+// All of these work on (possibly huge) BigInts.
+
+
+// Bases for Miller-Rabin.
+// These give an exact answer for all n < 3.3 * 10^24,
+// and a very good guess for larger n.
+const MR_BASES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+// Give up factoring after this many steps of Pollard's rho.
+const MAX_RHO_STEPS: usize = 1_000_000;
+
+// Greatest common divisor, always non-negative.
+pub fn gcd(a: &BigInt, b: &BigInt) -> BigInt {
+	return a.gcd(b);
+}
+
+// Least common multiple, always non-negative.
+pub fn lcm(a: &BigInt, b: &BigInt) -> BigInt {
+	return a.lcm(b);
+}
+
+// Inverse of a mod m.
+// None if a and m are not coprime.
+pub fn modinv(a: &BigInt, m: &BigInt) -> Option<BigInt> {
+	let m = m.abs();
+	let e = a.mod_floor(&m).extended_gcd(&m);
+	if !e.gcd.is_one() { return None; }
+	return Some(e.x.mod_floor(&m));
+}
+
+// b^e mod m, always non-negative.
+// Negative exponents use the modular inverse of b.
+pub fn modpow(b: &BigInt, e: &BigInt, m: &BigInt) -> Option<BigInt> {
+	let m = m.abs();
+	let b = if e.is_negative() { modinv(b, &m)? } else { b.mod_floor(&m) };
+	return Some(b.modpow(&e.abs(), &m));
+}
+
+pub fn isprime(n: &BigInt) -> bool {
+	if *n < BigInt::from(2) { return false; }
+
+	for p in MR_BASES {
+		let p = BigInt::from(p);
+		if *n == p { return true; }
+		if (n % &p).is_zero() { return false; }
+	}
+
+	// Miller-Rabin
+	// Write n - 1 as d * 2^s with d odd
+	let one = BigInt::one();
+	let nm1 = n - &one;
+	let s = nm1.trailing_zeros().unwrap();
+	let d = &nm1 >> s;
+
+	'outer: for a in MR_BASES {
+		let mut x = BigInt::from(a).modpow(&d, n);
+		if x == one || x == nm1 { continue; }
+		for _ in 1..s {
+			x = (&x * &x) % n;
+			if x == nm1 { continue 'outer; }
+		}
+		return false;
+	}
+
+	return true;
+}
+
+// The smallest prime larger than n.
+pub fn nextprime(n: &BigInt) -> BigInt {
+	if *n < BigInt::from(2) { return BigInt::from(2); }
+
+	let mut p = n + BigInt::one();
+	if p.is_even() && p != BigInt::from(2) { p += BigInt::one(); }
+	while !isprime(&p) { p += BigInt::from(2); }
+	return p;
+}
+
+// Find some nontrivial factor of n with Pollard's rho.
+// n must be odd and composite.
+fn rho(n: &BigInt) -> Option<BigInt> {
+	let mut c = BigInt::one();
+	let mut steps = 0;
+
+	loop {
+		let f = |x: &BigInt| (x * x + &c) % n;
+		let mut x = BigInt::from(2);
+		let mut y = BigInt::from(2);
+		let mut d = BigInt::one();
+
+		while d.is_one() {
+			x = f(&x);
+			y = f(&f(&y));
+			d = gcd(&(&x - &y), n);
+
+			steps += 1;
+			if steps > MAX_RHO_STEPS { return None; }
+		}
+
+		if d != *n { return Some(d); }
+
+		// This c didn't work, try another
+		c += BigInt::one();
+	}
+}
+
+// Prime factors of n, in increasing order, with repetition.
+// n must be positive.
+// None if n is too big to factor.
+pub fn factor(n: &BigInt) -> Option<Vec<BigInt>> {
+	let mut out = Vec::new();
+	let mut n = n.clone();
+
+	// Trial division removes small factors quickly
+	let mut p = BigInt::from(2);
+	while p < BigInt::from(1000) && &p * &p <= n {
+		while (&n % &p).is_zero() {
+			out.push(p.clone());
+			n /= &p;
+		}
+		p += BigInt::one();
+	}
+
+	// Split whatever is left with Pollard's rho
+	let mut stack = vec![n];
+	while let Some(n) = stack.pop() {
+		if n.is_one() { continue; }
+		if isprime(&n) { out.push(n); continue; }
+
+		let d = rho(&n)?;
+		stack.push(&n / &d);
+		stack.push(d);
+	}
+
+	out.sort();
+	return Some(out);
+}
+
+// All primes up to n, with a sieve of Eratosthenes
+fn primes_to(n: usize) -> Vec<usize> {
+	let mut sieve = vec![true; n + 1];
+	let mut out = Vec::new();
+	for i in 2..=n {
+		if !sieve[i] { continue; }
+		out.push(i);
+		let mut j = i * i;
+		while j <= n { sieve[j] = false; j += i; }
+	}
+	return out;
+}
+
+// Product of a list of numbers.
+// Splitting the list in half keeps both sides of each multiplication
+// about the same size, which is much faster for big numbers.
+fn product(v: &[BigInt]) -> BigInt {
+	match v.len() {
+		0 => BigInt::one(),
+		1 => v[0].clone(),
+		_ => product(&v[..v.len() / 2]) * product(&v[v.len() / 2..])
+	}
+}
+
+// n! / ((n/2)!)^2, the "swinging factorial" of n.
+// Its prime factors are easy to find, so we build it from those.
+fn swing(n: usize, primes: &[usize]) -> BigInt {
+	let mut f = Vec::new();
+	for &p in primes {
+		if p > n { break; }
+
+		if p * p > n {
+			// Large primes appear at most once
+			if (n / p) % 2 == 1 { f.push(BigInt::from(p)); }
+		} else {
+			let mut q = n;
+			let mut e = BigInt::one();
+			while q >= p {
+				q /= p;
+				if q % 2 == 1 { e *= p; }
+			}
+			f.push(e);
+		}
+	}
+	return product(&f);
+}
+
+fn factorial_rec(n: usize, primes: &[usize]) -> BigInt {
+	if n < 2 { return BigInt::one(); }
+	let f = factorial_rec(n / 2, primes);
+	return &f * &f * swing(n, primes);
+}
+
+// n!, using Luschny's prime swing algorithm
+pub fn factorial(n: usize) -> BigInt {
+	return factorial_rec(n, &primes_to(n));
+}
+
+// n!! = n * (n - 2) * (n - 4) * ...
+pub fn double_factorial(n: usize) -> BigInt {
+	let k = n / 2;
+	if n % 2 == 0 {
+		// (2k)!! = 2^k * k!
+		return factorial(k) << k;
+	} else {
+		// (2k + 1)!! = (2k + 1)! / (2^k * k!)
+		return factorial(n) / (factorial(k) << k);
+	}
+}
+
+// n choose k, for any n and non-negative k.
+pub fn binomial(n: &BigInt, k: usize) -> BigInt {
+	let mut r = BigInt::one();
+	for i in 0..k {
+		r = r * (n - BigInt::from(i)) / BigInt::from(i + 1);
+	}
+	return r;
+}
+
+// n! / (n - k)!
+pub fn permutations(n: &BigInt, k: usize) -> BigInt {
+	let mut r = BigInt::one();
+	for i in 0..k {
+		r *= n - BigInt::from(i);
+	}
+	return r;
+}
+
+// Euler's totient function.
+// n must be positive.
+pub fn totient(n: &BigInt) -> Option<BigInt> {
+	let mut f = factor(n)?;
+	f.dedup();
+
+	let mut r = n.clone();
+	for p in f {
+		r = r / &p * (&p - BigInt::one());
+	}
+	return Some(r);
+}
@@ -1,62 +1,128 @@
+use num::ToPrimitive;
+use num::BigInt;
+use num::Signed;
 use crate::parser::LineLocation;
 use crate::quantity::Quantity;
+use crate::quantity::Scalar;
+use crate::quantity::DisplayFormat;
 use crate::parser::Operator;
+use crate::parser::Arity;
 use crate::parser::Expression;
 use crate::context::Context;
 use crate::errors::DaisyError;
 use super::evaluate;
+use super::function::{unpack_args, check_arity, roll_dice};
+use super::numtheory;
+use super::matrix;
+
+// Largest shift we'll allow in `<<` and `>>`.
+const MAX_SHIFT: usize = 65536;
+
+// How deeply user functions may call each other,
+// and how much stack these calls may use.
+const MAX_CALL_DEPTH: usize = 1000;
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_CALL_STACK: usize = 1 << 20;
+#[cfg(target_arch = "wasm32")]
+const MAX_CALL_STACK: usize = 1 << 18;
+
+
+// Make sure two quantities can be compared.
+fn check_units(
+	context: &Context,
+	la: LineLocation, a: &Quantity,
+	lb: LineLocation, b: &Quantity,
+	op_loc: LineLocation
+) -> Result<(), (LineLocation, DaisyError)> {
+	if a.unit.compatible_with(&b.unit) { return Ok(()); }
+
+	let a = a.convert_to_base().unit;
+	let b = b.convert_to_base().unit;
+	let a_s = if a.unitless() { String::from("scalar") } else { a.display(context) };
+	let b_s = if b.unitless() { String::from("scalar") } else { b.display(context) };
+
+	return Err((la + lb + op_loc, DaisyError::IncompatibleUnits(a_s, b_s)));
+}
+
+// Levels like dBm are compared through their linear value,
+// so `10 dBm == 10 mW`. Returns `b` in the units of `a` if we can.
+fn match_level(a: &Quantity, b: &Quantity) -> Quantity {
+	if !(a.unit.is_log_level() || b.unit.is_log_level()) { return b.clone(); }
+	return b.convert_to(a.clone()).unwrap_or(b.clone());
+}
 
 
 pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
 
 	let Expression::Operator(op_loc, op, args) = g else {panic!()};
 
+	// Only logical operators and comparisons take booleans
+	if !matches!(
+		op,
+		Operator::Or | Operator::And | Operator::Not
+		| Operator::Equal | Operator::NotEqual
+		| Operator::UserFunction(_)
+	) {
+		for a in args {
+			if let Expression::Bool(l, _) = a {
+				return Err((*l, DaisyError::UnexpectedBool));
+			}
+		}
+	}
+
+	// Tuples are handled element by element
+	if {
+		!matches!(op, Operator::UserFunction(_)) &&
+		args.iter().any(|a| matches!(a, Expression::Tuple(_, _)))
+	} { return matrix::eval_tuple_operator(context, g); }
+
+	// There are no complex intervals or complex uncertain values,
+	// and intervals don't mix with uncertain values.
+	if !matches!(op, Operator::UserFunction(_)) && args.len() == 2 {
+		if let (Expression::Quantity(la, a), Expression::Quantity(lb, b)) = (&args[0], &args[1]) {
+			if !a.scalar.combines_with(&b.scalar) {
+				return Err((*la + *lb + *op_loc, DaisyError::BadMath));
+			}
+		}
+	}
+
 	match op {
 		Operator::Function(_) => unreachable!("Functions are handled seperately."),
 
 		Operator::UserFunction(s) => {
 			let (sh_vars, exp) = context.get_function(s).unwrap();
 
-			if args.len() != 1 {panic!()};
-			let a = &args[0];
-			let mut args_ll = op_loc.clone();
-
-			if sh_vars.len() == 1 {
-				if let Expression::Tuple(l, v) = a {
-					return Err((
-						*l + *op_loc,
-						DaisyError::BadArguments(s.clone(), 1, v.len())
-					))
-				};
+			let args = unpack_args(args);
+			check_arity(s, Arity::Fixed(sh_vars.len()), *op_loc, &args)?;
 
-				args_ll += a.get_linelocation();
-				context.add_shadow(sh_vars[0].clone(), Some(a.clone()));
-			} else {
-				let Expression::Tuple(l, v) = a else {
-					return Err((
-						a.get_linelocation() + *op_loc,
-						DaisyError::BadArguments(s.clone(), sh_vars.len(), 1)
-					));
-				};
+			// Wait until we know every argument.
+			// Arguments may be unknown while we check a new definition.
+			if args.iter().any(|a| !matches!(a, Expression::Quantity(..) | Expression::Bool(..) | Expression::Tuple(..))) {
+				return Ok(None);
+			}
 
-				if sh_vars.len() != v.len() {
-					return Err((
-						*l + *op_loc,
-						DaisyError::BadArguments(s.clone(), sh_vars.len(), v.len())
-					));
-				}
+			let mut args_ll = op_loc.clone();
+			for a in &args { args_ll += a.get_linelocation(); }
 
-				let mut i = 0;
-				while i < sh_vars.len() {
-					args_ll += v[i].get_linelocation();
-					context.add_shadow(sh_vars[i].clone(), Some(v[i].clone()));
-					i += 1;
-				}
+			if context.call_depth() >= MAX_CALL_DEPTH || context.call_stack_used() > MAX_CALL_STACK {
+				return Err((args_ll, DaisyError::TooDeep));
 			}
 
+			// Functions may call themselves,
+			// so we restore the caller's arguments when we're done.
+			let mut old = Vec::new();
+			for (v, a) in sh_vars.iter().zip(args.iter()) {
+				old.push(context.get_shadow(v));
+				context.add_shadow(v.clone(), Some((*a).clone()));
+			}
 
+			context.enter_call();
 			let r = evaluate(context, &exp);
-			context.clear_shadow();
+			context.exit_call();
+
+			for (v, o) in sh_vars.iter().zip(old) {
+				context.restore_shadow(v, o);
+			}
 
 			match r {
 				Ok(mut r) => {
@@ -70,6 +136,88 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 			}
 		},
 
+		Operator::Not => {
+			if args.len() != 1 { panic!() };
+			let args = &args[0];
+
+			match args {
+				Expression::Bool(l, v) => { return Ok(Some(Expression::Bool(*l + *op_loc, !v))); },
+				Expression::Quantity(l, _) => { return Err((*l, DaisyError::ExpectedBool)); },
+				_ => { return Ok(None); }
+			}
+		},
+
+		Operator::And |
+		Operator::Or => {
+			if args.len() != 2 { panic!() };
+
+			match (&args[0], &args[1]) {
+				(Expression::Bool(la, a), Expression::Bool(lb, b)) => {
+					let r = if let Operator::And = op { *a && *b } else { *a || *b };
+					return Ok(Some(Expression::Bool(*la + *lb + *op_loc, r)));
+				},
+
+				(Expression::Quantity(l, _), _)
+				| (_, Expression::Quantity(l, _))
+				=> { return Err((*l, DaisyError::ExpectedBool)); },
+
+				_ => { return Ok(None); }
+			}
+		},
+
+		Operator::Equal |
+		Operator::NotEqual => {
+			if args.len() != 2 { panic!() };
+
+			let (l, r) = match (&args[0], &args[1]) {
+				(Expression::Bool(la, a), Expression::Bool(lb, b)) => {
+					(*la + *lb + *op_loc, a == b)
+				},
+
+				(Expression::Quantity(la, a), Expression::Quantity(lb, b)) => {
+					let b = match_level(a, b);
+					check_units(context, *la, a, *lb, &b, *op_loc)?;
+					(*la + *lb + *op_loc, *a == b)
+				},
+
+				(Expression::Bool(_, _), Expression::Quantity(l, _))
+				| (Expression::Quantity(l, _), Expression::Bool(_, _))
+				=> { return Err((*l, DaisyError::ExpectedBool)); },
+
+				_ => { return Ok(None); }
+			};
+
+			let r = if let Operator::Equal = op { r } else { !r };
+			return Ok(Some(Expression::Bool(l, r)));
+		},
+
+		Operator::Less |
+		Operator::LessEqual |
+		Operator::Greater |
+		Operator::GreaterEqual => {
+			if args.len() != 2 { panic!() };
+
+			let Expression::Quantity(la, a) = &args[0] else { return Ok(None); };
+			let Expression::Quantity(lb, b) = &args[1] else { return Ok(None); };
+			let b = match_level(a, b);
+			check_units(context, *la, a, *lb, &b, *op_loc)?;
+
+			// Complex numbers and NaN can't be ordered
+			let Some(o) = a.partial_cmp(&b) else {
+				return Err((*la + *lb + *op_loc, DaisyError::BadMath));
+			};
+
+			let r = match op {
+				Operator::Less => o.is_lt(),
+				Operator::LessEqual => o.is_le(),
+				Operator::Greater => o.is_gt(),
+				Operator::GreaterEqual => o.is_ge(),
+				_ => unreachable!()
+			};
+
+			return Ok(Some(Expression::Bool(*la + *lb + *op_loc, r)));
+		},
+
 		Operator::Negative => {
 			if args.len() != 1 { panic!() };
 			let args = &args[0];
@@ -86,6 +234,13 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 
 			if let Expression::Quantity(la, a) = a {
 				if let Expression::Quantity(lb, b) = b {
+					if a.unit.has_log() || b.unit.has_log() {
+						let Some(r) = a.add_log(b) else {
+							return Err((*la + *lb + *op_loc, DaisyError::IncompatibleUnit));
+						};
+						return Ok(Some(Expression::Quantity(*la + *lb + *op_loc, r)));
+					}
+
 					if !a.unit.compatible_with(&b.unit) {
 						let a = a.convert_to_base().unit;
 						let b = b.convert_to_base().unit;
@@ -123,6 +278,13 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 
 			if let Expression::Quantity(la, a) = a {
 				if let Expression::Quantity(lb, b) = b {
+					if a.unit.has_log() || b.unit.has_log() {
+						let Some(r) = a.sub_log(b) else {
+							return Err((*la + *lb + *op_loc, DaisyError::IncompatibleUnit));
+						};
+						return Ok(Some(Expression::Quantity(*la + *lb + *op_loc, r)));
+					}
+
 					if !a.unit.compatible_with(&b.unit) {
 						let a = a.convert_to_base().unit;
 						let b = b.convert_to_base().unit;
@@ -153,6 +315,34 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 			return Ok(None);
 		},
 
+		Operator::PlusMinus => {
+			if args.len() != 2 { panic!() };
+			let Expression::Quantity(la, a) = &args[0] else { return Ok(None) };
+			let Expression::Quantity(lb, b) = &args[1] else { return Ok(None) };
+			let loc = *la + *lb + *op_loc;
+
+			if a.is_complex() || b.is_complex() || a.scalar.is_interval() || b.scalar.is_interval() {
+				return Err((loc, DaisyError::BadMath));
+			}
+
+			// A unit on only one side applies to both,
+			// so `9.81 ± 0.02 m/s^2` works as expected.
+			let mut a = a.clone();
+			let mut b = b.clone();
+			if a.unitless() {
+				a.set_unit(b.unit.clone());
+			} else if b.unitless() {
+				b.set_unit(a.unit.clone());
+			} else {
+				check_units(context, *la, &a, *lb, &b, *op_loc)?;
+				b = b.convert_to(a.clone()).unwrap();
+			}
+
+			let mut r = Quantity::from_scalar(Scalar::new_uncertain(a.scalar, b.scalar));
+			r.set_unit(a.unit);
+			return Ok(Some(Expression::Quantity(loc, r)));
+		},
+
 
 		Operator::Divide |
 		Operator::DivideLong => {
@@ -162,7 +352,12 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 
 			if let Expression::Quantity(la, a) = a {
 				if let Expression::Quantity(lb, b) = b {
-					if b.is_zero() { return Err((*la + *lb + *op_loc, DaisyError::ZeroDivision)); }
+					// Ratios may only be divided by scalars, and levels can't be scaled at all.
+					if b.unit.has_log() || a.unit.is_log_level() || (a.unit.has_log() && !b.unitless()) {
+						return Err((*la + *lb + *op_loc, DaisyError::IncompatibleUnit));
+					}
+
+					if b.scalar.contains_zero() { return Err((*la + *lb + *op_loc, DaisyError::ZeroDivision)); }
 					return Ok(Some(Expression::Quantity(*la + *lb + *op_loc, a.clone() / b.clone())));
 				}
 			}
@@ -178,6 +373,23 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 
 			if let Expression::Quantity(la, a) = a {
 				if let Expression::Quantity(lb, b) = b {
+					// Logarithmic units may only be multiplied by scalars,
+					// so `3 dB * 2` works but `dB * dB` doesn't.
+					if (a.unit.has_log() && !b.unitless()) || (b.unit.has_log() && !a.unitless()) {
+						return Err((*la + *lb + *op_loc, DaisyError::IncompatibleUnit));
+					}
+
+					// Levels can't be scaled. The only product we allow
+					// is a number next to a bare unit, like `20 dBm`.
+					let bare_a = a.unit.is_log_level() && a.is_one();
+					let bare_b = b.unit.is_log_level() && b.is_one();
+					if {
+						(a.unit.is_log_level() || b.unit.is_log_level()) &&
+						!(matches!(op, Operator::ImplicitMultiply) && (bare_a || bare_b))
+					} {
+						return Err((*la + *lb + *op_loc, DaisyError::IncompatibleUnit));
+					}
+
 					let o = a.clone() * b.clone();
 					return Ok(Some(Expression::Quantity(*la + *lb + *op_loc, o)));
 				}
@@ -199,7 +411,11 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 						return Err((*la + *lb + *op_loc, DaisyError::IncompatibleUnit));
 					}
 
-					if vb <= &Quantity::new_rational(1f64).unwrap() { return Err((*la + *lb + *op_loc, DaisyError::BadMath)); }
+					if va.is_complex() || vb.is_complex() {
+						return Err((*la + *lb + *op_loc, DaisyError::BadMath));
+					}
+
+					if vb.is_zero() { return Err((*la + *lb + *op_loc, DaisyError::ZeroDivision)); }
 					if va.fract() != Quantity::new_rational(0f64).unwrap() { return Err((*la + *lb + *op_loc, DaisyError::BadMath)); }
 					if vb.fract() != Quantity::new_rational(0f64).unwrap() { return Err((*la + *lb + *op_loc, DaisyError::BadMath)); }
 
@@ -219,7 +435,23 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 			let b = &args[1];
 
 			if let Expression::Quantity(la, va) = a {
+				if let Expression::Format(lb, f) = b {
+					let mut n = va.clone();
+					n.format = Some(f.clone());
+
+					// `to dec` always gives a decimal,
+					// even for exact values like `pi` and `sqrt(2)`.
+					if *f == DisplayFormat::Radix(10) { n.scalar = n.scalar.to_numeric(); }
+					return Ok(Some(Expression::Quantity(*la + *lb + *op_loc, n)));
+				}
+
 				if let Expression::Quantity(lb, vb) = b {
+					// Zero, negative, and complex quantities have no level
+					if {
+						vb.unit.is_log_level() && !va.unit.has_log() &&
+						(va.is_complex() || !va.is_positive())
+					} { return Err((*la + *lb + *op_loc, DaisyError::BadMath)); }
+
 					let n = va.clone().convert_to(vb.clone());
 					if n.is_none() {
 						let va = va.convert_to_base().unit;
@@ -255,7 +487,7 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 			let a = &args[0];
 
 			if let Expression::Quantity(l, v) = a {
-				if v.is_negative() { return Err((*l + *op_loc, DaisyError::BadMath)); }
+				if v.unit.has_log() { return Err((*l + *op_loc, DaisyError::IncompatibleUnit)); }
 				let p = v.pow(Quantity::new_rational_from_string("0.5").unwrap());
 				if p.is_nan() {return Err((*l + *op_loc, DaisyError::BadMath));}
 				return Ok(Some(Expression::Quantity(*l, p)));
@@ -274,6 +506,15 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 						return Err((*lb, DaisyError::IncompatibleUnit));
 					}
 
+					if va.unit.has_log() {
+						return Err((*la + *lb + *op_loc, DaisyError::IncompatibleUnit));
+					}
+
+					// Units can't be raised to complex powers
+					if !va.unitless() && vb.is_complex() {
+						return Err((*la + *lb + *op_loc, DaisyError::IncompatibleUnit));
+					}
+
 					if va.is_zero() && vb.is_negative() {
 						return Err((*la + *lb + *op_loc, DaisyError::ZeroDivision));
 					}
@@ -285,7 +526,87 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 			} else { return Ok(None); }
 		},
 
-		Operator::Factorial => {
+		Operator::BitNot => {
+			if args.len() != 1 {panic!()};
+			let args = &args[0];
+
+			if let Expression::Quantity(l, v) = args {
+				let Some(v) = v.to_integer() else {
+					return Err((*l + *op_loc, DaisyError::IntegerOnly(String::from("not"))));
+				};
+
+				return Ok(Some(Expression::Quantity(*l + *op_loc, Quantity::new_rational_from_integer(!v))));
+			} else { return Ok(None); }
+		},
+
+		Operator::BitAnd
+		| Operator::BitOr
+		| Operator::BitXor
+		| Operator::ShiftLeft
+		| Operator::ShiftRight
+		=> {
+			if args.len() != 2 { panic!() };
+			let a = &args[0];
+			let b = &args[1];
+
+			let s = match op {
+				Operator::BitAnd => "and",
+				Operator::BitOr => "or",
+				Operator::BitXor => "xor",
+				Operator::ShiftLeft => "<<",
+				Operator::ShiftRight => ">>",
+				_ => unreachable!()
+			};
+
+			if let Expression::Quantity(la, va) = a {
+				if let Expression::Quantity(lb, vb) = b {
+					let Some(x) = va.to_integer() else {
+						return Err((*la + *op_loc, DaisyError::IntegerOnly(String::from(s))));
+					};
+					let Some(y) = vb.to_integer() else {
+						return Err((*lb + *op_loc, DaisyError::IntegerOnly(String::from(s))));
+					};
+
+					let r = match op {
+						Operator::BitAnd => x & y,
+						Operator::BitOr => x | y,
+						Operator::BitXor => x ^ y,
+						_ => {
+							// Shifts
+							if y.sign() == num::bigint::Sign::Minus {
+								return Err((*lb + *op_loc, DaisyError::BadMath));
+							}
+
+							let y = y.to_usize();
+							if y.is_none() || y.unwrap() > MAX_SHIFT {
+								return Err((*la + *lb + *op_loc, DaisyError::TooBig));
+							}
+
+							if let Operator::ShiftLeft = op { x << y.unwrap() } else { x >> y.unwrap() }
+						}
+					};
+
+					return Ok(Some(Expression::Quantity(*la + *lb + *op_loc, Quantity::new_rational_from_integer(r))));
+				} else { return Ok(None); }
+			} else { return Ok(None); }
+		},
+
+		Operator::Dice => {
+			if args.len() != 2 { panic!() };
+			let a = &args[0];
+			let b = &args[1];
+
+			if let Expression::Quantity(la, va) = a {
+				if let Expression::Quantity(lb, vb) = b {
+					let r = roll_dice(context, "d", (*la, va), (*lb, vb))?;
+					return Ok(Some(Expression::Quantity(*la + *lb + *op_loc, r)));
+				} else { return Ok(None); }
+			} else { return Ok(None); }
+		},
+
+		Operator::Factorial
+		| Operator::DoubleFactorial
+		=> {
 			if args.len() != 1 {panic!()};
 			let args = &args[0];
 
@@ -295,18 +616,39 @@ pub fn eval_operator(context: &mut Context, g: &Expression) -> Result<Option<Exp
 					return Err((*l + *op_loc, DaisyError::IncompatibleUnit));
 				}
 
-				if !v.fract().is_zero() { return Err((*l + *op_loc, DaisyError::BadMath)); }
-				if v > &Quantity::new_rational(50_000f64).unwrap() { return Err((*l + *op_loc, DaisyError::TooBig)); }
+				if v.is_complex() { return Err((*l + *op_loc, DaisyError::BadMath)); }
 
-				let mut prod = Quantity::new_rational(1f64).unwrap();
-				let mut u = v.clone();
-				while u > Quantity::new_rational(0f64).unwrap() {
-					prod *= u.clone();
-					u = u - Quantity::new_rational(1f64).unwrap();
-				}
+				let Some(n) = v.to_integer() else {
+					if let Operator::DoubleFactorial = op {
+						return Err((*l + *op_loc, DaisyError::IntegerOnly(String::from("!!"))));
+					}
+
+					// Non-integers use the gamma function
+					let r = (v.clone() + Quantity::new_rational(1f64).unwrap()).gamma();
+					if r.is_nan() { return Err((*l + *op_loc, DaisyError::BadMath)); }
+					return Ok(Some(Expression::Quantity(*l + *op_loc, r)));
+				};
+
+				let r = match op {
+					// (-1)!! is 1, and makes odd double factorials consistent
+					Operator::DoubleFactorial if n == BigInt::from(-1) => BigInt::from(1),
+					_ if n.is_negative() => { return Err((*l + *op_loc, DaisyError::BadMath)); },
+					_ => {
+						let n = n.to_usize();
+						if n.is_none() || n.unwrap() > context.config.max_factorial {
+							return Err((*l + *op_loc, DaisyError::TooBig));
+						}
+
+						if let Operator::Factorial = op {
+							numtheory::factorial(n.unwrap())
+						} else {
+							numtheory::double_factorial(n.unwrap())
+						}
+					}
+				};
 
-				return Ok(Some(Expression::Quantity(*l + *op_loc, prod)));
+				return Ok(Some(Expression::Quantity(*l + *op_loc, Quantity::new_rational_from_integer(r))));
 			} else { return Ok(None); }
 		}
 	};
-}
\ No newline at end of file
+}
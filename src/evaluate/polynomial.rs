@@ -0,0 +1,291 @@
+use std::cmp::Ordering;
+use crate::parser::Expression;
+use crate::parser::Operator;
+use crate::parser::Function;
+use crate::parser::LineLocation;
+use crate::quantity::Quantity;
+use crate::quantity::Scalar;
+use crate::errors::DaisyError;
+use crate::context::Context;
+use super::function::{unpack_args, check_arity};
+use super::matrix::{zero, add, is_evaluated, to_vector, from_vector};
+use super::calculus::round_to;
+
+// Polynomials are tuples of coefficients, highest power first.
+// (1, -3, 2) is x^2 - 3x + 2, and a plain number is a constant.
+
+
+// The most Durand-Kerner iterations we'll do.
+// Repeated roots converge slowly, so we return
+// whatever we have when we reach this.
+const MAX_ITERATIONS: usize = 500;
+
+// The most continued fraction terms we'll try
+// when looking for a rational root
+const MAX_CONVERGENTS: usize = 64;
+
+
+fn float(f: f64) -> Scalar { Scalar::new_float(f).unwrap() }
+fn rational(f: f64) -> Scalar { Scalar::new_rational(f).unwrap() }
+
+fn max(a: Scalar, b: Scalar) -> Scalar { if a > b { a } else { b } }
+
+// Remove leading zeros. The zero polynomial is (0).
+fn trim(p: Vec<Quantity>) -> Vec<Quantity> {
+	let n = p.iter().take_while(|x| x.is_zero()).count();
+	let p: Vec<Quantity> = p.into_iter().skip(n).collect();
+	if p.len() == 0 { return vec![zero()]; }
+	return p;
+}
+
+// The coefficients of a polynomial argument
+fn to_poly(e: &Expression, loc: LineLocation) -> Result<Vec<Quantity>, (LineLocation, DaisyError)> {
+	return match e {
+		Expression::Quantity(_, q) => Ok(vec![q.clone()]),
+		Expression::Tuple(_, v) => {
+			let Some(p) = to_vector(v) else { return Err((loc, DaisyError::BadDimensions)); };
+			Ok(trim(p))
+		},
+		_ => unreachable!()
+	};
+}
+
+// Polynomials with one coefficient are plain quantities
+fn from_poly(l: LineLocation, p: Vec<Quantity>) -> Expression {
+	if p.len() == 1 { return Expression::Quantity(l, p[0].clone()); }
+	return from_vector(l, p);
+}
+
+fn horner(p: &[Scalar], x: &Scalar) -> Scalar {
+	let mut r = rational(0f64);
+	for c in p { r = r * x.clone() + c.clone(); }
+	return r;
+}
+
+// Divide p by (x - r), dropping the remainder
+fn deflate(p: &[Scalar], r: &Scalar) -> Vec<Scalar> {
+	let mut q = Vec::with_capacity(p.len() - 1);
+	let mut c = rational(0f64);
+	for a in &p[..p.len() - 1] {
+		c = c * r.clone() + a.clone();
+		q.push(c.clone());
+	}
+	return q;
+}
+
+
+// If x is close to a rational root of p, return that root.
+// We try each convergent of x's continued fraction, checking
+// each one exactly. p must have rational coefficients.
+fn rational_root(p: &[Scalar], x: &Scalar) -> Option<Scalar> {
+	if x.im().abs() > float(1e-6) * max(x.abs(), float(1f64)) { return None; }
+	let mut v = x.re().to_rational()?;
+
+	let (mut h0, mut h1) = (rational(0f64), rational(1f64));
+	let (mut k0, mut k1) = (rational(1f64), rational(0f64));
+	for _ in 0..MAX_CONVERGENTS {
+		let a = v.floor();
+		let h = a.clone() * h1.clone() + h0;
+		let k = a.clone() * k1.clone() + k0;
+		let c = h.clone() / k.clone();
+		if horner(p, &c).is_zero() { return Some(c); }
+
+		let f = v - a;
+		if f.is_zero() { return None; }
+		v = rational(1f64) / f;
+		(h0, h1, k0, k1) = (h1, h, k1, k);
+	}
+	return None;
+}
+
+// Find all roots of p at once with the Durand-Kerner method
+fn durand_kerner(p: &[Scalar], tol: &Scalar) -> Vec<Scalar> {
+	let n = p.len() - 1;
+	let p: Vec<Scalar> = p.iter().map(|c| c.clone() / p[0].clone()).collect();
+
+	// The usual starting points, powers of a number
+	// that isn't real and isn't a root of unity.
+	let seed = Scalar::new_complex(float(0.4), float(0.9));
+	let mut z = Vec::with_capacity(n);
+	let mut w = rational(1f64);
+	for _ in 0..n {
+		z.push(w.clone());
+		w = w * seed.clone();
+	}
+
+	for _ in 0..MAX_ITERATIONS {
+		let mut done = true;
+		for k in 0..n {
+			let mut d = rational(1f64);
+			for j in 0..n { if j != k { d = d * (z[k].clone() - z[j].clone()); } }
+			if d.is_zero() { continue; }
+
+			let step = horner(&p, &z[k]) / d;
+			z[k] = z[k].clone() - step.clone();
+			if step.abs() > tol.clone() * max(z[k].abs(), float(1f64)) { done = false; }
+		}
+		if done { break; }
+	}
+
+	// Our roots are only good to `tol`. Rounding them makes
+	// real roots real and conjugate pairs exact conjugates.
+	return z.into_iter().map(|x| {
+		let t = tol.clone() * max(x.abs(), float(1f64));
+		Scalar::new_complex(round_to(x.re(), &t), round_to(x.im(), &t))
+	}).collect();
+}
+
+// All roots of p, in order of real part, then imaginary part.
+// Rational roots of polynomials with rational coefficients are exact.
+fn roots(p: Vec<Scalar>, tol: &Scalar) -> Vec<Scalar> {
+	let mut p = p;
+	let mut out = Vec::new();
+
+	while p.len() > 1 && p[p.len() - 1].is_zero() {
+		p.pop();
+		out.push(rational(0f64));
+	}
+
+	// Find roots numerically, then look for rationals near them.
+	// Every rational root we find lowers p's degree by one.
+	// Degrees one and two are solved exactly below.
+	if p.iter().all(|x| x.is_rational()) {
+		'outer: while p.len() > 3 {
+			for x in durand_kerner(&p, tol) {
+				if let Some(r) = rational_root(&p, &x) {
+					p = deflate(&p, &r);
+					out.push(r);
+					continue 'outer;
+				}
+			}
+			break;
+		}
+	}
+
+	match p.len() {
+		1 => {},
+		2 => { out.push(-p[1].clone() / p[0].clone()); },
+		3 => {
+			let (a, b, c) = (p[0].clone(), p[1].clone(), p[2].clone());
+			let d = b.clone() * b.clone() - rational(4f64) * a.clone() * c.clone();
+			let s = d.pow(rational(0.5));
+
+			// Exact and complex roots can use the usual formula.
+			// Otherwise, -b ± s can cancel badly when b*b is much larger
+			// than 4ac, so we find the larger root first and get the other
+			// from r1 * r2 = c/a.
+			if s.is_rational() || s.is_symbolic() || s.is_complex() {
+				let a2 = rational(2f64) * a;
+				out.push((-b.clone() - s.clone()) / a2.clone());
+				out.push((-b + s) / a2);
+			} else {
+				let s = if b.is_negative() { -s } else { s };
+				let q = -(b + s) / rational(2f64);
+				out.push(q.clone() / a);
+				out.push(c / q);
+			}
+		},
+		_ => { out.extend(durand_kerner(&p, tol)); }
+	}
+
+	out.sort_by(|a, b| {
+		a.re().partial_cmp(&b.re()).unwrap_or(Ordering::Equal)
+			.then(a.im().partial_cmp(&b.im()).unwrap_or(Ordering::Equal))
+	});
+	return out;
+}
+
+
+pub fn eval_polynomial_function(context: &mut Context, g: &Expression) -> Result<Option<Expression>, (LineLocation, DaisyError)> {
+	let Expression::Operator(loc, Operator::Function(f), args) = g else { unreachable!() };
+
+	let args = unpack_args(args);
+	for a in &args { if !is_evaluated(a)? { return Ok(None); } }
+	let mut lall = *loc;
+	for a in &args { lall += a.get_linelocation(); }
+
+	if let Function::PolyRoots = f {
+		check_arity(&f.to_string(), f.arity(), *loc, &args)?;
+
+		// `polyroots(p)` with a tuple p is parsed
+		// the same way as `polyroots(1, -3, 2)`.
+		let p = match &args[..] {
+			[x] => to_poly(x, lall)?,
+			_ => {
+				let Some(p) = to_vector(args.iter().copied()) else {
+					return Err((lall, DaisyError::BadDimensions));
+				};
+				trim(p)
+			}
+		};
+
+		if p.len() == 1 { return Err((lall, DaisyError::ConstantPolynomial)); }
+
+		let mut s = Vec::with_capacity(p.len());
+		for x in p {
+			if !x.unitless() { return Err((lall, DaisyError::IncompatibleUnit)); }
+			s.push(x.scalar);
+		}
+
+		let tol = float(context.config.tolerance);
+		let r = roots(s, &tol).into_iter().map(Quantity::from_scalar).collect();
+		return Ok(Some(from_poly(lall, r)));
+	}
+
+	check_arity(&f.to_string(), f.arity(), *loc, &args)?;
+	let p = to_poly(args[0], lall)?;
+
+	match f {
+		Function::PolyVal => {
+			let Expression::Quantity(_, x) = args[1] else {
+				return Err((lall, DaisyError::BadDimensions));
+			};
+
+			let mut r = zero();
+			for c in p { r = add(r * x.clone(), c, lall)?; }
+			return Ok(Some(Expression::Quantity(lall, r)));
+		},
+
+		Function::PolyMul => {
+			let q = to_poly(args[1], lall)?;
+
+			let mut r = vec![zero(); p.len() + q.len() - 1];
+			for (i, a) in p.iter().enumerate() {
+				for (j, b) in q.iter().enumerate() {
+					r[i + j] = add(r[i + j].clone(), a.clone() * b.clone(), lall)?;
+				}
+			}
+			return Ok(Some(from_poly(lall, r)));
+		},
+
+		Function::PolyDiv | Function::PolyRem => {
+			let q = to_poly(args[1], lall)?;
+			if q[0].is_zero() { return Err((lall, DaisyError::ZeroDivision)); }
+
+			// Long division. After this, the last q.len() - 1
+			// coefficients of r are the remainder.
+			let mut r = p.clone();
+			let mut d = Vec::new();
+			if p.len() >= q.len() {
+				for i in 0..(p.len() - q.len() + 1) {
+					let c = r[i].clone() / q[0].clone();
+					for (j, b) in q.iter().enumerate().skip(1) {
+						r[i + j] = add(r[i + j].clone(), -(c.clone() * b.clone()), lall)?;
+					}
+					d.push(c);
+				}
+			}
+
+			if let Function::PolyDiv = f {
+				if d.len() == 0 { d.push(zero()); }
+				return Ok(Some(from_poly(lall, d)));
+			}
+
+			let n = p.len().min(q.len() - 1);
+			let r = r.into_iter().skip(p.len() - n).collect();
+			return Ok(Some(from_poly(lall, trim(r))));
+		},
+
+		_ => unreachable!()
+	}
+}
@@ -4,16 +4,20 @@ pub mod quantity;
 
 use crate::parser::substitute;
 use crate::parser::LineLocation;
+use crate::quantity::UserUnit;
 
 
 mod context;
+mod definitions;
 mod formattedtext;
 mod errors;
 mod evaluate;
 mod promptbuffer;
+mod rng;
 
 pub use crate::formattedtext::FormattedText;
 pub use crate::context::Context;
+pub use crate::definitions::load_definitions;
 pub use crate::errors::DaisyError;
 pub use crate::evaluate::evaluate;
 pub use crate::promptbuffer::PromptBuffer;
@@ -116,10 +120,12 @@ pub fn do_string(
 	s: &String
 ) -> Result<FormattedText, FormattedText> {
 
+	context.activate();
+
 	let r: (LineLocation, DaisyError);
 	if command::is_command(s) {
 		return Ok(command::do_command(context, s));
-	} else if s.contains("=") {
+	} else if find_assignment(s).is_some() {
 		let x = do_assignment(context, s);
 		match x {
 			Ok(t) => { return Ok(t) },
@@ -155,6 +161,19 @@ pub fn do_string(
 	return Err(t);
 }
 
+// Print a parsed expression to echo the user's input.
+// Output settings (like fraction mode and notation) only apply
+// to results, so we print this with the default ones and switch back.
+fn display_input(context: &mut Context, g: &parser::Expression) -> String {
+	let input_config = context.config.for_input();
+	let config = std::mem::replace(&mut context.config, input_config);
+	context.config.check();
+	let s = g.display(context);
+	context.config = config;
+	context.activate();
+	return s;
+}
+
 // Handle a simple evaluation string.
 // Returns a FormattedText with output that should be printed.
 #[inline(always)]
@@ -171,19 +190,31 @@ fn do_expression(
 	// Display parsed string
 	output.push(&format!(
 		" [s]=>[n] {}\n\n",
-		g.display(context)
+		display_input(context, &g)
 	));
 
-	// Display result
+	// Display result.
+	// Multi-line results (like matrices) are lined up after the `=`.
 	output.push(&format!(
 		"  [r]=[n] {}\n\n",
-		g_evaluated.display_outer(context),
+		g_evaluated.display_outer(context).replace("\n", "\n    "),
 	));
 
 	return Ok((output, g_evaluated));
 }
 
 
+// Find the `=` in a definition string, like `x = 5`.
+// `==`, `!=`, `<=`, and `>=` are comparisons, not definitions.
+fn find_assignment(s: &str) -> Option<usize> {
+	let b = s.as_bytes();
+	return (0..b.len()).find(|i| {
+		b[*i] == b'=' &&
+		!(*i > 0 && b"=!<>".contains(&b[*i - 1])) &&
+		!(*i + 1 < b.len() && b[*i + 1] == b'=')
+	});
+}
+
 // Handle a variable or function definition string.
 // Returns a FormattedText with output that should be printed.
 #[inline(always)]
@@ -194,13 +225,8 @@ fn do_assignment(
 
 	let mut output = FormattedText::new("".to_string());
 
-	let parts = s.split("=").collect::<Vec<&str>>();
-	if parts.len() != 2 {
-		return Err((
-			LineLocation::new_zero(),
-			DaisyError::Syntax
-		));
-	}
+	let i = find_assignment(s).unwrap();
+	let parts = [&s[..i], &s[i+1..]];
 
 	// Index of first non-whitespace character in left
 	// (relative to whole prompt)
@@ -225,10 +251,68 @@ fn do_assignment(
 	let right = substitute(context, &parts[1].trim().to_string());
 	let is_function = left.contains("(");
 
+	// Unit definitions look like `unit smoot = 1.7018 m`.
+	// Units that can take SI prefixes are defined with `prefixed unit`.
+	let words: Vec<&str> = left.split_whitespace().collect();
+	let unit = match words[..] {
+		["unit", n] => Some((n.to_string(), false)),
+		["prefixed", "unit", n] => Some((n.to_string(), true)),
+		_ => None
+	};
+
 	// The order of methods below is a bit odd.
 	// This is intentional, since we want to check a definition's
 	// variable name before even attempting to parse its content.
-	if is_function {
+	if let Some((name, prefixes)) = unit {
+		if !context.valid_unit(&name) {
+			return Err((
+				LineLocation{ pos: starting_left, len: left.chars().count() },
+				DaisyError::BadUnit
+			));
+		}
+
+		// Parse right hand side
+		let g = parser::parse(context, &right);
+		let Ok(g) = g else {
+			let Err((l, e)) = g else { unreachable!() };
+			return Err((
+				LineLocation{ pos: l.pos + starting_right, len: l.len},
+				e
+			));
+		};
+
+		// Display parsed string
+		output.push(&format!(
+			" [t]=>[n] {left} = {}\n\n",
+			display_input(context, &g)
+		));
+
+		// Evaluate expression
+		let g_evaluated = evaluate::evaluate(context, &g);
+		let Ok(g_evaluated) = g_evaluated else {
+			let Err((l, e)) = g_evaluated else { unreachable!() };
+			return Err((
+				LineLocation{ pos: l.pos + starting_right, len: l.len},
+				e
+			));
+		};
+
+		// Units must be positive real numbers
+		let q = match g_evaluated {
+			parser::Expression::Quantity(_, q) if {
+				q.scalar.to_rational().is_some() &&
+				q.is_positive()
+			} => q,
+			_ => {
+				return Err((
+					LineLocation{ pos: starting_right, len: right.chars().count() },
+					DaisyError::BadUnitValue
+				));
+			}
+		};
+
+		context.push_unit(UserUnit::new(name, q, prefixes)).unwrap();
+	} else if is_function {
 		let mut mode = 0;
 		let mut name = String::new();
 		let mut args = String::new();
@@ -297,9 +381,24 @@ fn do_assignment(
 			}
 		}
 
+		// Define this function before parsing its body,
+		// so that it can call itself. If anything fails,
+		// we put back whatever had this name before.
+		let old_variable = context.get_variables().get(&name).cloned();
+		let old_function = context.get_functions().get(&name).cloned();
+		let restore = |context: &mut Context| {
+			context.delete(&name).unwrap();
+			if let Some(v) = old_variable.clone() { context.push_variable(name.clone(), v).unwrap(); }
+			if let Some((a, f)) = old_function.clone() { context.push_function(name.clone(), a, f).unwrap(); }
+		};
+
+		let placeholder = parser::Expression::Tuple(LineLocation{ pos: 0, len: 0 }, std::collections::VecDeque::new());
+		context.push_function(name.clone(), args.clone(), placeholder).unwrap();
+
 		// Parse right hand side
 		let g = parser::parse(context, &right);
 		let Ok(g) = g else {
+			restore(context);
 			let Err((l, e)) = g else { unreachable!() };
 			return Err((
 				LineLocation{ pos: l.pos + starting_right, len: l.len},
@@ -310,14 +409,16 @@ fn do_assignment(
 		// Display parsed string
 		output.push(&format!(
 			" [s]=>[n] {left} = {}\n\n",
-			g.display(context)
+			display_input(context, &g)
 		));
 
 		// Evaluate expression with shadow variables
+		context.push_function(name.clone(), args.clone(), g.clone()).unwrap();
 		for a in &args { context.add_shadow(a.to_string(), None);}
 		let g_evaluated = evaluate::evaluate(context, &g);
 		context.clear_shadow();
 		let Ok(_g_evaluated) = g_evaluated else {
+			restore(context);
 			let Err((l, e)) = g_evaluated else { unreachable!() };
 			return Err((
 				LineLocation{ pos: l.pos + starting_right, len: l.len},
@@ -325,11 +426,10 @@ fn do_assignment(
 			));
 		};
 
-		// We could push g_evaluated instead, but an un-evaluated string
+		// We pushed g instead of g_evaluated: an un-evaluated string
 		// makes the 'vars' command prettier.
 		//
 		// We still need to evaluate g above, though, to make sure it works.
-		context.push_function(name, args, g).unwrap();
 	} else {
 
 		if !context.valid_varible(&left) {
@@ -352,7 +452,7 @@ fn do_assignment(
 		// Display parsed string
 		output.push(&format!(
 			" [t]=>[n] {left} = {}\n\n",
-			g.display(context)
+			display_input(context, &g)
 		));
 
 		// Evaluate expression
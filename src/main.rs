@@ -1,6 +1,8 @@
 use std::io::stdout;
 use std::io::stdin;
 use std::env;
+use std::fs;
+use std::path::PathBuf;
 
 use termion::{
 	event::Key,
@@ -14,11 +16,34 @@ use daisycalc::command;
 use daisycalc::Context;
 use daisycalc::FormattedText;
 use daisycalc::do_string;
+use daisycalc::load_definitions;
 
 #[cfg(test)]
 mod tests;
 
 
+// Every toml file in daisy's config directory, in order.
+// Unit and constant definitions are loaded from these.
+fn config_files() -> Vec<PathBuf> {
+	let dir = match env::var_os("XDG_CONFIG_HOME") {
+		Some(d) => PathBuf::from(d),
+		None => match env::var_os("HOME") {
+			Some(h) => PathBuf::from(h).join(".config"),
+			None => return Vec::new()
+		}
+	};
+
+	let Ok(r) = fs::read_dir(dir.join("daisy")) else { return Vec::new() };
+	let mut v: Vec<PathBuf> = r
+		.filter_map(|e| e.ok())
+		.map(|e| e.path())
+		.filter(|p| p.extension().is_some_and(|x| x == "toml"))
+		.collect();
+
+	v.sort();
+	return v;
+}
+
 
 #[inline(always)]
 pub fn main() -> Result<(), std::io::Error> {
@@ -77,11 +102,43 @@ pub fn main() -> Result<(), std::io::Error> {
 		context.config.enable_super_powers = false;
 	} else if args.iter().any(|s| s == "--nooneover") {
 		context.config.enable_one_over_power = false;
+	} else if args.iter().any(|s| s == "--polar") {
+		context.config.enable_polar = true;
 	}
 
 	context.config.check();
 
 
+	// Load definitions from the config directory,
+	// then from every `--units FILE`.
+	let mut files = config_files();
+	let mut a = args.iter().skip(1);
+	while let Some(s) = a.next() {
+		if s != "--units" { continue; }
+		let Some(f) = a.next() else {
+			let t = FormattedText::new("[e]Error:[n] [c]--units[n] needs a file\n".to_string());
+			t.write(&context, &mut stdout)?;
+			return Ok(());
+		};
+		files.push(PathBuf::from(f));
+	}
+
+	for f in files {
+		let r = match fs::read_to_string(&f) {
+			Ok(s) => load_definitions(&mut context, &s),
+			Err(e) => Err(e.to_string())
+		};
+
+		if let Err(e) = r {
+			let t = FormattedText::new(format!(
+				"[e]Error:[n] {}: {e}\n", f.display()
+			));
+			t.write(&context, &mut stdout)?;
+			return Ok(());
+		}
+	}
+
+
 	'outer: loop {
 
 		let t = pb.write_prompt(&mut context);
@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 use crate::quantity::Quantity;
+use crate::quantity::DisplayFormat;
 use crate::context::Context;
 
 use super::Operator;
@@ -13,7 +14,7 @@ use super::super::LineLocation;
 pub enum Expression {
 	// Meaning of `LineLocation`:
 	//
-	// For Variables, Constants, Quantities, Tuples:
+	// For Variables, Constants, Quantities, Booleans, Tuples:
 	// If this expression was parsed, LineLocation is what part of the prompt was parsed to get this expression
 	// If this expression is the result of a calculation, LineLocaion is the sum of the LineLocations of
 	//	all expressions used to make it. In other words, it points to the part of the prompt that was evaluated
@@ -30,17 +31,24 @@ pub enum Expression {
 
 	Variable(LineLocation, String),
 	Quantity(LineLocation, Quantity),
+	Bool(LineLocation, bool),
 	Constant(LineLocation, Constant),
 	Operator(LineLocation, Operator, VecDeque<Expression>),
 	Tuple(LineLocation, VecDeque<Expression>),
+
+	// An output format, like `hex`.
+	// Only valid as the right side of `to`.
+	Format(LineLocation, DisplayFormat),
 }
 
 impl Expression {
 	pub fn display(&self, context: &Context) -> String {
 		match self {
 			Expression::Quantity(_, v) => v.display(context),
+			Expression::Bool(_, b) => b.to_string(),
 			Expression::Constant(_, c) => c.to_string(),
 			Expression::Variable(_, s) => s.clone(),
+			Expression::Format(_, f) => f.to_string(),
 			Expression::Operator(_, o,a) => o.display(context, a),
 			Expression::Tuple(_, v) => {
 				format!("({})",
@@ -54,16 +62,62 @@ impl Expression {
 	}
 }
 
+// Matrices (tuples of equal-length tuples) are shown as a grid,
+// with one row per line.
+// Returns None if `v` isn't a matrix.
+fn display_grid(context: &Context, v: &VecDeque<Expression>) -> Option<String> {
+	let mut rows: Vec<Vec<String>> = Vec::new();
+	for r in v {
+		let Expression::Tuple(_, r) = r else { return None; };
+		if r.len() == 0 || r.iter().any(|x| matches!(x, Expression::Tuple(_, _))) { return None; }
+		if rows.len() > 0 && r.len() != rows[0].len() { return None; }
+		rows.push(r.iter().map(|x| x.display_outer(context)).collect());
+	}
+	if rows.len() == 0 { return None; }
+
+	// Right-align each column
+	let mut width = vec![0; rows[0].len()];
+	for r in &rows {
+		for (i, s) in r.iter().enumerate() {
+			width[i] = width[i].max(s.chars().count());
+		}
+	}
+
+	let n = rows.len();
+	let lines = rows.iter().enumerate().map(|(i, r)| {
+		let (a, b) = {
+			if n == 1 { ("[", "]") }
+			else if i == 0 { ("⎡", "⎤") }
+			else if i == n - 1 { ("⎣", "⎦") }
+			else { ("⎢", "⎥") }
+		};
+
+		let r = r.iter()
+			.enumerate()
+			.map(|(j, s)| format!("{s:>w$}", w = width[j]))
+			.collect::<Vec<String>>()
+			.join("  ");
+
+		format!("{a} {r} {b}")
+	});
+
+	return Some(lines.collect::<Vec<String>>().join("\n"));
+}
+
 impl Expression {
 	// This is called only when this is the outermost Expression.
 	// This sometimes leads to different--usually more verbose--behavior.
 	pub fn display_outer(&self, context: &Context) -> String {
 		match self {
 			Expression::Quantity(_, v) => v.display_outer(context),
+			Expression::Bool(_, b) => b.to_string(),
 			Expression::Constant(_, c) => c.to_string(),
 			Expression::Variable(_, s) => s.clone(),
+			Expression::Format(_, f) => f.to_string(),
 			Expression::Operator(_, o,a) => o.display(context, a),
 			Expression::Tuple(_, v) => {
+				if let Some(s) = display_grid(context, v) { return s; }
+
 				format!("({})",
 					v.iter()
 						.map(|x| x.display(context))
@@ -168,10 +222,12 @@ impl Expression {
 	pub fn get_linelocation(&self) -> LineLocation {
 		match self {
 			Expression::Quantity(l, _)
+			| Expression::Bool(l, _)
 			| Expression::Constant(l, _)
 			| Expression::Variable(l, _)
 			| Expression::Operator(l, _,_)
 			| Expression::Tuple(l, _)
+			| Expression::Format(l, _)
 			=> { *l }
 		}
 	}
@@ -179,10 +235,12 @@ impl Expression {
 	pub fn set_linelocation(&mut self, loc: &LineLocation) {
 		match self {
 			Expression::Quantity(l, _) => { *l = *loc },
+			Expression::Bool(l, _) => { *l = *loc },
 			Expression::Constant(l, _) => { *l = *loc },
 			Expression::Variable(l, _) => { *l = *loc },
 			Expression::Operator(l, _,_) => { *l = *loc },
 			Expression::Tuple(l, _) => { *l = *loc },
+			Expression::Format(l, _) => { *l = *loc },
 		}
 	}
 }
\ No newline at end of file
@@ -1,3 +1,43 @@
+/// How many arguments a function takes.
+#[derive(Debug)]
+#[derive(Copy, Clone)]
+pub enum Arity {
+	Fixed(usize),
+	Optional(usize, usize), // Between min and max, inclusive
+	Variadic(usize) // At least this many
+}
+
+impl Arity {
+	pub fn accepts(&self, n: usize) -> bool {
+		match self {
+			Arity::Fixed(a) => n == *a,
+			Arity::Optional(a, b) => n >= *a && n <= *b,
+			Arity::Variadic(a) => n >= *a
+		}
+	}
+
+	// The largest number of arguments we accept
+	pub fn max(&self) -> Option<usize> {
+		match self {
+			Arity::Fixed(a) => Some(*a),
+			Arity::Optional(_, b) => Some(*b),
+			Arity::Variadic(_) => None
+		}
+	}
+}
+
+impl ToString for Arity {
+	fn to_string(&self) -> String {
+		match self {
+			Arity::Fixed(a) => format!("{a}"),
+			Arity::Optional(a, b) if *b == a + 1 => format!("{a} or {b}"),
+			Arity::Optional(a, b) => format!("{a} to {b}"),
+			Arity::Variadic(a) => format!("at least {a}")
+		}
+	}
+}
+
+
 #[derive(Debug)]
 #[derive(Copy, Clone)]
 pub enum Function {
@@ -10,7 +50,54 @@ pub enum Function {
 	Round,
 
 	NaturalLog,
-	TenLog,
+	TenLog, // Also takes an optional base
+
+	Atan2,
+	Hypot,
+	Min,
+	Max,
+	Root,
+	Clamp,
+
+	If, // Only evaluates the branch it takes
+
+	Integrate,
+	Derive,
+	Solve,
+
+	Sum,
+	Product,
+	Mean,
+	Median,
+	Mode,
+	Variance,
+	Stdev,
+	Range,
+	Percentile,
+
+	Gamma,
+	LGamma,
+	Beta,
+	Erf,
+	Erfc,
+
+	NCr,
+	NPr,
+	Multinomial,
+	BinomPmf,
+	BinomCdf,
+	Dice,
+	Rand,
+
+	Gcd,
+	Lcm,
+	ModInv,
+	ModPow,
+	Rationalize,
+	IsPrime,
+	Factor,
+	Totient,
+	NextPrime,
 
 	Sin,
 	Cos,
@@ -32,6 +119,26 @@ pub enum Function {
 	Sech,
 	Coth,
 
+	Re,
+	Im,
+	Arg,
+	Conj,
+
+	Det,
+	Inverse,
+	Transpose,
+	Dot,
+	Cross,
+	Norm,
+
+	PolyRoots,
+	PolyVal,
+	PolyMul,
+	PolyDiv,
+	PolyRem,
+
+	Interval,
+
 	NoUnit,
 	ToBase,
 	FromCelsius,
@@ -52,6 +159,46 @@ impl ToString for Function {
 			Function::Round => { String::from("round") },
 			Function::NaturalLog => { String::from("ln") },
 			Function::TenLog => { String::from("log") },
+			Function::Atan2 => { String::from("atan2") },
+			Function::Hypot => { String::from("hypot") },
+			Function::Min => { String::from("min") },
+			Function::Max => { String::from("max") },
+			Function::Root => { String::from("root") },
+			Function::Clamp => { String::from("clamp") },
+			Function::If => { String::from("if") },
+			Function::Integrate => { String::from("integrate") },
+			Function::Derive => { String::from("derive") },
+			Function::Solve => { String::from("solve") },
+			Function::Sum => { String::from("sum") },
+			Function::Product => { String::from("product") },
+			Function::Mean => { String::from("mean") },
+			Function::Median => { String::from("median") },
+			Function::Mode => { String::from("mode") },
+			Function::Variance => { String::from("variance") },
+			Function::Stdev => { String::from("stdev") },
+			Function::Range => { String::from("range") },
+			Function::Percentile => { String::from("percentile") },
+			Function::Gamma => { String::from("gamma") },
+			Function::LGamma => { String::from("lgamma") },
+			Function::Beta => { String::from("beta") },
+			Function::Erf => { String::from("erf") },
+			Function::Erfc => { String::from("erfc") },
+			Function::NCr => { String::from("nCr") },
+			Function::NPr => { String::from("nPr") },
+			Function::Multinomial => { String::from("multinomial") },
+			Function::BinomPmf => { String::from("binompmf") },
+			Function::BinomCdf => { String::from("binomcdf") },
+			Function::Dice => { String::from("dice") },
+			Function::Rand => { String::from("rand") },
+			Function::Gcd => { String::from("gcd") },
+			Function::Lcm => { String::from("lcm") },
+			Function::ModInv => { String::from("modinv") },
+			Function::ModPow => { String::from("modpow") },
+			Function::Rationalize => { String::from("rationalize") },
+			Function::IsPrime => { String::from("isprime") },
+			Function::Factor => { String::from("factor") },
+			Function::Totient => { String::from("totient") },
+			Function::NextPrime => { String::from("nextprime") },
 			Function::Sin => { String::from("sin") },
 			Function::Cos => { String::from("cos") },
 			Function::Tan => { String::from("tan") },
@@ -70,6 +217,22 @@ impl ToString for Function {
 			Function::Csch => { String::from("csch") },
 			Function::Sech => { String::from("sech") },
 			Function::Coth => { String::from("coth") },
+			Function::Re => { String::from("re") },
+			Function::Im => { String::from("im") },
+			Function::Arg => { String::from("arg") },
+			Function::Conj => { String::from("conj") },
+			Function::Det => { String::from("det") },
+			Function::Inverse => { String::from("inv") },
+			Function::Transpose => { String::from("transpose") },
+			Function::Dot => { String::from("dot") },
+			Function::Cross => { String::from("cross") },
+			Function::Norm => { String::from("norm") },
+			Function::PolyRoots => { String::from("polyroots") },
+			Function::PolyVal => { String::from("polyval") },
+			Function::PolyMul => { String::from("polymul") },
+			Function::PolyDiv => { String::from("polydiv") },
+			Function::PolyRem => { String::from("polyrem") },
+			Function::Interval => { String::from("interval") },
 			Function::NoUnit => { String::from("nounit") },
 			Function::ToBase => { String::from("tobase") },
 			Function::FromCelsius => { String::from("fromcelsius") },
@@ -84,6 +247,61 @@ impl ToString for Function {
 }
 
 impl Function {
+	pub fn arity(&self) -> Arity {
+		match self {
+			Function::TenLog => Arity::Optional(1, 2),
+			Function::Integrate => Arity::Optional(3, 4),
+			Function::Derive => Arity::Optional(2, 3),
+			Function::Solve => Arity::Optional(2, 4),
+			Function::Rand => Arity::Fixed(0),
+			Function::Atan2
+			| Function::Hypot
+			| Function::Root
+			| Function::ModInv
+			| Function::Rationalize
+			| Function::Beta
+			| Function::NCr
+			| Function::NPr
+			| Function::Dice
+			| Function::Dot
+			| Function::Cross
+			| Function::PolyVal
+			| Function::PolyMul
+			| Function::PolyDiv
+			| Function::PolyRem
+			| Function::Interval
+			=> Arity::Fixed(2),
+			Function::Clamp
+			| Function::If
+			| Function::ModPow
+			| Function::BinomPmf
+			| Function::BinomCdf
+			=> Arity::Fixed(3),
+			Function::Min
+			| Function::Max
+			| Function::Sum
+			| Function::Product
+			| Function::Mean
+			| Function::Median
+			| Function::Mode
+			| Function::Range
+			| Function::Multinomial
+			| Function::Det
+			| Function::Inverse
+			| Function::Transpose
+			| Function::Norm
+			| Function::PolyRoots
+			=> Arity::Variadic(1),
+			Function::Variance
+			| Function::Stdev
+			| Function::Percentile
+			| Function::Gcd
+			| Function::Lcm
+			=> Arity::Variadic(2),
+			_ => Arity::Fixed(1)
+		}
+	}
+
 	#[inline(always)]
 	pub fn from_string(s: &str) -> Option<Function> {
 		match s {
@@ -93,6 +311,56 @@ impl Function {
 			"round"   => {Some(Function::Round)},
 			"ln"      => {Some(Function::NaturalLog)},
 			"log"     => {Some(Function::TenLog)},
+			"atan2"   => {Some(Function::Atan2)},
+			"hypot"   => {Some(Function::Hypot)},
+			"min"     => {Some(Function::Min)},
+			"max"     => {Some(Function::Max)},
+			"root"    => {Some(Function::Root)},
+			"clamp"   => {Some(Function::Clamp)},
+			"if"      => {Some(Function::If)},
+
+			"integrate" => {Some(Function::Integrate)},
+			"derive"    => {Some(Function::Derive)},
+			"solve"     => {Some(Function::Solve)},
+
+			"sum"        => {Some(Function::Sum)},
+			"product"    => {Some(Function::Product)},
+			"prod"       => {Some(Function::Product)},
+			"mean"       => {Some(Function::Mean)},
+			"median"     => {Some(Function::Median)},
+			"mode"       => {Some(Function::Mode)},
+			"variance"   => {Some(Function::Variance)},
+			"stdev"      => {Some(Function::Stdev)},
+			"range"      => {Some(Function::Range)},
+			"percentile" => {Some(Function::Percentile)},
+
+			"gamma"     => {Some(Function::Gamma)},
+			"lgamma"    => {Some(Function::LGamma)},
+			"beta"      => {Some(Function::Beta)},
+			"erf"       => {Some(Function::Erf)},
+			"erfc"      => {Some(Function::Erfc)},
+
+			"nCr"         => {Some(Function::NCr)},
+			"ncr"         => {Some(Function::NCr)},
+			"nPr"         => {Some(Function::NPr)},
+			"npr"         => {Some(Function::NPr)},
+			"multinomial" => {Some(Function::Multinomial)},
+			"binompmf"    => {Some(Function::BinomPmf)},
+			"binompdf"    => {Some(Function::BinomPmf)},
+			"binomcdf"    => {Some(Function::BinomCdf)},
+			"dice"        => {Some(Function::Dice)},
+			"rand"        => {Some(Function::Rand)},
+
+			"gcd"       => {Some(Function::Gcd)},
+			"lcm"       => {Some(Function::Lcm)},
+			"modinv"    => {Some(Function::ModInv)},
+			"modpow"    => {Some(Function::ModPow)},
+			"rationalize" => {Some(Function::Rationalize)},
+			"isprime"   => {Some(Function::IsPrime)},
+			"factor"    => {Some(Function::Factor)},
+			"totient"   => {Some(Function::Totient)},
+			"nextprime" => {Some(Function::NextPrime)},
+
 			"sin"     => {Some(Function::Sin)},
 			"cos"     => {Some(Function::Cos)},
 			"tan"     => {Some(Function::Tan)},
@@ -112,6 +380,27 @@ impl Function {
 			"sech"    => {Some(Function::Sech)},
 			"coth"    => {Some(Function::Coth)},
 
+			"re"      => {Some(Function::Re)},
+			"im"      => {Some(Function::Im)},
+			"arg"     => {Some(Function::Arg)},
+			"conj"    => {Some(Function::Conj)},
+
+			"det"       => {Some(Function::Det)},
+			"inv"       => {Some(Function::Inverse)},
+			"inverse"   => {Some(Function::Inverse)},
+			"transpose" => {Some(Function::Transpose)},
+			"dot"       => {Some(Function::Dot)},
+			"cross"     => {Some(Function::Cross)},
+			"norm"      => {Some(Function::Norm)},
+
+			"polyroots" => {Some(Function::PolyRoots)},
+			"polyval"   => {Some(Function::PolyVal)},
+			"polymul"   => {Some(Function::PolyMul)},
+			"polydiv"   => {Some(Function::PolyDiv)},
+			"polyrem"   => {Some(Function::PolyRem)},
+
+			"interval"  => {Some(Function::Interval)},
+
 			"nounit" => {Some(Function::NoUnit)},
 			"tobase" => {Some(Function::ToBase)},
 
@@ -1,11 +1,19 @@
 mod operator;
 mod function;
 mod expression;
+mod userconstant;
 
 pub use self::operator::Operator;
 pub use self::function::Function;
+pub use self::function::Arity;
 pub use self::expression::Expression;
+pub use self::userconstant::UserConstant;
+pub use self::userconstant::set_user_constants;
 
 
 use super::parse_no_context;
+use crate::quantity::with_all_unit_sets;
+use userconstant::user_constant_name;
+use userconstant::user_constant_value;
+use userconstant::user_constant_from_string;
 include!(concat!(env!("OUT_DIR"), "/constants.rs"));
\ No newline at end of file
@@ -15,21 +15,39 @@ use super::Function;
 pub enum Operator {
 	// When adding operators, don't forget to update help command text.
 	// It isn't automatically generated.
-	ModuloLong = 0, // Mod invoked with "mod"
+	Or = 0,
+	And,
+	Not,
+	Equal,
+	NotEqual,
+	Less,
+	LessEqual,
+	Greater,
+	GreaterEqual,
+	ModuloLong, // Mod invoked with "mod"
 	DivideLong, // Division invoked with "per"
 	UnitConvert,
+	BitOr,
+	BitXor,
+	BitAnd,
+	ShiftLeft,
+	ShiftRight,
+	PlusMinus,
 	Subtract,
 	Add,
 	Divide, // Division invoked with "/"
 	Multiply,
 	Modulo, // Mod invoked with %
 	Negative,
+	BitNot,
 
 	Sqrt,
 	ImplicitMultiply,
 
 	Power,
+	Dice,
 	Factorial,
+	DoubleFactorial,
 
 	Function(Function),
 	UserFunction(String)
@@ -48,6 +66,11 @@ impl PartialOrd for Operator {
 			| (Operator::Subtract, Operator::Add)
 			| (Operator::Multiply, Operator::Divide)
 			| (Operator::Divide, Operator::Multiply)
+			| (Operator::ShiftLeft, Operator::ShiftRight)
+			| (Operator::ShiftRight, Operator::ShiftLeft)
+			=> {Some(Ordering::Equal)}
+
+			(a, b) if a.is_comparison() && b.is_comparison()
 			=> {Some(Ordering::Equal)}
 
 			_ => { self.as_int().partial_cmp(&other.as_int()) }
@@ -85,8 +108,26 @@ impl Operator {
 			"per"    => {Some( Operator::DivideLong )},
 			"to"     => {Some( Operator::UnitConvert )},
 			"^"|"**" => {Some( Operator::Power )},
+			"d*"     => {Some( Operator::Dice )},
 			"!"      => {Some( Operator::Factorial )},
+			"!!"     => {Some( Operator::DoubleFactorial )},
 			"sqrt"|"rt"|"√" => {Some( Operator::Sqrt )},
+			"and"    => {Some( Operator::BitAnd )},
+			"or"     => {Some( Operator::BitOr )},
+			"xor"    => {Some( Operator::BitXor )},
+			"not"    => {Some( Operator::BitNot )},
+			"||"     => {Some( Operator::Or )},
+			"&&"     => {Some( Operator::And )},
+			"!n"     => {Some( Operator::Not )},
+			"=="     => {Some( Operator::Equal )},
+			"!="|"≠" => {Some( Operator::NotEqual )},
+			"<"      => {Some( Operator::Less )},
+			"<="|"≤" => {Some( Operator::LessEqual )},
+			">"      => {Some( Operator::Greater )},
+			">="|"≥" => {Some( Operator::GreaterEqual )},
+			"<<"     => {Some( Operator::ShiftLeft )},
+			">>"     => {Some( Operator::ShiftRight )},
+			"±"|"+-"|"+/-" => {Some( Operator::PlusMinus )},
 
 			_ => None
 		};
@@ -96,7 +137,10 @@ impl Operator {
 	pub fn is_binary(&self) -> bool {
 		match self {
 			Operator::Negative
+			| Operator::BitNot
+			| Operator::Not
 			| Operator::Factorial
+			| Operator::DoubleFactorial
 			| Operator::Sqrt
 			| Operator::Function(_)
 			| Operator::UserFunction(_)
@@ -109,6 +153,8 @@ impl Operator {
 	pub fn is_left_associative(&self) -> bool {
 		match self {
 			Operator::Negative
+			| Operator::BitNot
+			| Operator::Not
 			| Operator::Sqrt
 			| Operator::Function(_)
 			| Operator::UserFunction(_)
@@ -117,6 +163,21 @@ impl Operator {
 		}
 	}
 
+	// Comparisons all have the same precedence.
+	#[inline(always)]
+	pub fn is_comparison(&self) -> bool {
+		match self {
+			Operator::Equal
+			| Operator::NotEqual
+			| Operator::Less
+			| Operator::LessEqual
+			| Operator::Greater
+			| Operator::GreaterEqual
+			=> true,
+			_ => false
+		}
+	}
+
 	// When printing, remap some operators to fix priority.
 	// This ensures that odd expressions like 1/2pi = 1/(2pi) print properly.
 	#[inline(always)]
@@ -150,12 +211,173 @@ impl Operator {
 	}
 
 
+	// Factorial operands are wrapped unless they're atomic,
+	// since `(3!)!` isn't `3!!` and `(-3)!` isn't `-3!`.
+	#[inline(always)]
+	fn add_parens_to_factorial_arg(&self, context: &Context, arg: &Expression) -> String {
+		let astr: String = arg.display(context);
+		let atomic = match arg {
+			Expression::Operator(_, Operator::Function(_), _) => true,
+			Expression::Operator(_, _, _) => false,
+			Expression::Quantity(_, _) => astr.chars().all(|c| c.is_alphanumeric() || c == '.'),
+			_ => true
+		};
+
+		if atomic { return astr; }
+		return format!("({})", astr);
+	}
+
+	// Tuples already have parenthesis,
+	// so we don't need to add more.
+	#[inline(always)]
+	fn display_fn_args(context: &Context, arg: &Expression) -> String {
+		if let Expression::Tuple(_, _) = arg {
+			return arg.display(context);
+		}
+		return format!("({})", arg.display(context));
+	}
+
 	pub fn display(&self, context: &Context, args: &VecDeque<Expression>) -> String {
 		match self {
 			Operator::Negative => {
 				return format!("-{}", self.add_parens_to_arg(context, &args[0]));
 			},
 
+			Operator::BitNot => {
+				return format!("not {}", self.add_parens_to_arg(context, &args[0]));
+			},
+
+			Operator::Not => {
+				// `!` binds loosely, so `!(a < b)` is the same as `!a < b`.
+				// We keep the parenthesis anyway, since they're much easier to read.
+				if let Expression::Operator(_, o, _) = &args[0] {
+					if o.is_binary() {
+						return format!("!({})", args[0].display(context));
+					}
+				}
+				return format!("!{}", self.add_parens_to_arg(context, &args[0]));
+			},
+
+			Operator::Or => {
+				return format!(
+					"{} || {}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
+			Operator::And => {
+				return format!(
+					"{} && {}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
+			Operator::Equal => {
+				return format!(
+					"{} == {}",
+					self.add_parens_to_arg_strict(context, &args[0]),
+					self.add_parens_to_arg_strict(context, &args[1])
+				);
+			},
+
+			Operator::NotEqual => {
+				return format!(
+					"{} ≠ {}",
+					self.add_parens_to_arg_strict(context, &args[0]),
+					self.add_parens_to_arg_strict(context, &args[1])
+				);
+			},
+
+			Operator::Less => {
+				return format!(
+					"{} < {}",
+					self.add_parens_to_arg_strict(context, &args[0]),
+					self.add_parens_to_arg_strict(context, &args[1])
+				);
+			},
+
+			Operator::LessEqual => {
+				return format!(
+					"{} ≤ {}",
+					self.add_parens_to_arg_strict(context, &args[0]),
+					self.add_parens_to_arg_strict(context, &args[1])
+				);
+			},
+
+			Operator::Greater => {
+				return format!(
+					"{} > {}",
+					self.add_parens_to_arg_strict(context, &args[0]),
+					self.add_parens_to_arg_strict(context, &args[1])
+				);
+			},
+
+			Operator::GreaterEqual => {
+				return format!(
+					"{} ≥ {}",
+					self.add_parens_to_arg_strict(context, &args[0]),
+					self.add_parens_to_arg_strict(context, &args[1])
+				);
+			},
+
+			Operator::BitAnd => {
+				return format!(
+					"{} and {}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
+			Operator::BitOr => {
+				return format!(
+					"{} or {}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
+			Operator::BitXor => {
+				return format!(
+					"{} xor {}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
+			Operator::ShiftLeft => {
+				return format!(
+					"{} << {}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
+			Operator::ShiftRight => {
+				return format!(
+					"{} >> {}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
+			Operator::PlusMinus => {
+				return format!(
+					"{} ± {}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
+			Operator::Dice => {
+				return format!(
+					"{}d{}",
+					self.add_parens_to_arg(context, &args[0]),
+					self.add_parens_to_arg(context, &args[1])
+				);
+			},
+
 			Operator::Sqrt => {
 				return format!(
 					"√{}",
@@ -210,7 +432,7 @@ impl Operator {
 				if {
 					context.config.enable_super_powers &&
 					q.is_unitless_integer() &&
-					!q.display(context).contains("e")
+					q.display(context).chars().all(|c| c == '-' || c.is_ascii_digit())
 				} {
 					// Write integer powers as a superscript
 					let mut b = String::new();
@@ -246,7 +468,11 @@ impl Operator {
 			},
 
 			Operator::Factorial => {
-				return format!("{}!", self.add_parens_to_arg(context, &args[0]));
+				return format!("{}!", self.add_parens_to_factorial_arg(context, &args[0]));
+			},
+
+			Operator::DoubleFactorial => {
+				return format!("{}!!", self.add_parens_to_factorial_arg(context, &args[0]));
 			},
 
 			Operator::Add => {
@@ -326,12 +552,18 @@ impl Operator {
 				);
 			},
 
+			// Intervals are written `[a, b]`
+			Operator::Function(Function::Interval) => {
+				let s = Operator::display_fn_args(context, &args[0]);
+				return format!("[{}]", &s[1..s.len()-1]);
+			},
+
 			Operator::Function(s) => {
-				return format!("{}({})", s.to_string(), args[0].display(context));
+				return format!("{}{}", s.to_string(), Operator::display_fn_args(context, &args[0]));
 			},
 
 			Operator::UserFunction(s) => {
-				return format!("{}({})", s, args[0].display(context));
+				return format!("{}{}", s, Operator::display_fn_args(context, &args[0]));
 			}
 		};
 	}
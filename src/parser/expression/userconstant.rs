@@ -0,0 +1,48 @@
+use std::cell::RefCell;
+
+use super::Expression;
+use super::Constant;
+
+
+// A constant loaded from a definition file at runtime
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct UserConstant {
+	// Strings that are parsed as this constant.
+	// The first is the one we print.
+	pub strings: Vec<String>,
+
+	// This constant's name in help texts.
+	// If this is None, this constant is not listed.
+	pub pretty_name: Option<String>,
+
+	// The value of this constant, already evaluated
+	pub value: Expression
+}
+
+
+thread_local!(
+	// Constants loaded at runtime.
+	// `Constant::User(i)` is the ith constant in this list.
+	//
+	// This is kept in sync with Context by Context::push_constant().
+	static USER_CONSTANTS: RefCell<Vec<UserConstant>> = const { RefCell::new(Vec::new()) }
+);
+
+pub fn set_user_constants(v: &[UserConstant]) { USER_CONSTANTS.with(|x| *x.borrow_mut() = v.to_vec()); }
+
+pub(super) fn user_constant_name(i: usize) -> String {
+	return USER_CONSTANTS.with(|x| x.borrow()[i].strings[0].clone());
+}
+
+pub(super) fn user_constant_value(i: usize) -> Expression {
+	return USER_CONSTANTS.with(|x| x.borrow()[i].value.clone());
+}
+
+// Called by Constant::from_string() if `s` isn't a built-in constant
+pub(super) fn user_constant_from_string(s: &str) -> Option<Constant> {
+	return USER_CONSTANTS.with(|x| {
+		let i = x.borrow().iter().position(|c| c.strings.iter().any(|x| x == s))?;
+		return Some(Constant::User(i));
+	});
+}
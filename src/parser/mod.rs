@@ -11,6 +11,9 @@ pub use self::{
 	expression::Constant,
 	expression::Operator,
 	expression::Function,
+	expression::Arity,
+	expression::UserConstant,
+	expression::set_user_constants,
 	linelocation::LineLocation,
 };
 
@@ -20,6 +23,20 @@ use crate::errors::DaisyError;
 pub fn parse(
 	context: &Context, s: &String
 ) -> Result<Expression, (LineLocation, DaisyError)> {
+	context.activate();
+	return parse_inactive(context, s);
+}
+
+// Parse without variables, functions, or user units.
+// Unlike parse(), this keeps the state of the active context,
+// so that numbers are read at its float precision.
+pub fn parse_no_context(s: &String) -> Result<Expression, (LineLocation, DaisyError)> {
+	parse_inactive(&Context::new_inactive(), s)
+}
+
+fn parse_inactive(
+	context: &Context, s: &String
+) -> Result<Expression, (LineLocation, DaisyError)> {
 
 	let mut expressions = stage::tokenize(context, s);
 	if context.config.enable_substituion {
@@ -31,10 +48,6 @@ pub fn parse(
 	return Ok(g);
 }
 
-pub fn parse_no_context(s: &String) -> Result<Expression, (LineLocation, DaisyError)> {
-	parse(&Context::new(), s)
-}
-
 
 
 // Substitiution replaces certain string with pretty unicode characters.
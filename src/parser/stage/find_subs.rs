@@ -2,7 +2,8 @@ use std::collections::VecDeque;
 
 use super::super::{
 	Token,
-	LineLocation
+	LineLocation,
+	Function
 };
 
 
@@ -15,6 +16,11 @@ fn sub_string(s: &str) -> Option<&'static str> {
 		"/"    => "÷",
 		"sqrt" => "√",
 		"rt"   => "√",
+		"!="   => "≠",
+		"<="   => "≤",
+		">="   => "≥",
+		"+-"   => "±",
+		"+/-"  => "±",
 
 
 
@@ -84,6 +90,10 @@ pub fn find_subs(
 
 
 		let target: Option<&str> = match &mut t {
+			// Some function names (like `gamma`) are also greek letters.
+			// Don't replace those.
+			Token::Operator(_, s) if Function::from_string(s).is_some() => { None },
+
 			Token::Operator(_, s) => {
 				let target = sub_string(s);
 
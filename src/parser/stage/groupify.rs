@@ -16,6 +16,7 @@ fn lookback_signs(
 ) -> Result<(), (LineLocation, DaisyError)> {
 
 	// Convert `-` operators to `neg` operators
+	// Convert prefix `!` operators to `!n` (logical not)
 	// Delete `+`s that mean "positive" instead of "add"
 	let mut i: usize = 0;
 	while i < g.len() {
@@ -26,6 +27,8 @@ fn lookback_signs(
 				=> {
 					if o == "-" {
 						g.insert(i, Token::Operator(*l, String::from("neg")));
+					} else if o == "!" {
+						g.insert(i, Token::Operator(*l, String::from("!n")));
 					} else if o == "+" && g.len() != 0 {
 						// Don't remove "+" if it's the only token,
 						// this is a syntax error that is caught later.e
@@ -55,6 +58,9 @@ fn lookback_signs(
 						if sb == "-" {
 							g.insert(i-1, Token::Operator(*l, String::from("neg")));
 							g.insert(i-1, a);
+						} else if sb == "!" {
+							g.insert(i-1, Token::Operator(*l, String::from("!n")));
+							g.insert(i-1, a);
 						} else if sb == "+" {
 							g.insert(i-1, a);
 							i -= 1; // g is now shorter, we don't need to advance i.
@@ -63,6 +69,21 @@ fn lookback_signs(
 					} else { g.insert(i-1, b); g.insert(i-1, a); }
 				},
 
+				// Signs at the start of a tuple element
+				(Token::TupleDelim(_), Token::Operator(l,sb))
+				=> {
+					if sb == "-" {
+						g.insert(i-1, Token::Operator(*l, String::from("neg")));
+						g.insert(i-1, a);
+					} else if sb == "!" {
+						g.insert(i-1, Token::Operator(*l, String::from("!n")));
+						g.insert(i-1, a);
+					} else if sb == "+" {
+						g.insert(i-1, a);
+						i -= 1;
+					} else { g.insert(i-1, b); g.insert(i-1, a); }
+				},
+
 				_ => { g.insert(i-1, b); g.insert(i-1, a); }
 			}
 		}
@@ -123,6 +144,13 @@ fn lookback(
 				| (Token::Quantity(la,_), Token::Word(lb,_))
 				| (Token::Word(la,_), Token::Quantity(lb,_))
 				| (Token::Word(la,_), Token::Word(lb,_))
+				| (Token::Tuple(la,_), Token::Tuple(lb,_))
+				| (Token::Tuple(la,_), Token::Group(lb,_))
+				| (Token::Group(la,_), Token::Tuple(lb,_))
+				| (Token::Tuple(la,_), Token::Quantity(lb,_))
+				| (Token::Quantity(la,_), Token::Tuple(lb,_))
+				| (Token::Tuple(la,_), Token::Word(lb,_))
+				| (Token::Word(la,_), Token::Tuple(lb,_))
 				=> {
 					let la = la.clone();
 					let lb = lb.clone();
@@ -138,6 +166,7 @@ fn lookback(
 				// Insert implicit multiplications for right-unary operators
 				(Token::Quantity(la,_), Token::Operator(lb,s))
 				| (Token::Group(la,_), Token::Operator(lb,s))
+				| (Token::Tuple(la,_), Token::Operator(lb,s))
 				| (Token::Word(la,_), Token::Operator(lb,s))
 				=> {
 					let la = la.clone();
@@ -160,6 +189,7 @@ fn lookback(
 				// Insert implicit multiplications for left-unary operators.
 				(Token::Operator(la,s), Token::Quantity(lb,_))
 				| (Token::Operator(la,s), Token::Group(lb,_))
+				| (Token::Operator(la,s), Token::Tuple(lb,_))
 				| (Token::Operator(la,s), Token::Word(lb,_))
 				=> {
 					let la = la.clone();
@@ -195,6 +225,22 @@ fn lookback(
 }
 
 
+// Empty parenthesis are only allowed after a function,
+// like `rand()`. These become a tuple with no arguments.
+fn empty_call(
+	context: &Context,
+	l: LineLocation,
+	v: &VecDeque<Token>
+) -> Result<Token, (LineLocation, DaisyError)> {
+	if let Some(Token::Operator(_, s)) = v.back() {
+		if let Some(Operator::Function(_) | Operator::UserFunction(_)) = Operator::from_string(context, s) {
+			return Ok(Token::Tuple(l, VecDeque::new()));
+		}
+	}
+	return Err((l, DaisyError::EmptyGroup));
+}
+
+
 pub fn groupify(
 	context: &Context,
 	mut g: VecDeque<Token>
@@ -234,12 +280,18 @@ pub fn groupify(
 				let l = *l_now + l;
 
 				if i_level == 0 { return Err((l, DaisyError::ExtraCloseParen)) }
-				if v_now.len() == 0 { return Err((l, DaisyError::EmptyGroup)) }
 
 				i_level -= 1;
 
 				let (_, mut v) = levels.pop().unwrap();
 				let (_, v_now) = levels.last_mut().unwrap();
+
+				if v.len() == 0 {
+					is_tuple.pop();
+					v_now.push_back(empty_call(context, l, v_now)?);
+					continue;
+				}
+
 				lookback(context, &mut v)?;
 
 				let q = is_tuple.pop().unwrap();
@@ -274,7 +326,12 @@ pub fn groupify(
 		let (l, mut v) = levels.pop().unwrap();
 		let (_, v_now) = levels.last_mut().unwrap();
 
-		if v.len() == 0 { return Err((l, DaisyError::EmptyGroup)) }
+		if v.len() == 0 {
+			is_tuple.pop();
+			v_now.push_back(empty_call(context, l, v_now)?);
+			continue;
+		}
+
 		lookback(context, &mut v)?;
 
 		let q = is_tuple.pop().unwrap();
@@ -1,10 +1,12 @@
 use std::collections::VecDeque;
 use crate::context::Context;
+use crate::quantity::Unit;
 
 use super::super::{
 	Token,
 	LineLocation,
-	Operator
+	Operator,
+	Function
 };
 
 // Called whenever a token is finished.
@@ -42,9 +44,10 @@ fn push_token(
 
 	// `2e` isn't exponential notation, it's 2*e.
 	// If a number ends in `e`, disconnect the `e` and make it a word.
+	// Hex numbers may end in `e`, so we skip them.
 	if let Token::Quantity(l, s) = &t {
 		let last = &s[s.len()-1..];
-		if last == "e" {
+		if last == "e" && !is_hex(s) {
 			g.push_back(Token::Quantity(
 				LineLocation { pos: l.pos, len: l.len-1 },
 				String::from(&s[0..s.len()-1])
@@ -68,6 +71,57 @@ fn push_token(
 	g.push_back(t);
 }
 
+// Is this string the start of a binary, octal, or hex literal?
+// Prefixes may be upper or lower case, like `0xFF` and `0XFF`.
+// A bare prefix like `0x` is left as an invalid number.
+#[inline(always)]
+fn is_radix_prefix(s: &str) -> bool {
+	return ["0b", "0o", "0x"].iter().any(|p| s.eq_ignore_ascii_case(p));
+}
+
+#[inline(always)]
+fn is_hex(s: &str) -> bool {
+	return s.len() >= 2 && s[0..2].eq_ignore_ascii_case("0x");
+}
+
+// Called for every character that could be part of a word.
+#[inline(always)]
+fn push_word_char(
+	context: &Context,
+	g: &mut VecDeque<Token>,
+	t: &mut Option<Token>,
+	i: usize,
+	c: char
+) {
+	match t {
+		Some(Token::Word(_, val)) => { val.push(c); },
+
+		// Radix prefixes, like 0x
+		Some(Token::Quantity(_, val)) if val == "0" && "boxBOX".contains(c) => { val.push(c); },
+
+		// Hex digits
+		Some(Token::Quantity(_, val)) if is_hex(val) && c.is_ascii_hexdigit() => { val.push(c); },
+
+		// This wasn't a radix literal after all (like `0xyz`).
+		// Split off the zero and keep building a word.
+		Some(Token::Quantity(l, val)) if is_radix_prefix(val) => {
+			let w = format!("{}{c}", &val[1..]);
+			let pos = l.pos;
+			push_token(
+				context, g,
+				Some(Token::Quantity(LineLocation{pos, len: 0}, String::from("0"))),
+				pos + 1
+			);
+			*t = Some(Token::Word(LineLocation{pos: pos + 1, len: 0}, w));
+		},
+
+		_ => {
+			push_token(context, g, t.take(), i);
+			*t = Some(Token::Word(LineLocation{pos: i, len: 0}, String::from(c)));
+		}
+	};
+}
+
 /// Turns a string into Tokens. First stage of parsing.
 pub fn tokenize(context: &Context, input: &String) -> VecDeque<Token> {
 	let mut t: Option<Token> = None; // The current token we're reading
@@ -85,6 +139,11 @@ pub fn tokenize(context: &Context, input: &String) -> VecDeque<Token> {
 						val.push(if c == ',' {'.'} else {c});
 					},
 
+					// Some function names contain digits, like `atan2`.
+					Some(Token::Word(_, val)) if Function::from_string(&format!("{val}{c}")).is_some() => {
+						val.push(c);
+					},
+
 					// If we're not building a number, finalize
 					// previous token and start one.
 					_ => {
@@ -98,13 +157,8 @@ pub fn tokenize(context: &Context, input: &String) -> VecDeque<Token> {
 			// Can be both a word or a number.
 			'e' => {
 				match &mut t {
-					Some(Token::Word(_, val)) => { val.push(c); },
-					Some(Token::Quantity(_, val)) => { val.push(c); },
-
-					_ => {
-						push_token(context, &mut g, t, i);
-						t = Some(Token::Word(LineLocation{pos: i, len: 0}, String::from(c)));
-					}
+					Some(Token::Quantity(_, val)) if !is_radix_prefix(val) || is_hex(val) => { val.push(c); },
+					_ => { push_word_char(context, &mut g, &mut t, i, c); }
 				};
 			}
 
@@ -114,7 +168,7 @@ pub fn tokenize(context: &Context, input: &String) -> VecDeque<Token> {
 			'-' | '+' => {
 				match &mut t {
 					Some(Token::Quantity(_, val)) => {
-						if &val[val.len()-1..] == "e" {
+						if &val[val.len()-1..] == "e" && !is_hex(val) {
 							// If the current number ends in an `e`,
 							// this negative specifies a negative exponent
 							// like 2e-2 = 0.02.
@@ -130,8 +184,18 @@ pub fn tokenize(context: &Context, input: &String) -> VecDeque<Token> {
 						}
 					},
 
+					// `+-` is the ASCII form of `±`, but only if it's followed by a space.
+					// Otherwise, `5+-3` is 5 + (-3).
+					Some(Token::Operator(_, val)) if {
+						val == "+" && c == '-' &&
+						input.chars().nth(i + 1).is_none_or(char::is_whitespace)
+					} => { val.push(c); },
+
+					// `+/-` is always `±`
+					Some(Token::Operator(_, val)) if val == "+/" && c == '-' => { val.push(c); },
+
 					// This may be a negative or a subtraction.
-					// Multi-character operators with - and + are NOT supported!
+					// Other multi-character operators with - and + are NOT supported!
 					// (for example, we can't use -> for unit conversion)
 					_ => {
 						push_token(context, &mut g, t, i);
@@ -152,7 +216,8 @@ pub fn tokenize(context: &Context, input: &String) -> VecDeque<Token> {
 			'^'|'!'|'%'|'\\'|
 			'*'|'×'|'/'|'÷'|
 			'>'|'<'|'?'|'@'|
-			'&'|'|'|'~'
+			'&'|'|'|'~'|'='|
+			'≠'|'≤'|'≥'|'±'
 			=> {
 				match &mut t {
 					Some(Token::Operator(_, val)) => { val.push(c); },
@@ -173,6 +238,24 @@ pub fn tokenize(context: &Context, input: &String) -> VecDeque<Token> {
 				t = Some(Token::GroupEnd(LineLocation{pos: i, len: 0}));
 			},
 
+			// Intervals. `[a, b]` is the same as `interval(a, b)`.
+			'[' => {
+				push_token(context, &mut g, t, i);
+				g.push_back(Token::Operator(LineLocation{pos: i, len: 1}, String::from("interval")));
+				t = Some(Token::GroupStart(LineLocation{pos: i, len: 0}));
+			},
+			']' => {
+				push_token(context, &mut g, t, i);
+				t = Some(Token::GroupEnd(LineLocation{pos: i, len: 0}));
+			},
+
+			// `√` is always a whole operator,
+			// so `π√2` is π times √2.
+			'√' => {
+				push_token(context, &mut g, t, i);
+				t = Some(Token::Operator(LineLocation{pos: i, len: 0}, String::from(c)));
+			},
+
 			// Space. Basic seperator.
 			' ' => {
 				push_token(context, &mut g, t, i);
@@ -180,20 +263,50 @@ pub fn tokenize(context: &Context, input: &String) -> VecDeque<Token> {
 			}
 
 			// Word
-			_ => {
-				match &mut t {
-					Some(Token::Word(_, val)) => { val.push(c); },
-
-					_ => {
-						push_token(context, &mut g, t, i);
-						t = Some(Token::Word(LineLocation{pos: i, len: 0}, String::from(c)));
-					}
-				};
-			}
+			_ => { push_word_char(context, &mut g, &mut t, i, c); }
 		};
 	}
 
 	push_token(context, &mut g, t, input.chars().count());
 
+	// Some words (like `min`) are both functions and units.
+	// These are only functions if they're followed by parenthesis.
+	let mut i = 0;
+	while i < g.len() {
+		if let Token::Operator(l, s) = &g[i] {
+			if {
+				Function::from_string(s).is_some() &&
+				Unit::from_string(s).is_some() &&
+				!matches!(g.get(i+1), Some(Token::GroupStart(_)))
+			} {
+				g[i] = Token::Word(*l, s.clone());
+			}
+		}
+		i += 1;
+	}
+
+	// Dice notation, like `3d6`.
+	// This is only dice if there are no spaces and both numbers are integers,
+	// otherwise `d` is a day.
+	let mut i = 1;
+	while i + 1 < g.len() {
+		if let (
+			Token::Quantity(la, a),
+			Token::Word(l, s),
+			Token::Quantity(lb, b)
+		) = (&g[i-1], &g[i], &g[i+1]) {
+			if {
+				s == "d" &&
+				la.pos + la.len == l.pos &&
+				l.pos + l.len == lb.pos &&
+				a.chars().all(|c| c.is_ascii_digit()) &&
+				b.chars().all(|c| c.is_ascii_digit())
+			} {
+				g[i] = Token::Operator(*l, String::from("d*"));
+			}
+		}
+		i += 1;
+	}
+
 	return g;
 }
\ No newline at end of file
@@ -254,9 +254,16 @@ fn treeify_unary(
 			} else {
 				next_pre = g_inner.remove(i).unwrap();
 			}
-			if let Token::Group(l, _) = next_pre {
+			if let Token::Group(l, ref v) = next_pre {
+				// A function called with one tuple, like `det(((1, 2), (3, 4)))`,
+				// keeps that tuple as its only argument so it's echoed as written.
+				let one_tuple = v.len() == 1 && matches!(v[0], Token::Tuple(_, _));
+
 				next = treeify(context, next_pre)?;
 				next.set_linelocation(&(next.get_linelocation() + l));
+				if one_tuple && matches!(this_op, Operator::Function(_)) {
+					next = Expression::Tuple(l, VecDeque::from([next]));
+				}
 			} else if let Token::Tuple(l, _) = next_pre {
 				next = treeify(context, next_pre)?;
 				next.set_linelocation(&(next.get_linelocation() + l));
@@ -372,6 +379,12 @@ pub fn treeify(
 
 	let g = g_inner.pop_front().unwrap();
 	return match g {
+		// A user function's name on its own,
+		// like `f` in `integrate(f, 0, 1)`
+		Token::Operator(l, s) if context.is_function(&s) => {
+			Ok(Expression::Variable(l, s))
+		},
+
 		// Catch edge cases
 		Token::Operator(l, _) => {
 			Err((l, DaisyError::Syntax))
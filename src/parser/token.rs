@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 use crate::quantity::Unit;
 use crate::quantity::Quantity;
+use crate::quantity::DisplayFormat;
 use crate::context::Context;
 use crate::errors::DaisyError;
 
@@ -84,13 +85,24 @@ impl Token {
 			},
 
 			Token::Word(l, s) => {
+				match &s[..] {
+					"true" => { return Ok(Expression::Bool(l, true)); },
+					"false" => { return Ok(Expression::Bool(l, false)); },
+					_ => {}
+				};
+
+				// Some constants (like `j`) may be replaced by variables
+				if context.is_varible(&s) { return Ok(Expression::Variable(l, s)); }
+
 				let c = Constant::from_string(&s);
 				if c.is_some() { return Ok(Expression::Constant(l, c.unwrap())); }
 
 				let c = Unit::from_string(&s);
 				if c.is_some() { return Ok(Expression::Quantity(l, c.unwrap())); }
 
-				if context.is_varible(&s) { return Ok(Expression::Variable(l, s)); }
+				let c = DisplayFormat::from_string(&s);
+				if c.is_some() { return Ok(Expression::Format(l, c.unwrap())); }
+
 				return Ok(Expression::Variable(l, s));
 			}
 
@@ -17,16 +17,28 @@ cross-compilation to other systems. RUG does not work on all systems.
 
 mod scalar;
 pub use crate::quantity::scalar::Scalar;
+pub use crate::quantity::scalar::set_float_precision;
+pub use crate::quantity::scalar::get_float_precision;
+pub use crate::quantity::scalar::set_number_format;
+pub use crate::quantity::scalar::Notation;
 
 
 mod unit;
 pub use crate::quantity::unit::Unit;
 pub use crate::quantity::unit::FreeUnit;
+pub use crate::quantity::unit::Prefix;
 pub use crate::quantity::unit::WholeUnit;
 pub use crate::quantity::unit::freeunit_from_string;
+pub use crate::quantity::unit::named_dimensions;
+pub use crate::quantity::unit::UserUnit;
+pub use crate::quantity::unit::set_user_units;
+pub use crate::quantity::unit::UnitSet;
+pub use crate::quantity::unit::set_disabled_unit_sets;
+pub use crate::quantity::unit::with_all_unit_sets;
 
 mod quantity;
 pub use crate::quantity::quantity::Quantity;
+pub use crate::quantity::quantity::DisplayFormat;
 
 
 
@@ -6,6 +6,8 @@ use std::ops::{
 	MulAssign, DivAssign
 };
 use std::cmp::Ordering;
+use num::BigInt;
+use num::Integer;
 
 use crate::context::Context;
 use crate::quantity::Unit;
@@ -13,18 +15,123 @@ use crate::quantity::FreeUnit;
 
 use crate::quantity::Scalar;
 
+// Special ways to print a quantity,
+// selected with `to`. (for example, `255 to hex`)
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub enum DisplayFormat {
+	// Print in the given base.
+	// Only 2, 8, 10 and 16 are supported.
+	Radix(u32),
+
+	// Print exact rationals as fractions, like `4/3`.
+	// Everything else is printed in decimal.
+	Fraction,
+
+	// Same as Fraction, but as a mixed number like `1 1/3`.
+	Mixed
+}
+
+impl DisplayFormat {
+	pub fn from_string(s: &str) -> Option<DisplayFormat> {
+		match s {
+			"bin" => Some(DisplayFormat::Radix(2)),
+			"oct" => Some(DisplayFormat::Radix(8)),
+			"dec" => Some(DisplayFormat::Radix(10)),
+			"hex" => Some(DisplayFormat::Radix(16)),
+			"frac" | "fraction" => Some(DisplayFormat::Fraction),
+			"mixed" => Some(DisplayFormat::Mixed),
+			_ => None
+		}
+	}
+}
+
+impl ToString for DisplayFormat {
+	fn to_string(&self) -> String {
+		match self {
+			DisplayFormat::Radix(2) => String::from("bin"),
+			DisplayFormat::Radix(8) => String::from("oct"),
+			DisplayFormat::Radix(16) => String::from("hex"),
+			DisplayFormat::Radix(_) => String::from("dec"),
+			DisplayFormat::Fraction => String::from("frac"),
+			DisplayFormat::Mixed => String::from("mixed"),
+		}
+	}
+}
+
+
 #[derive(Debug)]
 #[derive(Clone)]
 pub struct Quantity {
 	pub scalar: Scalar,
-	pub unit: Unit
+	pub unit: Unit,
+
+	// How to print this quantity.
+	// If None, use the defaults in Config.
+	pub format: Option<DisplayFormat>
 }
 
 
 
 impl Quantity {
+	// Format our scalar, respecting the polar display setting.
+	// Complex numbers and fractions with units are wrapped in parenthesis.
+	fn display_scalar(&self, context: &Context) -> String {
+		let format = self.format.unwrap_or(context.config.output_format);
+
+		let (radix, f) = match format {
+			DisplayFormat::Radix(r) => (r, None),
+			DisplayFormat::Fraction => (10, self.scalar.to_fraction_string(false)),
+			DisplayFormat::Mixed => (10, self.scalar.to_fraction_string(true))
+		};
+
+		let n = if let Some(f) = f {
+			f
+		} else if radix != 10 && !self.scalar.is_complex() {
+			self.scalar.to_radix_string(radix)
+		} else if context.config.enable_polar {
+			self.scalar.to_polar_string()
+		} else { self.scalar.to_string() };
+
+		if self.scalar.is_complex() && !self.unitless() {
+			return format!("({n})");
+		}
+
+		// Fractions like π/2 and 1 1/3 are wrapped too,
+		// since `π/2 rad` is π/(2 rad).
+		if n.contains('/') && !self.unitless() {
+			return format!("({n})");
+		}
+		return n;
+	}
+
+	// If enabled, rewrite quantities with one unit
+	// to use the best SI prefix, like `4.7 mF` instead of `0.0047 F`.
+	fn with_si_prefix(&self, context: &Context) -> Option<Quantity> {
+		if !context.config.enable_si_prefix { return None; }
+
+		let v = self.unit.get_val();
+		if v.len() != 1 { return None; }
+		let (u, p) = v.iter().next().unwrap();
+		if !p.is_one() { return None; }
+
+		let u = u.engineering_prefix(self.scalar.magnitude()?)?;
+		let mut r = Quantity::new_rational(1f64).unwrap();
+		r.insert_unit(u, Scalar::new_rational(1f64).unwrap());
+
+		let mut r = self.convert_to(r)?;
+		r.format = self.format;
+		return Some(r);
+	}
+
 	pub fn display(&self, context: &Context) -> String {
-		let n = self.scalar.to_string();
+		if let Some(q) = self.with_si_prefix(context) { return q.display_no_prefix(context); }
+		return self.display_no_prefix(context);
+	}
+
+	fn display_no_prefix(&self, context: &Context) -> String {
+		let n = self.display_scalar(context);
 		if self.unitless() { return n; }
 
 		let u = self.unit.display(context);
@@ -40,7 +147,12 @@ impl Quantity {
 
 impl Quantity {
 	pub fn display_outer(&self, context: &Context) -> String {
-		let n = self.scalar.to_string();
+		if let Some(q) = self.with_si_prefix(context) { return q.display_outer_no_prefix(context); }
+		return self.display_outer_no_prefix(context);
+	}
+
+	fn display_outer_no_prefix(&self, context: &Context) -> String {
+		let n = self.display_scalar(context);
 		if self.unitless() { return n; }
 
 		let u = self.unit.display(context);
@@ -57,7 +169,8 @@ impl Quantity {
 
 		return Some(Quantity{
 			scalar: v.unwrap(),
-			unit: Unit::new()
+			unit: Unit::new(),
+			format: None
 		});
 	}
 
@@ -67,7 +180,8 @@ impl Quantity {
 
 		return Some(Quantity{
 			scalar: v.unwrap(),
-			unit: Unit::new()
+			unit: Unit::new(),
+			format: None
 		});
 	}
 
@@ -77,7 +191,8 @@ impl Quantity {
 
 		return Some(Quantity{
 			scalar: v.unwrap(),
-			unit: Unit::new()
+			unit: Unit::new(),
+			format: None
 		});
 	}
 
@@ -87,7 +202,8 @@ impl Quantity {
 
 		return Some(Quantity{
 			scalar: v.unwrap(),
-			unit: Unit::new()
+			unit: Unit::new(),
+			format: None
 		});
 	}
 
@@ -97,14 +213,20 @@ impl Quantity {
 
 		return Some(Quantity{
 			scalar: v.unwrap(),
-			unit: Unit::new()
+			unit: Unit::new(),
+			format: None
 		});
 	}
 
+	pub fn new_rational_from_integer(n: BigInt) -> Quantity {
+		return Quantity::from_scalar(Scalar::new_rational_from_integer(n));
+	}
+
 	pub fn from_scalar(s: Scalar) -> Quantity {
 		return Quantity{
 			scalar: s,
-			unit: Unit::new()
+			unit: Unit::new(),
+			format: None
 		};
 	}
 
@@ -113,6 +235,11 @@ impl Quantity {
 	pub fn without_unit(&self) -> Quantity { Quantity::from_scalar(self.scalar.clone()) }
 
 	pub fn convert_to(&self, other: Quantity) -> Option<Quantity> {
+		// Levels like dBm don't convert by a constant factor
+		if self.unit.is_log_level() || other.unit.is_log_level() {
+			return self.convert_level(&other.unit);
+		}
+
 		if !self.unit.compatible_with(&other.unit) { return None; }
 
 		let n = self.clone();
@@ -163,7 +290,8 @@ macro_rules! quant_foward {
 			if !self.unitless() { panic!() }
 			Quantity {
 				scalar: self.scalar.$x(),
-				unit: self.unit.clone()
+				unit: self.unit.clone(),
+				format: None
 			}
 		}
 	}
@@ -176,7 +304,35 @@ impl Quantity {
 	pub fn is_nan(&self) -> bool { self.scalar.is_nan() }
 	pub fn is_negative(&self) -> bool { self.scalar.is_negative() }
 	pub fn is_positive(&self) -> bool { self.scalar.is_positive() }
+	pub fn is_complex(&self) -> bool { self.scalar.is_complex() }
 	pub fn unitless(&self) -> bool { self.unit.unitless() }
+
+	pub fn limit_bits(&self, bits: u64) -> Quantity {
+		return Quantity {
+			scalar: self.scalar.limit_bits(bits),
+			unit: self.unit.clone(),
+			format: self.format
+		};
+	}
+
+	// See Scalar::rationalize. Keeps our unit.
+	pub fn rationalize(&self, maxden: &BigInt) -> Option<Quantity> {
+		return Some(Quantity {
+			scalar: self.scalar.rationalize(maxden)?,
+			unit: self.unit.clone(),
+			format: self.format
+		});
+	}
+
+	pub fn is_odd_integer(&self) -> bool {
+		return self.to_integer().is_some_and(|n| n.is_odd());
+	}
+
+	// The value of this quantity, if it is an exact unitless integer.
+	pub fn to_integer(&self) -> Option<BigInt> {
+		if !self.unitless() { return None; }
+		return self.scalar.to_integer();
+	}
 	pub fn unit(&self) -> &Unit { &self.unit }
 
 	quant_foward!(fract);
@@ -206,19 +362,63 @@ impl Quantity {
 	quant_foward!(ln);
 	quant_foward!(log10);
 	quant_foward!(log2);
+	quant_foward!(gamma);
+	quant_foward!(lgamma);
+	quant_foward!(erf);
+	quant_foward!(erfc);
 
 	pub fn log(&self, base: Quantity) -> Quantity {
 		if !self.unitless() { panic!() }
 		Quantity {
 			scalar: self.scalar.log(base.scalar),
-			unit: self.unit.clone()
+			unit: self.unit.clone(),
+			format: None
+		}
+	}
+
+	pub fn re(&self) -> Quantity {
+		Quantity {
+			scalar: self.scalar.re(),
+			unit: self.unit.clone(),
+			format: None
+		}
+	}
+
+	pub fn im(&self) -> Quantity {
+		Quantity {
+			scalar: self.scalar.im(),
+			unit: self.unit.clone(),
+			format: None
+		}
+	}
+
+	pub fn conj(&self) -> Quantity {
+		Quantity {
+			scalar: self.scalar.conj(),
+			unit: self.unit.clone(),
+			format: None
 		}
 	}
 
+	// Always returns a unitless angle in radians.
+	pub fn beta(&self, b: Quantity) -> Quantity {
+		Quantity::from_scalar(self.scalar.beta(b.scalar))
+	}
+
+	pub fn atan2(&self, x: Quantity) -> Quantity {
+		Quantity::from_scalar(self.scalar.atan2(x.scalar))
+	}
+
+	// Always returns a unitless angle in radians.
+	pub fn arg(&self) -> Quantity {
+		Quantity::from_scalar(self.scalar.arg())
+	}
+
 	pub fn pow(&self, pwr: Quantity) -> Quantity {
 		Quantity {
 			scalar: self.scalar.pow(pwr.scalar.clone()),
-			unit: self.unit.pow(pwr.scalar)
+			unit: self.unit.pow(pwr.scalar),
+			format: None
 		}
 	}
 }
@@ -228,7 +428,8 @@ impl Quantity {
 	pub fn mul_no_convert(self, other: Self) -> Self {
 		Quantity {
 			scalar: self.scalar * other.scalar,
-			unit: self.unit * other.unit
+			unit: self.unit * other.unit,
+			format: None
 		}
 	}
 
@@ -240,7 +441,8 @@ impl Quantity {
 	pub fn div_no_convert(self, other: Self) -> Self {
 		Quantity {
 			scalar: self.scalar / other.scalar,
-			unit: self.unit / other.unit
+			unit: self.unit / other.unit,
+			format: None
 		}
 	}
 
@@ -257,7 +459,8 @@ impl Neg for Quantity where {
 	fn neg(self) -> Self::Output {
 		Quantity {
 			scalar: -self.scalar,
-			unit: self.unit
+			unit: self.unit,
+			format: None
 		}
 	}
 }
@@ -275,7 +478,8 @@ impl Add for Quantity {
 
 		Quantity {
 			scalar: self.scalar + o.scalar,
-			unit: self.unit
+			unit: self.unit,
+			format: None
 		}
 	}
 }
@@ -306,7 +510,8 @@ impl Sub for Quantity {
 
 		Quantity {
 			scalar: self.scalar - o.scalar,
-			unit: self.unit
+			unit: self.unit,
+			format: None
 		}
 	}
 }
@@ -334,7 +539,8 @@ impl Mul for Quantity {
 
 		Quantity {
 			scalar: self.scalar * o.scalar,
-			unit: self.unit * o.unit
+			unit: self.unit * o.unit,
+			format: None
 		}
 	}
 }
@@ -360,7 +566,8 @@ impl Div for Quantity {
 
 		Quantity {
 			scalar: self.scalar / o.scalar,
-			unit: self.unit / o.unit
+			unit: self.unit / o.unit,
+			format: None
 		}
 	}
 }
@@ -385,22 +592,28 @@ impl Rem<Quantity> for Quantity {
 
 		Quantity {
 			scalar: self.scalar % other.scalar,
-			unit: self.unit
+			unit: self.unit,
+			format: None
 		}
 	}
 }
 
+// Quantities with compatible units are compared after conversion,
+// so 1 m == 100 cm. Incompatible units are never equal or ordered.
 impl PartialEq for Quantity {
 	fn eq(&self, other: &Self) -> bool {
-		if self.unit != other.unit {false} else {
-			self.scalar == other.scalar
-		}
+		self.partial_cmp(other) == Some(Ordering::Equal)
 	}
 }
 
 impl PartialOrd for Quantity {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		if self.unit != other.unit { panic!("Tried to compare incompatible units") }
-		self.scalar.partial_cmp(&other.scalar)
+		if !self.unit.compatible_with(&other.unit) { return None; }
+
+		let mut o = other.clone();
+		if self.unit != o.unit {
+			o = o.convert_to(self.clone()).unwrap();
+		}
+		self.scalar.partial_cmp(&o.scalar)
 	}
 }
\ No newline at end of file
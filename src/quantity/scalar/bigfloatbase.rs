@@ -0,0 +1,536 @@
+use std::ops::{
+	Add, Sub, Mul, Div,
+	Neg, Rem,
+
+	AddAssign, SubAssign,
+	MulAssign, DivAssign
+};
+
+use std::cmp::Ordering;
+use std::cell::RefCell;
+use std::str::FromStr;
+
+use dashu_base::SquareRoot;
+use dashu_base::Abs;
+use dashu_float::FBig;
+use dashu_float::DBig;
+use dashu_float::round::mode::HalfEven;
+use dashu_int::IBig;
+use num::rational::BigRational;
+use num::BigInt;
+
+use super::ScalarBase;
+use super::dec_to_sci;
+use super::get_float_precision;
+
+
+type Big = FBig<HalfEven, 2>;
+
+// Extra bits of precision used for intermediate results.
+const GUARD_BITS: usize = 32;
+
+thread_local!(
+	// Highest-precision value of pi we've computed so far
+	static PI_CACHE: RefCell<Option<Big>> = RefCell::new(None)
+);
+
+
+// Make an integer with precision p
+fn int(n: i64, p: usize) -> Big { Big::from(n).with_precision(p).value() }
+fn round_to(x: Big, p: usize) -> Big { x.with_precision(p).value() }
+
+// Round exact values (like big integers) to p bits.
+// Conversions keep every digit of exact values, so
+// with_precision alone won't shrink them.
+fn fit(x: Big, p: usize) -> Big {
+	let d = x.digits();
+	return x.with_precision(d.max(p) + 1).value().with_precision(p).value();
+}
+
+// Rough base-2 logarithm of |x|
+fn magnitude(x: &Big) -> isize {
+	if x.repr().is_zero() { return isize::MIN; }
+	return x.repr().exponent() + x.repr().digits() as isize;
+}
+
+// atan(x) for small |x|, using its taylor series.
+// x must have precision w.
+fn atan_series(x: &Big, w: usize) -> Big {
+	let x2 = x.clone() * x.clone();
+	let mut sum = x.clone();
+	let mut term = x.clone();
+	let mut n = 1i64;
+	loop {
+		term = -term * x2.clone();
+		n += 2;
+		let next = sum.clone() + term.clone() / int(n, w);
+		if next == sum { break; }
+		sum = next;
+	}
+	return sum;
+}
+
+// Pi to p bits, using Machin's formula.
+fn pi(p: usize) -> Big {
+	let cached = PI_CACHE.with(|c| c.borrow().clone());
+	if let Some(v) = cached {
+		if v.precision() >= p { return round_to(v, p); }
+	}
+
+	let w = p + GUARD_BITS;
+	let a = atan_series(&(int(1, w) / int(5, w)), w);
+	let b = atan_series(&(int(1, w) / int(239, w)), w);
+	let v = a * int(16, w) - b * int(4, w);
+
+	PI_CACHE.with(|c| *c.borrow_mut() = Some(v.clone()));
+	return round_to(v, p);
+}
+
+// sin and cos of x, as a pair.
+fn sin_cos(x: &Big) -> (Big, Big) {
+	let p = x.precision();
+
+	// Reduce x to [-pi, pi]. Large arguments need more bits.
+	let w = p + GUARD_BITS + magnitude(x).max(0) as usize;
+	let x = round_to(x.clone(), w);
+	let tau = pi(w) * int(2, w);
+	let k = (x.clone() / tau.clone()).round();
+	let r = x - k * tau;
+	let r2 = r.clone() * r.clone();
+
+	let mut sin = r.clone();
+	let mut term = r;
+	let mut n = 1i64;
+	loop {
+		term = -term * r2.clone() / int((n + 1) * (n + 2), w);
+		n += 2;
+		let next = sin.clone() + term.clone();
+		if next == sin { break; }
+		sin = next;
+	}
+
+	let mut cos = int(1, w);
+	let mut term = int(1, w);
+	let mut n = 0i64;
+	loop {
+		term = -term * r2.clone() / int((n + 1) * (n + 2), w);
+		n += 2;
+		let next = cos.clone() + term.clone();
+		if next == cos { break; }
+		cos = next;
+	}
+
+	return (round_to(sin, p), round_to(cos, p));
+}
+
+fn atan(x: &Big) -> Big {
+	let p = x.precision();
+	let w = p + GUARD_BITS;
+	let x = round_to(x.clone(), w);
+	let one = int(1, w);
+
+	// atan(x) = ±pi/2 - atan(1/x)
+	if x.clone().abs() > one {
+		let h = pi(w) / int(2, w);
+		let r = atan(&(one / x.clone()));
+		let r = if x.repr().sign() == dashu_base::Sign::Negative { -h - r } else { h - r };
+		return round_to(r, p);
+	}
+
+	// Halve the angle a few times so the series converges quickly.
+	// atan(x) = 2 atan(x / (1 + sqrt(1 + x^2)))
+	let mut y = x;
+	for _ in 0..3 {
+		y = y.clone() / (one.clone() + (one.clone() + y.clone() * y.clone()).sqrt());
+	}
+
+	return round_to(atan_series(&y, w) * int(8, w), p);
+}
+
+
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct BigFloatBase where {
+	// None if this value is undefined.
+	pub val: Option<Big>
+}
+
+macro_rules! nan {
+	() => { BigFloatBase{ val: None } }
+}
+
+impl BigFloatBase {
+	fn new(v: Big) -> BigFloatBase {
+		if !v.repr().is_finite() { return nan!(); }
+		return BigFloatBase{ val: Some(v) };
+	}
+
+	pub fn from_string_with_precision(s: &str, p: usize) -> Option<BigFloatBase> {
+		match s {
+			"NaN" | "inf" | "-inf" => { return Some(nan!()); },
+			_ => {}
+		}
+
+		let v = DBig::from_str(s).ok()?;
+		let v = v.with_base_and_precision::<2>(p).value();
+		return Some(BigFloatBase::new(fit(v.with_rounding::<HalfEven>(), p)));
+	}
+
+	pub fn with_precision(self, p: usize) -> BigFloatBase {
+		match self.val {
+			None => nan!(),
+			Some(v) => {
+				if v.precision() == p { return BigFloatBase{ val: Some(v) }; }
+				BigFloatBase::new(v.with_precision(p).value())
+			}
+		}
+	}
+
+	pub fn to_f64(&self) -> f64 {
+		match &self.val {
+			None => f64::NAN,
+			Some(v) => v.to_f64().value()
+		}
+	}
+
+	// The exact value of this float.
+	// None if this is NaN.
+	pub fn to_rational(&self) -> Option<BigRational> {
+		let (m, e) = self.val.as_ref()?.repr().clone().into_parts();
+		let m = BigRational::from_integer(BigInt::from_str(&m.to_string()).unwrap());
+		let p = BigRational::from_integer(num::pow(BigInt::from(2), e.unsigned_abs()));
+		return Some(if e < 0 { m / p } else { m * p });
+	}
+
+	pub fn pi(p: usize) -> BigFloatBase { BigFloatBase::new(pi(p)) }
+	pub fn is_nan(&self) -> bool { self.val.is_none() }
+
+	fn one(&self) -> Option<Big> {
+		Some(int(1, self.val.as_ref()?.precision()))
+	}
+}
+
+
+impl ToString for BigFloatBase {
+	fn to_string(&self) -> String {
+		let Some(v) = &self.val else { return "NaN".to_string(); };
+		if v.repr().is_zero() { return "0".to_string(); }
+
+		// Round to a few more digits than we'll show,
+		// dec_to_sci will take care of the rest.
+		// Fixed notation may show every digit we have.
+		let n = match super::get_notation() {
+			super::Notation::Fixed(_) => super::float_digits(),
+			_ => super::show_sig()
+		};
+		let d = v.clone().with_base_and_precision::<10>(n + 2).value();
+		let (m, e) = d.into_repr().into_parts();
+
+		let mut s = m.to_string();
+		let neg = s.starts_with("-");
+		if neg { s = String::from(&s[1..]); }
+
+		let p = e as i64 + s.len() as i64 - 1;
+		let s = s.trim_end_matches('0');
+
+		return dec_to_sci(neg, s.to_string(), p);
+	}
+}
+
+
+// Apply a function to a defined value,
+// returning NaN if the value is undefined.
+macro_rules! with_val {
+	( $s:expr, $v:ident, $x:expr ) => {
+		match &$s.val {
+			None => Some(nan!()),
+			Some($v) => Some(BigFloatBase::new($x))
+		}
+	}
+}
+
+impl ScalarBase for BigFloatBase {
+	fn from_string(s: &str) -> Option<BigFloatBase> {
+		// Use f64 precision if we aren't in big float mode.
+		BigFloatBase::from_string_with_precision(s, get_float_precision().unwrap_or(53))
+	}
+
+	fn fract(&self) -> Option<BigFloatBase> { with_val!(self, v, v.fract()) }
+
+	fn is_zero(&self) -> bool { self.val.as_ref().is_some_and(|v| v.repr().is_zero()) }
+	fn is_one(&self) -> bool { self.val.as_ref().is_some_and(|v| v.repr().is_one()) }
+	fn is_negative(&self) -> bool { self.val.as_ref().is_some_and(|v| v.repr().sign() == dashu_base::Sign::Negative) }
+	fn is_positive(&self) -> bool { self.val.as_ref().is_some_and(|v| v.repr().sign() == dashu_base::Sign::Positive) }
+	fn is_int(&self) -> bool { self.val.as_ref().is_some_and(|v| v.repr().is_int()) }
+
+	fn abs(&self) -> Option<BigFloatBase> { with_val!(self, v, v.clone().abs()) }
+	fn floor(&self) -> Option<BigFloatBase> { with_val!(self, v, v.floor()) }
+	fn ceil(&self) -> Option<BigFloatBase> { with_val!(self, v, v.ceil()) }
+	fn round(&self) -> Option<BigFloatBase> { with_val!(self, v, v.round()) }
+
+	fn sin(&self) -> Option<BigFloatBase> { with_val!(self, v, sin_cos(v).0) }
+	fn cos(&self) -> Option<BigFloatBase> { with_val!(self, v, sin_cos(v).1) }
+
+	fn tan(&self) -> Option<BigFloatBase> {
+		let Some(v) = &self.val else { return Some(nan!()); };
+		let (s, c) = sin_cos(v);
+		if c.repr().is_zero() { return Some(nan!()); }
+		Some(BigFloatBase::new(s / c))
+	}
+
+	fn csc(&self) -> Option<BigFloatBase> { Some(BigFloatBase{ val: self.one() } / self.sin()?) }
+	fn sec(&self) -> Option<BigFloatBase> { Some(BigFloatBase{ val: self.one() } / self.cos()?) }
+	fn cot(&self) -> Option<BigFloatBase> { Some(BigFloatBase{ val: self.one() } / self.tan()?) }
+
+	// asin(x) = atan(x / sqrt(1 - x^2))
+	fn asin(&self) -> Option<BigFloatBase> {
+		let Some(v) = &self.val else { return Some(nan!()); };
+		let p = v.precision();
+		let one = int(1, p);
+
+		if v.clone().abs() > one { return Some(nan!()); }
+		if v.clone().abs() == one {
+			let h = pi(p) / int(2, p);
+			return Some(BigFloatBase::new(if self.is_negative() { -h } else { h }));
+		}
+
+		Some(BigFloatBase::new(atan(&(v.clone() / (one - v.clone() * v.clone()).sqrt()))))
+	}
+
+	// acos(x) = 2 atan(sqrt((1 - x) / (1 + x)))
+	fn acos(&self) -> Option<BigFloatBase> {
+		let Some(v) = &self.val else { return Some(nan!()); };
+		let p = v.precision();
+		let one = int(1, p);
+
+		if v.clone().abs() > one { return Some(nan!()); }
+		if *v == -one.clone() { return Some(BigFloatBase::new(pi(p))); }
+
+		let r = ((one.clone() - v.clone()) / (one + v.clone())).sqrt();
+		Some(BigFloatBase::new(atan(&r) * int(2, p)))
+	}
+
+	fn atan(&self) -> Option<BigFloatBase> { with_val!(self, v, atan(v)) }
+
+	// sinh(x) = m(m + 2) / 2(m + 1), where m = e^x - 1.
+	// This avoids cancellation for small x.
+	fn sinh(&self) -> Option<BigFloatBase> {
+		with_val!(self, v, {
+			let p = v.precision();
+			let m = v.exp_m1();
+			m.clone() * (m.clone() + int(2, p)) / ((m + int(1, p)) * int(2, p))
+		})
+	}
+
+	fn cosh(&self) -> Option<BigFloatBase> {
+		with_val!(self, v, {
+			let e = v.exp();
+			(e.clone() + int(1, v.precision()) / e) / int(2, v.precision())
+		})
+	}
+
+	// tanh(x) = m / (m + 2), where m = e^2x - 1
+	fn tanh(&self) -> Option<BigFloatBase> {
+		with_val!(self, v, {
+			let m = (v.clone() * int(2, v.precision())).exp_m1();
+			m.clone() / (m + int(2, v.precision()))
+		})
+	}
+
+	fn csch(&self) -> Option<BigFloatBase> { Some(BigFloatBase{ val: self.one() } / self.sinh()?) }
+	fn sech(&self) -> Option<BigFloatBase> { Some(BigFloatBase{ val: self.one() } / self.cosh()?) }
+	fn coth(&self) -> Option<BigFloatBase> { Some(BigFloatBase{ val: self.one() } / self.tanh()?) }
+
+	// asinh(x) = ln(1 + |x| + x^2 / (1 + sqrt(x^2 + 1))), with the sign of x
+	fn asinh(&self) -> Option<BigFloatBase> {
+		with_val!(self, v, {
+			let one = int(1, v.precision());
+			let a = v.clone().abs();
+			let s = (a.clone() * a.clone() + one.clone()).sqrt();
+			let r = (a.clone() + a.clone() * a / (one + s)).ln_1p();
+			if self.is_negative() { -r } else { r }
+		})
+	}
+
+	fn acosh(&self) -> Option<BigFloatBase> {
+		let Some(v) = &self.val else { return Some(nan!()); };
+		let one = int(1, v.precision());
+		if *v < one { return Some(nan!()); }
+		Some(BigFloatBase::new((v.clone() + (v.clone() * v.clone() - one).sqrt()).ln()))
+	}
+
+	// atanh(x) = ln(1 + 2x / (1 - x)) / 2
+	fn atanh(&self) -> Option<BigFloatBase> {
+		let Some(v) = &self.val else { return Some(nan!()); };
+		let p = v.precision();
+		let one = int(1, p);
+		if v.clone().abs() >= one { return Some(nan!()); }
+		Some(BigFloatBase::new(
+			(v.clone() * int(2, p) / (one - v.clone())).ln_1p() / int(2, p)
+		))
+	}
+
+	fn exp(&self) -> Option<BigFloatBase> { with_val!(self, v, v.exp()) }
+
+	fn ln(&self) -> Option<BigFloatBase> {
+		if !self.is_positive() || self.is_zero() { return Some(nan!()); }
+		with_val!(self, v, v.ln())
+	}
+
+	fn log10(&self) -> Option<BigFloatBase> {
+		let Some(v) = &self.val else { return Some(nan!()); };
+		let ten = BigFloatBase{ val: Some(int(10, v.precision())) };
+		self.log(ten)
+	}
+
+	fn log2(&self) -> Option<BigFloatBase> {
+		let Some(v) = &self.val else { return Some(nan!()); };
+		let two = BigFloatBase{ val: Some(int(2, v.precision())) };
+		self.log(two)
+	}
+
+	fn log(&self, base: Self) -> Option<Self> {
+		Some(self.ln()? / base.ln()?)
+	}
+
+	fn pow(&self, exp: Self) -> Option<Self> {
+		let (Some(b), Some(e)) = (&self.val, &exp.val) else { return Some(nan!()); };
+		let p = b.precision().max(e.precision());
+
+		if b.repr().is_zero() {
+			if e.repr().is_zero() { return Some(BigFloatBase::new(int(1, p))); }
+			if exp.is_negative() { return Some(nan!()); }
+			return Some(BigFloatBase::new(int(0, p)));
+		}
+
+		if e.repr().is_int() {
+			let n: IBig = e.to_int().value();
+			return Some(BigFloatBase::new(round_to(b.clone(), p).powi(n)));
+		}
+
+		// Fractional powers of negative numbers aren't real
+		if self.is_negative() { return Some(nan!()); }
+
+		Some(BigFloatBase::new(round_to(b.clone(), p).powf(e)))
+	}
+}
+
+
+// Binary operations.
+// Undefined values stay undefined.
+macro_rules! binop {
+	( $a:expr, $b:expr, $x:ident, $y:ident, $f:expr ) => {
+		match ($a.val, $b.val) {
+			(Some($x), Some($y)) => BigFloatBase::new($f),
+			_ => nan!()
+		}
+	}
+}
+
+impl Add for BigFloatBase where {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self::Output {
+		binop!(self, other, a, b, a + b)
+	}
+}
+
+impl AddAssign for BigFloatBase where {
+	fn add_assign(&mut self, other: Self) {
+		*self = self.clone() + other;
+	}
+}
+
+impl Sub for BigFloatBase {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self::Output {
+		binop!(self, other, a, b, a - b)
+	}
+}
+
+impl SubAssign for BigFloatBase where {
+	fn sub_assign(&mut self, other: Self) {
+		*self = self.clone() - other;
+	}
+}
+
+impl Mul for BigFloatBase {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self::Output {
+		binop!(self, other, a, b, a * b)
+	}
+}
+
+impl MulAssign for BigFloatBase where {
+	fn mul_assign(&mut self, other: Self) {
+		*self = self.clone() * other;
+	}
+}
+
+impl Div for BigFloatBase {
+	type Output = Self;
+
+	fn div(self, other: Self) -> Self::Output {
+		if other.is_zero() { return nan!(); }
+		binop!(self, other, a, b, a / b)
+	}
+}
+
+impl DivAssign for BigFloatBase where {
+	fn div_assign(&mut self, other: Self) {
+		*self = self.clone() / other;
+	}
+}
+
+impl Neg for BigFloatBase where {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		match self.val {
+			None => nan!(),
+			Some(v) => BigFloatBase::new(-v)
+		}
+	}
+}
+
+impl Rem<BigFloatBase> for BigFloatBase {
+	type Output = Self;
+
+	fn rem(self, modulus: BigFloatBase) -> Self::Output {
+		if {
+			(!self.is_int()) ||
+			(!modulus.is_int())
+		} { panic!() }
+
+		if modulus.is_zero() { return nan!(); }
+
+		binop!(self, modulus, a, b, {
+			let p = a.precision();
+			// Euclidean modulo, the result is never negative.
+			let b: IBig = b.to_int().value();
+			let mut r: IBig = a.to_int().value() % &b;
+			if r < IBig::ZERO { r += b.abs(); }
+			fit(Big::from(r), p)
+		})
+	}
+}
+
+impl PartialEq for BigFloatBase {
+	fn eq(&self, other: &Self) -> bool {
+		match (&self.val, &other.val) {
+			(Some(a), Some(b)) => a == b,
+			_ => false
+		}
+	}
+}
+
+impl PartialOrd for BigFloatBase {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		match (&self.val, &other.val) {
+			(Some(a), Some(b)) => a.partial_cmp(b),
+			_ => None
+		}
+	}
+}
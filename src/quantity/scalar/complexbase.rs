@@ -0,0 +1,378 @@
+use std::ops::{
+	Add, Sub, Mul, Div,
+	Neg, Rem,
+
+	AddAssign, SubAssign,
+	MulAssign, DivAssign
+};
+
+use std::cmp::Ordering;
+use super::ScalarBase;
+use super::FloatBase;
+use super::float_digits;
+
+
+fn float(s: &str) -> FloatBase { FloatBase::from_string(s).unwrap() }
+fn float_pi() -> FloatBase { FloatBase::pi() }
+
+// Four-quadrant arctangent of y/x.
+fn atan2(y: &FloatBase, x: &FloatBase) -> FloatBase {
+	if x.is_zero() {
+		if y.is_zero() { return float("0"); }
+		let h = float_pi() / float("2");
+		return if y.is_negative() { -h } else { h };
+	}
+
+	let a = (y.clone() / x.clone()).atan().unwrap();
+	if !x.is_negative() { return a; }
+	if y.is_negative() { a - float_pi() } else { a + float_pi() }
+}
+
+fn hypot(a: &FloatBase, b: &FloatBase) -> FloatBase {
+	(a.clone() * a.clone() + b.clone() * b.clone()).pow(float("0.5")).unwrap()
+}
+
+// True if `a` is too small to be seen next to `b`.
+// Used to hide floating-point noise like the imaginary part of e^(iπ).
+fn negligible(a: &FloatBase, b: &FloatBase) -> bool {
+	if a.is_zero() { return true; }
+	if b.is_zero() { return false; }
+	let r = float(&format!("1e{}", float_digits().saturating_sub(4)));
+	return a.abs().unwrap() * r < b.abs().unwrap();
+}
+
+
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct ComplexBase where {
+	pub re: FloatBase,
+	pub im: FloatBase
+}
+
+impl ComplexBase {
+	pub fn new(re: FloatBase, im: FloatBase) -> ComplexBase {
+		return ComplexBase{ re, im };
+	}
+
+	pub fn from_float(re: FloatBase) -> ComplexBase {
+		return ComplexBase{ re, im: float("0") };
+	}
+
+	fn i() -> ComplexBase { ComplexBase::new(float("0"), float("1")) }
+	fn one() -> ComplexBase { ComplexBase::new(float("1"), float("0")) }
+
+	// True if this number has no imaginary part.
+	pub fn is_real(&self) -> bool { self.im.is_zero() }
+
+	pub fn conj(&self) -> ComplexBase {
+		return ComplexBase::new(self.re.clone(), -self.im.clone());
+	}
+
+	pub fn magnitude(&self) -> FloatBase { hypot(&self.re, &self.im) }
+	pub fn arg(&self) -> FloatBase { atan2(&self.im, &self.re) }
+
+	// Principal square root.
+	// We don't use pow(0.5) here, because this is exact
+	// for perfect squares like -1.
+	pub fn sqrt(&self) -> ComplexBase {
+		let r = self.magnitude();
+		let two = float("2");
+
+		let re = ((r.clone() + self.re.clone()) / two.clone()).pow(float("0.5")).unwrap();
+		let mut im = ((r - self.re.clone()) / two).pow(float("0.5")).unwrap();
+		if self.im.is_negative() { im = -im; }
+
+		return ComplexBase::new(re, im);
+	}
+
+	// Writes this number as `r∠θ`, with θ in degrees.
+	pub fn to_polar_string(&self) -> String {
+		let t = self.arg() * float("180") / float_pi();
+		return format!("{}∠{}°", self.magnitude().to_string(), t.to_string());
+	}
+}
+
+impl ToString for ComplexBase {
+	fn to_string(&self) -> String {
+		let show_re = !negligible(&self.re, &self.im);
+		let show_im = !negligible(&self.im, &self.re);
+
+		if !show_im { return self.re.to_string(); }
+
+		let mut s = self.im.abs().unwrap().to_string();
+		if s == "1" { s = String::new(); }
+
+		if !show_re {
+			let neg = if self.im.is_negative() {"-"} else {""};
+			return format!("{neg}{s}i");
+		}
+
+		let sign = if self.im.is_negative() {"-"} else {"+"};
+		return format!("{} {sign} {s}i", self.re.to_string());
+	}
+}
+
+impl ScalarBase for ComplexBase {
+	fn from_string(s: &str) -> Option<ComplexBase> {
+		let v = FloatBase::from_string(s);
+		if v.is_none() { return None; }
+		return Some(ComplexBase::from_float(v.unwrap()));
+	}
+
+	fn fract(&self) -> Option<ComplexBase> {
+		Some(ComplexBase::new(self.re.fract()?, self.im.fract()?))
+	}
+
+	fn is_zero(&self) -> bool { self.re.is_zero() && self.im.is_zero() }
+	fn is_one(&self) -> bool { self.re.is_one() && self.im.is_zero() }
+	fn is_int(&self) -> bool { self.re.is_int() && self.im.is_zero() }
+	fn is_negative(&self) -> bool { self.re.is_negative() && self.im.is_zero() }
+	fn is_positive(&self) -> bool { self.re.is_positive() && self.im.is_zero() }
+
+	// Gaussian rounding: round each part seperately.
+	fn floor(&self) -> Option<ComplexBase> { Some(ComplexBase::new(self.re.floor()?, self.im.floor()?)) }
+	fn ceil(&self) -> Option<ComplexBase> { Some(ComplexBase::new(self.re.ceil()?, self.im.ceil()?)) }
+	fn round(&self) -> Option<ComplexBase> { Some(ComplexBase::new(self.re.round()?, self.im.round()?)) }
+
+	fn abs(&self) -> Option<ComplexBase> { Some(ComplexBase::from_float(self.magnitude())) }
+
+	fn exp(&self) -> Option<ComplexBase> {
+		let r = self.re.exp()?;
+		Some(ComplexBase::new(
+			r.clone() * self.im.cos()?,
+			r * self.im.sin()?
+		))
+	}
+
+	fn sin(&self) -> Option<ComplexBase> {
+		Some(ComplexBase::new(
+			self.re.sin()? * self.im.cosh()?,
+			self.re.cos()? * self.im.sinh()?
+		))
+	}
+
+	fn cos(&self) -> Option<ComplexBase> {
+		Some(ComplexBase::new(
+			self.re.cos()? * self.im.cosh()?,
+			-(self.re.sin()? * self.im.sinh()?)
+		))
+	}
+
+	fn tan(&self) -> Option<ComplexBase> { Some(self.sin()? / self.cos()?) }
+	fn csc(&self) -> Option<ComplexBase> { Some(ComplexBase::one() / self.sin()?) }
+	fn sec(&self) -> Option<ComplexBase> { Some(ComplexBase::one() / self.cos()?) }
+	fn cot(&self) -> Option<ComplexBase> { Some(self.cos()? / self.sin()?) }
+
+	fn sinh(&self) -> Option<ComplexBase> {
+		Some(ComplexBase::new(
+			self.re.sinh()? * self.im.cos()?,
+			self.re.cosh()? * self.im.sin()?
+		))
+	}
+
+	fn cosh(&self) -> Option<ComplexBase> {
+		Some(ComplexBase::new(
+			self.re.cosh()? * self.im.cos()?,
+			self.re.sinh()? * self.im.sin()?
+		))
+	}
+
+	fn tanh(&self) -> Option<ComplexBase> { Some(self.sinh()? / self.cosh()?) }
+	fn csch(&self) -> Option<ComplexBase> { Some(ComplexBase::one() / self.sinh()?) }
+	fn sech(&self) -> Option<ComplexBase> { Some(ComplexBase::one() / self.cosh()?) }
+	fn coth(&self) -> Option<ComplexBase> { Some(self.cosh()? / self.sinh()?) }
+
+	// asin(z) = -i ln(iz + sqrt(1 - z^2))
+	fn asin(&self) -> Option<ComplexBase> {
+		let i = ComplexBase::i();
+		let s = (ComplexBase::one() - self.clone() * self.clone()).sqrt();
+		Some(-i.clone() * (i * self.clone() + s).ln()?)
+	}
+
+	// acos(z) = π/2 - asin(z)
+	fn acos(&self) -> Option<ComplexBase> {
+		let h = ComplexBase::from_float(float_pi() / float("2"));
+		Some(h - self.asin()?)
+	}
+
+	// atan(z) = (i/2) (ln(1 - iz) - ln(1 + iz))
+	fn atan(&self) -> Option<ComplexBase> {
+		let i = ComplexBase::i();
+		let iz = i.clone() * self.clone();
+		let a = (ComplexBase::one() - iz.clone()).ln()?;
+		let b = (ComplexBase::one() + iz).ln()?;
+		Some(i / ComplexBase::from_float(float("2")) * (a - b))
+	}
+
+	// asinh(z) = ln(z + sqrt(z^2 + 1))
+	fn asinh(&self) -> Option<ComplexBase> {
+		let s = (self.clone() * self.clone() + ComplexBase::one()).sqrt();
+		(self.clone() + s).ln()
+	}
+
+	// acosh(z) = ln(z + sqrt(z + 1) sqrt(z - 1))
+	fn acosh(&self) -> Option<ComplexBase> {
+		let a = (self.clone() + ComplexBase::one()).sqrt();
+		let b = (self.clone() - ComplexBase::one()).sqrt();
+		(self.clone() + a * b).ln()
+	}
+
+	// atanh(z) = (ln(1 + z) - ln(1 - z)) / 2
+	fn atanh(&self) -> Option<ComplexBase> {
+		let a = (ComplexBase::one() + self.clone()).ln()?;
+		let b = (ComplexBase::one() - self.clone()).ln()?;
+		Some((a - b) / ComplexBase::from_float(float("2")))
+	}
+
+	fn ln(&self) -> Option<ComplexBase> {
+		if self.is_zero() { return None; }
+		Some(ComplexBase::new(self.magnitude().ln()?, self.arg()))
+	}
+
+	fn log10(&self) -> Option<ComplexBase> { self.log(ComplexBase::from_float(float("10"))) }
+	fn log2(&self) -> Option<ComplexBase> { self.log(ComplexBase::from_float(float("2"))) }
+
+	fn log(&self, base: ComplexBase) -> Option<ComplexBase> {
+		Some(self.ln()? / base.ln()?)
+	}
+
+	fn pow(&self, exp: ComplexBase) -> Option<ComplexBase> {
+		if exp.is_zero() { return Some(ComplexBase::one()); }
+
+		// Like 0^-1, zero to a power with a non-positive real part is undefined.
+		if self.is_zero() {
+			if exp.re.is_zero() || !exp.re.is_positive() { return None; }
+			return Some(ComplexBase::from_float(float("0")));
+		}
+
+		if exp.is_real() && exp.re == float("0.5") {
+			return Some(self.sqrt());
+		}
+
+		// Small integer powers are done by repeated squaring.
+		// This keeps results like i^2 exact.
+		if exp.is_int() && exp.re.abs()? <= float("64") {
+			let mut n = exp.re.abs()?;
+			let mut b = self.clone();
+			let mut r = ComplexBase::one();
+			let two = float("2");
+			while !n.is_zero() {
+				if !(n.clone() % two.clone()).is_zero() {
+					r *= b.clone();
+				}
+				b = b.clone() * b;
+				n = (n / two.clone()).floor()?;
+			}
+
+			if exp.re.is_negative() { r = ComplexBase::one() / r; }
+			return Some(r);
+		}
+
+		(exp * self.ln()?).exp()
+	}
+}
+
+
+impl Add for ComplexBase where {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self::Output {
+		ComplexBase::new(self.re + other.re, self.im + other.im)
+	}
+}
+
+impl AddAssign for ComplexBase where {
+	fn add_assign(&mut self, other: Self) {
+		self.re += other.re;
+		self.im += other.im;
+	}
+}
+
+impl Sub for ComplexBase {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self::Output {
+		ComplexBase::new(self.re - other.re, self.im - other.im)
+	}
+}
+
+impl SubAssign for ComplexBase where {
+	fn sub_assign(&mut self, other: Self) {
+		self.re -= other.re;
+		self.im -= other.im;
+	}
+}
+
+impl Mul for ComplexBase {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self::Output {
+		ComplexBase::new(
+			self.re.clone() * other.re.clone() - self.im.clone() * other.im.clone(),
+			self.re * other.im + self.im * other.re
+		)
+	}
+}
+
+impl MulAssign for ComplexBase where {
+	fn mul_assign(&mut self, other: Self) {
+		*self = self.clone() * other;
+	}
+}
+
+impl Div for ComplexBase {
+	type Output = Self;
+
+	fn div(self, other: Self) -> Self::Output {
+		let d = other.re.clone() * other.re.clone() + other.im.clone() * other.im.clone();
+		ComplexBase::new(
+			(self.re.clone() * other.re.clone() + self.im.clone() * other.im.clone()) / d.clone(),
+			(self.im * other.re - self.re * other.im) / d
+		)
+	}
+}
+
+impl DivAssign for ComplexBase where {
+	fn div_assign(&mut self, other: Self) {
+		*self = self.clone() / other;
+	}
+}
+
+impl Neg for ComplexBase where {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		ComplexBase::new(-self.re, -self.im)
+	}
+}
+
+impl Rem<ComplexBase> for ComplexBase {
+	type Output = Self;
+
+	// Only defined for real integers.
+	// Anything else is NaN, which callers report as an error.
+	fn rem(self, modulus: ComplexBase) -> Self::Output {
+		if {
+			(!self.is_int()) ||
+			(!modulus.is_int())
+		} { return ComplexBase::from_float(FloatBase::nan()); }
+
+		ComplexBase::from_float(self.re % modulus.re)
+	}
+}
+
+impl PartialEq for ComplexBase {
+	fn eq(&self, other: &Self) -> bool {
+		self.re == other.re && self.im == other.im
+	}
+}
+
+impl PartialOrd for ComplexBase {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		if self.is_real() && other.is_real() {
+			return self.re.partial_cmp(&other.re);
+		}
+
+		if self == other { Some(Ordering::Equal) } else { None }
+	}
+}
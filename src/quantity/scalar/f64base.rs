@@ -7,6 +7,7 @@ use std::ops::{
 };
 
 use std::cmp::Ordering;
+use num::rational::BigRational;
 use super::ScalarBase;
 use super::dec_to_sci;
 
@@ -25,6 +26,14 @@ pub struct F64Base where {
 	pub val: f64
 }
 
+impl F64Base {
+	// The exact value of this float.
+	// None if this is NaN or infinite.
+	pub fn to_rational(&self) -> Option<BigRational> {
+		BigRational::from_float(self.val)
+	}
+}
+
 impl ToString for F64Base {
 	fn to_string(&self) -> String {
 		// Remove negative sign from string
@@ -194,7 +203,8 @@ impl Rem<F64Base> for F64Base {
 			(!modulus.is_int())
 		} { panic!() }
 
-		F64Base{val : self.val.round() % modulus.val.round()}
+		// Euclidean modulo, the result is never negative.
+		F64Base{val : self.val.round().rem_euclid(modulus.val.round())}
 	}
 }
 
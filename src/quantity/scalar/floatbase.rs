@@ -1,8 +1,3 @@
-use bigdecimal::BigDecimal;
-use bigdecimal::Zero;
-use bigdecimal::RoundingMode;
-use std::str::FromStr;
-
 use std::ops::{
 	Add, Sub, Mul, Div,
 	Neg, Rem,
@@ -12,169 +7,208 @@ use std::ops::{
 };
 
 use std::cmp::Ordering;
+use num::rational::BigRational;
 
 use super::ScalarBase;
-use super::dec_to_sci;
+use super::get_float_precision;
+use super::f64base::F64Base;
+use super::bigfloatbase::BigFloatBase;
 
 
+// A float backed by either f64 or an arbitrary-precision binary float.
+// Which one we use is decided at runtime by `get_float_precision`.
+//
+// Values created under a different setting (for example, a variable
+// defined before the precision was changed) are converted before
+// we do math with them.
 #[derive(Debug)]
 #[derive(Clone)]
-pub struct FloatBase where {
-	pub val: BigDecimal
+pub enum FloatBase {
+	F64(F64Base),
+	Big(BigFloatBase)
 }
 
 impl FloatBase {
-	pub fn new(s: &str) -> FloatBase {
-		return FloatBase {
-			val: s.parse().unwrap()
-		};
+	// Convert this float to the current backend.
+	fn current(self) -> FloatBase {
+		match (self, get_float_precision()) {
+			(FloatBase::F64(v), None) => FloatBase::F64(v),
+			(FloatBase::Big(v), Some(p)) => FloatBase::Big(v.with_precision(p)),
+
+			(FloatBase::F64(v), Some(p)) => FloatBase::Big(
+				BigFloatBase::from_string_with_precision(&v.val.to_string(), p).unwrap()
+			),
+			(FloatBase::Big(v), None) => FloatBase::F64(F64Base{ val: v.to_f64() }),
+		}
 	}
-}
-
-
-impl ToString for FloatBase {
-	fn to_string(&self) -> String {
 
-		if self.val.is_nan() {
-			return "NaN".to_string();
-		} else if self.val.is_inf_neg() {
-			return "-Inf".to_string();
-		} else if self.val.is_inf_pos() {
-			return "+Inf".to_string();
+	pub fn pi() -> FloatBase {
+		match get_float_precision() {
+			None => FloatBase::F64(F64Base{ val: std::f64::consts::PI }),
+			Some(p) => FloatBase::Big(BigFloatBase::pi(p))
 		}
+	}
 
+	pub fn nan() -> FloatBase {
+		FloatBase::from_string("NaN").unwrap()
+	}
 
-		// Already in scientific notation,we just need to trim significant digits.
-		let mut _a = self.val.round(32, astro_float::RoundingMode::Up).to_string();
-		let mut _b = _a.split('e');
-
-		let mut s = String::from(_b.next().unwrap()); // Decimal
-		let p: i64 = _b.next().unwrap().parse().unwrap(); // Exponent
+	// The exact value of this float.
+	// None if this is NaN or infinite.
+	pub fn to_rational(&self) -> Option<BigRational> {
+		match self {
+			FloatBase::F64(v) => v.to_rational(),
+			FloatBase::Big(v) => v.to_rational()
+		}
+	}
 
-		// Remove negative sign from string
-		let neg = s.starts_with("-");
-		if neg { s = String::from(&s[1..]); }
+	pub fn is_nan(&self) -> bool {
+		match self {
+			FloatBase::F64(v) => v.val.is_nan(),
+			FloatBase::Big(v) => v.is_nan()
+		}
+	}
 
-		// We no longer need a decimal point in our string.
-		// also, trim off leading zeros and adjust power.
-		let mut s: &str = &s.replace(".", "");
-		s = &s[0..];
-		s = s.trim_end_matches('0');
-		s = s.trim_start_matches('0');
+	// A float slightly smaller than this one.
+	// For f64, this is the next float down.
+	// Big floats don't have a "next" float,
+	// so we step two units in the last place.
+	pub fn next_down(&self) -> FloatBase {
+		match (self.clone().current(), get_float_precision()) {
+			(FloatBase::F64(v), _) => FloatBase::F64(F64Base{ val: v.val.next_down() }),
+			(v, Some(p)) => {
+				let u = FloatBase::from_string("2").unwrap()
+					.pow(FloatBase::from_string(&(2 - p as i64).to_string()).unwrap()).unwrap();
+				v.clone() - v.abs().unwrap() * u
+			},
+			_ => unreachable!()
+		}
+	}
 
-		return dec_to_sci(neg, s.to_string(), p);
+	// A float slightly larger than this one.
+	pub fn next_up(&self) -> FloatBase {
+		return -(-self.clone()).next_down();
 	}
 }
 
 
-
-impl ScalarBase for FloatBase {
-
-	fn from_string(s: &str) -> Option<FloatBase> {
-		let v = BigDecimal::from_str(s);
-		let v = match v {
-			Ok(x) => x,
-			Err(_) => return None
-		};
-
-		return Some(FloatBase{ val: v });
+impl ToString for FloatBase {
+	fn to_string(&self) -> String {
+		match self {
+			FloatBase::F64(v) => v.to_string(),
+			FloatBase::Big(v) => v.to_string()
+		}
 	}
+}
 
-	//foward!(fract);
-
-	fn is_zero(&self) -> bool {self.val.is_zero()}
-	fn is_one(&self) -> bool {self.val == BigDecimal::from_str("1").unwrap()}
-	fn is_negative(&self) -> bool { self.val.sign() == num::bigint::Sign::Minus }
-	fn is_positive(&self) -> bool { self.val.sign() == num::bigint::Sign::Plus }
-
-	fn is_int(&self) -> bool { self.val.is_integer() }
-
-	fn abs(&self) -> Option<FloatBase> { Some(FloatBase{ val: self.val.abs() }) }
-	fn round(&self) -> Option<FloatBase> { Some(FloatBase{ val: self.val.round(0) }) }
 
-	fn floor(&self) -> Option<FloatBase> {
-		let (_, scale) = self.val.as_bigint_and_exponent();
-		Some(FloatBase{ val: self.val.with_scale_round(scale, RoundingMode::Down) })
+macro_rules! foward {
+	( $x:ident ) => {
+		fn $x(&self) -> Option<FloatBase> {
+			match self.clone().current() {
+				FloatBase::F64(v) => Some(FloatBase::F64(v.$x()?)),
+				FloatBase::Big(v) => Some(FloatBase::Big(v.$x()?))
+			}
+		}
 	}
+}
 
-	fn ceil(&self) -> Option<FloatBase> {
-		let (_, scale) = self.val.as_bigint_and_exponent();
-		Some(FloatBase{ val: self.val.with_scale_round(scale, RoundingMode::Up) })
+macro_rules! foward_bool {
+	( $x:ident ) => {
+		fn $x(&self) -> bool {
+			match self {
+				FloatBase::F64(v) => v.$x(),
+				FloatBase::Big(v) => v.$x()
+			}
+		}
 	}
+}
 
-	fn fract(&self) -> Option<FloatBase> { Some(self.clone() - self.floor().unwrap()) }
-
-
-	fn sin(&self) -> Option<FloatBase> {
-		let c0: BigDecimal = "1.276278962".parse().unwrap();
-		let c1: BigDecimal = "-.285261569".parse().unwrap();
-		let c2: BigDecimal = "0.009118016".parse().unwrap();
-		let c3: BigDecimal = "-.000136587".parse().unwrap();
-		let c4: BigDecimal = "0.000001185".parse().unwrap();
-		let c5: BigDecimal = "-.000000007".parse().unwrap();
-		
-
-		// z should be between -0.25 to 0.25 (percent of a full circle)
-		let z: BigDecimal = self.val.clone() / 360f64;
-		let w = BigDecimal::from(4) * z;
-		let x: BigDecimal = 2 * w.clone() * w.clone() - 1;
-
-		let p = (
-			c0 * 1 +
-			c1 * x.clone() +
-			c2 * (2 * x.clone()*x.clone() - 1) +
-			c3 * (4 * x.clone()*x.clone()*x.clone() - 3 * x.clone()) +
-			c4 * (8 * x.clone()*x.clone()*x.clone()*x.clone() - 8 * x.clone()*x.clone() + 1) +
-			c5 * (16 * x.clone()*x.clone()*x.clone()*x.clone()*x.clone() - 20 * x.clone()*x.clone()*x.clone() + 5 * x.clone())
-		) * w;
-
-		return Some(FloatBase{ val: p })
+impl ScalarBase for FloatBase {
+	fn from_string(s: &str) -> Option<FloatBase> {
+		match get_float_precision() {
+			None => Some(FloatBase::F64(F64Base::from_string(s)?)),
+			Some(p) => Some(FloatBase::Big(BigFloatBase::from_string_with_precision(s, p)?))
+		}
 	}
-	fn cos(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn tan(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn csc(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn sec(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn cot(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn asin(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn acos(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn atan(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn sinh(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn cosh(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn tanh(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn csch(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn sech(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn coth(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn asinh(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn acosh(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn atanh(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn exp(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn ln(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn log10(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
-	fn log2(&self) -> Option<FloatBase> { Some(FloatBase{ val: "1".parse().unwrap() }) }
 
-
-	fn log(&self, _base: FloatBase) -> Option<FloatBase> {
-		Some(FloatBase{ val: "1".parse().unwrap() })
+	foward!(fract);
+
+	foward_bool!(is_zero);
+	foward_bool!(is_one);
+	foward_bool!(is_negative);
+	foward_bool!(is_positive);
+	foward_bool!(is_int);
+
+	foward!(abs);
+	foward!(floor);
+	foward!(ceil);
+	foward!(round);
+
+	foward!(sin);
+	foward!(cos);
+	foward!(tan);
+	foward!(csc);
+	foward!(sec);
+	foward!(cot);
+	foward!(asin);
+	foward!(acos);
+	foward!(atan);
+
+	foward!(sinh);
+	foward!(cosh);
+	foward!(tanh);
+	foward!(csch);
+	foward!(sech);
+	foward!(coth);
+	foward!(asinh);
+	foward!(acosh);
+	foward!(atanh);
+
+	foward!(exp);
+	foward!(ln);
+	foward!(log10);
+	foward!(log2);
+
+	fn log(&self, base: Self) -> Option<Self> {
+		match (self.clone().current(), base.current()) {
+			(FloatBase::F64(a), FloatBase::F64(b)) => Some(FloatBase::F64(a.log(b)?)),
+			(FloatBase::Big(a), FloatBase::Big(b)) => Some(FloatBase::Big(a.log(b)?)),
+			_ => unreachable!()
+		}
 	}
 
-	fn pow(&self, _base: FloatBase) -> Option<FloatBase> {
-		Some(FloatBase{ val: "1".parse().unwrap() })
+	fn pow(&self, exp: Self) -> Option<Self> {
+		match (self.clone().current(), exp.current()) {
+			(FloatBase::F64(a), FloatBase::F64(b)) => Some(FloatBase::F64(a.pow(b)?)),
+			(FloatBase::Big(a), FloatBase::Big(b)) => Some(FloatBase::Big(a.pow(b)?)),
+			_ => unreachable!()
+		}
 	}
-
 }
 
 
+macro_rules! binop {
+	( $a:expr, $b:expr, $op:tt ) => {
+		match ($a.current(), $b.current()) {
+			(FloatBase::F64(a), FloatBase::F64(b)) => FloatBase::F64(a $op b),
+			(FloatBase::Big(a), FloatBase::Big(b)) => FloatBase::Big(a $op b),
+			_ => unreachable!()
+		}
+	}
+}
+
 impl Add for FloatBase where {
 	type Output = Self;
 
 	fn add(self, other: Self) -> Self::Output {
-		Self { val: self.val + other.val}
+		binop!(self, other, +)
 	}
 }
 
 impl AddAssign for FloatBase where {
 	fn add_assign(&mut self, other: Self) {
-		self.val += other.val;
+		*self = self.clone() + other;
 	}
 }
 
@@ -182,13 +216,13 @@ impl Sub for FloatBase {
 	type Output = Self;
 
 	fn sub(self, other: Self) -> Self::Output {
-		Self {val: self.val - other.val}
+		binop!(self, other, -)
 	}
 }
 
 impl SubAssign for FloatBase where {
 	fn sub_assign(&mut self, other: Self) {
-		self.val -= other.val;
+		*self = self.clone() - other;
 	}
 }
 
@@ -196,13 +230,13 @@ impl Mul for FloatBase {
 	type Output = Self;
 
 	fn mul(self, other: Self) -> Self::Output {
-		Self {val: self.val * other.val}
+		binop!(self, other, *)
 	}
 }
 
 impl MulAssign for FloatBase where {
 	fn mul_assign(&mut self, other: Self) {
-		self.val *= other.val;
+		*self = self.clone() * other;
 	}
 }
 
@@ -210,13 +244,13 @@ impl Div for FloatBase {
 	type Output = Self;
 
 	fn div(self, other: Self) -> Self::Output {
-		Self {val: self.val / other.val}
+		binop!(self, other, /)
 	}
 }
 
 impl DivAssign for FloatBase where {
 	fn div_assign(&mut self, other: Self) {
-		self.val = self.val.clone() / other.val;
+		*self = self.clone() / other;
 	}
 }
 
@@ -224,7 +258,10 @@ impl Neg for FloatBase where {
 	type Output = Self;
 
 	fn neg(self) -> Self::Output {
-		Self {val: -self.val}
+		match self {
+			FloatBase::F64(v) => FloatBase::F64(-v),
+			FloatBase::Big(v) => FloatBase::Big(-v)
+		}
 	}
 }
 
@@ -232,23 +269,26 @@ impl Rem<FloatBase> for FloatBase {
 	type Output = Self;
 
 	fn rem(self, modulus: FloatBase) -> Self::Output {
-		if {
-			(!self.is_int()) ||
-			(!modulus.is_int())
-		} { panic!() }
-
-		FloatBase{val : self.val.round(0) % modulus.val.round(0)}
+		binop!(self, modulus, %)
 	}
 }
 
 impl PartialEq for FloatBase {
 	fn eq(&self, other: &Self) -> bool {
-		self.val == other.val
+		match (self.clone().current(), other.clone().current()) {
+			(FloatBase::F64(a), FloatBase::F64(b)) => a == b,
+			(FloatBase::Big(a), FloatBase::Big(b)) => a == b,
+			_ => unreachable!()
+		}
 	}
 }
 
 impl PartialOrd for FloatBase {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		self.val.partial_cmp(&other.val)
+		match (self.clone().current(), other.clone().current()) {
+			(FloatBase::F64(a), FloatBase::F64(b)) => a.partial_cmp(&b),
+			(FloatBase::Big(a), FloatBase::Big(b)) => a.partial_cmp(&b),
+			_ => unreachable!()
+		}
 	}
-}
\ No newline at end of file
+}
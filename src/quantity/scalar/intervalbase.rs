@@ -0,0 +1,371 @@
+use std::ops::{
+	Add, Sub, Mul, Div,
+	Neg, Rem,
+
+	AddAssign, SubAssign,
+	MulAssign, DivAssign
+};
+
+use std::cmp::Ordering;
+use super::ScalarBase;
+use super::Scalar;
+use super::FloatBase;
+
+
+// Basic arithmetic rounds to nearest, so the exact answer
+// is at most one step away. Library functions like sin and exp
+// aren't always that good, so we step further for them.
+const FN_STEPS: usize = 4;
+
+fn rational(s: &str) -> Scalar { Scalar::new_rational_from_string(s).unwrap() }
+fn pi() -> Scalar { Scalar::Float{ v: FloatBase::pi() } }
+
+// Move a float n steps down.
+// Rationals are exact, so they stay where they are.
+fn down(x: Scalar, n: usize) -> Scalar {
+	let Scalar::Float{mut v} = x else { return x; };
+	for _ in 0..n { v = v.next_down(); }
+	return Scalar::Float{v};
+}
+
+fn up(x: Scalar, n: usize) -> Scalar {
+	let Scalar::Float{mut v} = x else { return x; };
+	for _ in 0..n { v = v.next_up(); }
+	return Scalar::Float{v};
+}
+
+fn min(a: Scalar, b: Scalar) -> Scalar { if b < a { b } else { a } }
+fn max(a: Scalar, b: Scalar) -> Scalar { if b > a { b } else { a } }
+
+// Scalar functions give NaN or complex numbers outside their real domain
+fn real(x: Scalar) -> Option<Scalar> {
+	if x.is_nan() || x.is_complex() { return None; }
+	return Some(x);
+}
+
+
+// A closed interval of real numbers.
+// The ends are rationals or floats. Rationals are exact, and
+// every float result is rounded outward (`lo` down, `hi` up),
+// so the exact answer is always inside the result.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct IntervalBase where {
+	pub lo: Scalar,
+	pub hi: Scalar
+}
+
+impl IntervalBase {
+	pub fn new(lo: Scalar, hi: Scalar) -> IntervalBase {
+		if hi < lo { return IntervalBase{ lo: hi, hi: lo }; }
+		return IntervalBase{ lo, hi };
+	}
+
+	pub fn from_scalar(x: Scalar) -> IntervalBase {
+		return IntervalBase{ lo: x.clone(), hi: x };
+	}
+
+	fn nan() -> IntervalBase { IntervalBase::from_scalar(Scalar::Float{ v: FloatBase::nan() }) }
+	fn one() -> IntervalBase { IntervalBase::from_scalar(rational("1")) }
+
+	pub fn is_point(&self) -> bool { self.lo == self.hi }
+	pub fn is_nan(&self) -> bool { self.lo.is_nan() || self.hi.is_nan() }
+	pub fn contains_zero(&self) -> bool { !self.lo.is_positive() && !self.hi.is_negative() }
+
+	// Apply a monotonic function to both ends
+	fn monotonic(&self, f: impl Fn(&Scalar) -> Scalar, increasing: bool) -> Option<IntervalBase> {
+		let a = real(f(&self.lo))?;
+		let b = real(f(&self.hi))?;
+		if increasing {
+			return Some(IntervalBase{ lo: down(a, FN_STEPS), hi: up(b, FN_STEPS) });
+		} else {
+			return Some(IntervalBase{ lo: down(b, FN_STEPS), hi: up(a, FN_STEPS) });
+		}
+	}
+
+	// True if x + kp is in this interval for some integer k
+	fn hits(&self, x: Scalar, p: Scalar) -> bool {
+		let k = ((self.lo.clone() - x.clone()) / p.clone()).ceil();
+		return x + k * p <= self.hi;
+	}
+
+	// sin and cos: the ends of the result come from the ends of this
+	// interval, unless this interval contains a peak or a trough.
+	fn periodic(&self, f: impl Fn(&Scalar) -> Scalar, peak: Scalar) -> Option<IntervalBase> {
+		let two_pi = pi() * rational("2");
+		if self.hi.clone() - self.lo.clone() >= two_pi {
+			return Some(IntervalBase::new(rational("-1"), rational("1")));
+		}
+
+		let a = real(f(&self.lo))?;
+		let b = real(f(&self.hi))?;
+		let mut lo = max(down(min(a.clone(), b.clone()), FN_STEPS), rational("-1"));
+		let mut hi = min(up(max(a, b), FN_STEPS), rational("1"));
+		if self.hits(peak.clone(), two_pi.clone()) { hi = rational("1"); }
+		if self.hits(peak + pi(), two_pi) { lo = rational("-1"); }
+		return Some(IntervalBase{ lo, hi });
+	}
+}
+
+impl ToString for IntervalBase {
+	fn to_string(&self) -> String {
+		return format!("[{}, {}]", self.lo.to_string(), self.hi.to_string());
+	}
+}
+
+impl ScalarBase for IntervalBase {
+	fn from_string(s: &str) -> Option<IntervalBase> {
+		return Some(IntervalBase::from_scalar(Scalar::new_rational_from_string(s)?));
+	}
+
+	fn fract(&self) -> Option<IntervalBase> {
+		if self.lo.floor() != self.hi.floor() {
+			return Some(IntervalBase::new(rational("0"), rational("1")));
+		}
+		return Some(IntervalBase::new(self.lo.fract(), self.hi.fract()));
+	}
+
+	fn is_zero(&self) -> bool { self.lo.is_zero() && self.hi.is_zero() }
+	fn is_one(&self) -> bool { self.lo.is_one() && self.hi.is_one() }
+	fn is_int(&self) -> bool { self.is_point() && self.lo.is_int() }
+	fn is_negative(&self) -> bool { self.hi.is_negative() }
+	fn is_positive(&self) -> bool { self.lo.is_positive() }
+
+	// Rounding gives exact integers, so we don't widen these.
+	fn floor(&self) -> Option<IntervalBase> { Some(IntervalBase::new(self.lo.floor(), self.hi.floor())) }
+	fn ceil(&self) -> Option<IntervalBase> { Some(IntervalBase::new(self.lo.ceil(), self.hi.ceil())) }
+	fn round(&self) -> Option<IntervalBase> { Some(IntervalBase::new(self.lo.round(), self.hi.round())) }
+
+	fn abs(&self) -> Option<IntervalBase> {
+		if !self.lo.is_negative() { return Some(self.clone()); }
+		if !self.hi.is_positive() { return Some(-self.clone()); }
+		return Some(IntervalBase::new(rational("0"), max(-self.lo.clone(), self.hi.clone())));
+	}
+
+	fn exp(&self) -> Option<IntervalBase> { self.monotonic(Scalar::exp, true) }
+
+	fn sin(&self) -> Option<IntervalBase> { self.periodic(Scalar::sin, pi() / rational("2")) }
+	fn cos(&self) -> Option<IntervalBase> { self.periodic(Scalar::cos, rational("0")) }
+
+	fn tan(&self) -> Option<IntervalBase> {
+		if self.hits(pi() / rational("2"), pi()) { return None; }
+		self.monotonic(Scalar::tan, true)
+	}
+
+	fn csc(&self) -> Option<IntervalBase> { Some(IntervalBase::one() / self.sin()?) }
+	fn sec(&self) -> Option<IntervalBase> { Some(IntervalBase::one() / self.cos()?) }
+	fn cot(&self) -> Option<IntervalBase> { Some(IntervalBase::one() / self.tan()?) }
+
+	fn asin(&self) -> Option<IntervalBase> {
+		if self.lo < rational("-1") || self.hi > rational("1") { return None; }
+		self.monotonic(Scalar::asin, true)
+	}
+
+	fn acos(&self) -> Option<IntervalBase> {
+		if self.lo < rational("-1") || self.hi > rational("1") { return None; }
+		self.monotonic(Scalar::acos, false)
+	}
+
+	fn atan(&self) -> Option<IntervalBase> { self.monotonic(Scalar::atan, true) }
+
+	fn sinh(&self) -> Option<IntervalBase> { self.monotonic(Scalar::sinh, true) }
+	fn cosh(&self) -> Option<IntervalBase> { self.abs()?.monotonic(Scalar::cosh, true) }
+	fn tanh(&self) -> Option<IntervalBase> { self.monotonic(Scalar::tanh, true) }
+	fn csch(&self) -> Option<IntervalBase> { Some(IntervalBase::one() / self.sinh()?) }
+	fn sech(&self) -> Option<IntervalBase> { Some(IntervalBase::one() / self.cosh()?) }
+	fn coth(&self) -> Option<IntervalBase> { Some(IntervalBase::one() / self.tanh()?) }
+
+	fn asinh(&self) -> Option<IntervalBase> { self.monotonic(Scalar::asinh, true) }
+
+	fn acosh(&self) -> Option<IntervalBase> {
+		if self.lo < rational("1") { return None; }
+		self.monotonic(Scalar::acosh, true)
+	}
+
+	fn atanh(&self) -> Option<IntervalBase> {
+		if self.lo <= rational("-1") || self.hi >= rational("1") { return None; }
+		self.monotonic(Scalar::atanh, true)
+	}
+
+	fn ln(&self) -> Option<IntervalBase> {
+		if !self.lo.is_positive() { return None; }
+		self.monotonic(Scalar::ln, true)
+	}
+
+	fn log10(&self) -> Option<IntervalBase> {
+		if !self.lo.is_positive() { return None; }
+		self.monotonic(Scalar::log10, true)
+	}
+
+	fn log2(&self) -> Option<IntervalBase> {
+		if !self.lo.is_positive() { return None; }
+		self.monotonic(Scalar::log2, true)
+	}
+
+	fn log(&self, base: IntervalBase) -> Option<IntervalBase> {
+		let b = base.ln()?;
+		if b.contains_zero() { return None; }
+		Some(self.ln()? / b)
+	}
+
+	fn pow(&self, exp: IntervalBase) -> Option<IntervalBase> {
+		// Integer powers work for negative numbers.
+		// Even powers are symmetric, odd powers are increasing.
+		if exp.is_int() {
+			let n = exp.lo.clone();
+			if n.is_zero() { return Some(IntervalBase::one()); }
+
+			let m = n.abs();
+			let b = if (m.clone() % rational("2")).is_zero() { self.abs()? } else { self.clone() };
+			let r = b.monotonic(|x| x.pow(m.clone()), true)?;
+			if n.is_negative() { return Some(IntervalBase::one() / r); }
+			return Some(r);
+		}
+
+		// x^y is monotonic in both x and y when x is positive,
+		// so its extremes are at the corners.
+		if self.lo.is_negative() { return None; }
+		// Float exponents like 0.5 are made exact,
+		// so that sqrt([4, 9]) is exactly [2, 3].
+		let mut v = Vec::with_capacity(4);
+		for x in [&self.lo, &self.hi] {
+			for y in [&exp.lo, &exp.hi] {
+				let y = y.to_rational().unwrap_or(y.clone());
+				let r = real(x.pow(y.clone()))?;
+
+				// Roots of perfect powers, like sqrt(4), are exact
+				let r = match r.to_rational() {
+					Some(q) if x.is_rational() && !y.is_zero() => {
+						let k = rational("1") / y;
+						if k.is_int() && q.pow(k) == *x { q } else { r }
+					},
+					_ => r
+				};
+				v.push(r);
+			}
+		}
+		let lo = v.iter().cloned().reduce(min)?;
+		let hi = v.into_iter().reduce(max)?;
+		return Some(IntervalBase{ lo: down(lo, FN_STEPS), hi: up(hi, FN_STEPS) });
+	}
+}
+
+
+impl Add for IntervalBase where {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self::Output {
+		IntervalBase{
+			lo: down(self.lo + other.lo, 1),
+			hi: up(self.hi + other.hi, 1)
+		}
+	}
+}
+
+impl AddAssign for IntervalBase where {
+	fn add_assign(&mut self, other: Self) {
+		*self = self.clone() + other;
+	}
+}
+
+impl Sub for IntervalBase {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self::Output {
+		self + (-other)
+	}
+}
+
+impl SubAssign for IntervalBase where {
+	fn sub_assign(&mut self, other: Self) {
+		*self = self.clone() - other;
+	}
+}
+
+impl Mul for IntervalBase {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self::Output {
+		let v = [
+			self.lo.clone() * other.lo.clone(),
+			self.lo * other.hi.clone(),
+			self.hi.clone() * other.lo,
+			self.hi * other.hi
+		];
+		let lo = v.iter().cloned().reduce(min).unwrap();
+		let hi = v.into_iter().reduce(max).unwrap();
+		IntervalBase{ lo: down(lo, 1), hi: up(hi, 1) }
+	}
+}
+
+impl MulAssign for IntervalBase where {
+	fn mul_assign(&mut self, other: Self) {
+		*self = self.clone() * other;
+	}
+}
+
+impl Div for IntervalBase {
+	type Output = Self;
+
+	// Dividing by an interval that contains zero
+	// gives an unbounded result, which we can't represent.
+	fn div(self, other: Self) -> Self::Output {
+		if other.contains_zero() { return IntervalBase::nan(); }
+
+		let v = [
+			self.lo.clone() / other.lo.clone(),
+			self.lo / other.hi.clone(),
+			self.hi.clone() / other.lo,
+			self.hi / other.hi
+		];
+		let lo = v.iter().cloned().reduce(min).unwrap();
+		let hi = v.into_iter().reduce(max).unwrap();
+		IntervalBase{ lo: down(lo, 1), hi: up(hi, 1) }
+	}
+}
+
+impl DivAssign for IntervalBase where {
+	fn div_assign(&mut self, other: Self) {
+		*self = self.clone() / other;
+	}
+}
+
+impl Neg for IntervalBase where {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		IntervalBase{ lo: -self.hi, hi: -self.lo }
+	}
+}
+
+impl Rem<IntervalBase> for IntervalBase {
+	type Output = Self;
+
+	// Remainders jump, so all we know in general is
+	// that the result is smaller than the modulus.
+	fn rem(self, modulus: IntervalBase) -> Self::Output {
+		if self.is_point() && modulus.is_point() {
+			return IntervalBase::from_scalar(self.lo % modulus.lo);
+		}
+
+		let m = max(modulus.lo.abs(), modulus.hi.abs());
+		IntervalBase::new(-m.clone(), m)
+	}
+}
+
+impl PartialEq for IntervalBase {
+	fn eq(&self, other: &Self) -> bool {
+		self.lo == other.lo && self.hi == other.hi
+	}
+}
+
+// One interval is less than another if all its values are.
+// Overlapping intervals can't be compared.
+impl PartialOrd for IntervalBase {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		if self == other { return Some(Ordering::Equal); }
+		if self.hi < other.lo { return Some(Ordering::Less); }
+		if self.lo > other.hi { return Some(Ordering::Greater); }
+		return None;
+	}
+}
@@ -1,19 +1,85 @@
-//const FLOAT_PRECISION: u32 = 1024;
-const SHOW_SIG: usize = 5; // How many significant digits we will show in output
+use std::cell::Cell;
+
 const MAX_LEN: usize = 5; // If a scientific exponent is >= this value, do not use scientific notation.
 
 pub(in self) mod rationalbase;
+pub(in self) mod complexbase;
+pub(in self) mod intervalbase;
+pub(in self) mod uncertainbase;
+pub(in self) mod symbolicbase;
 
 
-// Pick a float implementation.
-// floatbase is high-precision, f64base is for testing.
-
-//pub(in self) mod floatbase;
-//pub use floatbase::FloatBase;
-
+// Floats are either plain f64s or arbitrary-precision
+// binary floats. floatbase picks one at runtime.
 pub(in self) mod f64base;
-pub use f64base::F64Base as FloatBase;
-
+pub(in self) mod bigfloatbase;
+pub(in self) mod floatbase;
+pub use floatbase::FloatBase;
+
+pub(in self) mod special;
+
+
+// How we write decimal numbers
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub enum Notation {
+	// Scientific notation for very large and very small numbers,
+	// plain decimals otherwise.
+	Auto,
+
+	// Always show this many digits after the decimal point
+	Fixed(usize),
+
+	// Always use scientific notation
+	Scientific,
+
+	// Scientific notation with exponents that are multiples of 3
+	Engineering
+}
+
+
+thread_local!(
+	// Precision of our floats, in bits.
+	// None means we use f64.
+	//
+	// This is kept in sync with Config by Config::check().
+	static FLOAT_PRECISION: Cell<Option<usize>> = const { Cell::new(None) };
+
+	// How we print numbers. See Config.
+	// These are also kept in sync by Config::check().
+	static SIG_DIGITS: Cell<Option<usize>> = const { Cell::new(None) };
+	static NOTATION: Cell<Notation> = const { Cell::new(Notation::Auto) };
+	static DIGIT_SEPARATOR: Cell<Option<char>> = const { Cell::new(None) };
+);
+
+pub fn set_float_precision(p: Option<usize>) { FLOAT_PRECISION.with(|x| x.set(p)); }
+pub fn get_float_precision() -> Option<usize> { FLOAT_PRECISION.with(|x| x.get()) }
+
+pub fn set_number_format(sig: Option<usize>, notation: Notation, separator: Option<char>) {
+	SIG_DIGITS.with(|x| x.set(sig));
+	NOTATION.with(|x| x.set(notation));
+	DIGIT_SEPARATOR.with(|x| x.set(separator));
+}
+
+pub(in self) fn get_notation() -> Notation { NOTATION.with(|x| x.get()) }
+
+// How many decimal digits our floats can hold
+pub(in self) fn float_digits() -> usize {
+	let bits = get_float_precision().unwrap_or(53);
+	return (bits as f64 * std::f64::consts::LOG10_2) as usize;
+}
+
+// How many significant digits we will show in output.
+// We drop a few digits from high-precision floats to hide rounding error.
+pub(in self) fn show_sig() -> usize {
+	if let Some(n) = SIG_DIGITS.with(|x| x.get()) { return n; }
+
+	match get_float_precision() {
+		None => 5,
+		Some(_) => float_digits().saturating_sub(2).max(1)
+	}
+}
 
 
 mod scalar;
@@ -23,7 +89,7 @@ pub use self::scalar::ScalarBase;
 
 
 // Convert a string to scientific notation,
-// with parameters SHOW_SIG and MAX_LEN.
+// with parameters show_sig(), get_notation(), and MAX_LEN.
 //
 // input:
 //  neg: true if negative
@@ -34,15 +100,36 @@ pub use self::scalar::ScalarBase;
 //  So, (-1)^(neg) + (s * 10^p) should give us our number.
 
 #[allow(dead_code)]
-pub(in self) fn dec_to_sci(neg: bool, mut s: String, p: i64) -> String {
+pub(in self) fn dec_to_sci(neg: bool, mut s: String, mut p: i64) -> String {
+	let notation = get_notation();
+
+	// In fixed mode, the number of digits we show
+	// depends on the size of the number.
+	let show_sig = match notation {
+		Notation::Fixed(n) => {
+			let k = p + 1 + n as i64;
+			if k > 0 {
+				k as usize
+			} else if k == 0 && s.starts_with(['5', '6', '7', '8', '9']) {
+				// This rounds up to the last place we show
+				s = String::from("1");
+				p += 1;
+				1
+			} else {
+				return positional("", 0, Some(n));
+			}
+		},
+		_ => show_sig()
+	};
+
 	// Pick significant digits and round
-	if s.len() > SHOW_SIG {
+	if s.len() > show_sig {
 		let round;
-		if s.len() != SHOW_SIG + 1 {
-			round = s[SHOW_SIG..SHOW_SIG+1].parse().unwrap();
+		if s.len() != show_sig + 1 {
+			round = s[show_sig..show_sig+1].parse().unwrap();
 		} else { round = 0; }
 
-		s = String::from(&s[0..SHOW_SIG]);
+		s = String::from(&s[0..show_sig]);
 
 		if round >= 5 {
 			let new = s[s.len()-1..s.len()].parse::<u8>().unwrap() + 1u8;
@@ -52,41 +139,76 @@ pub(in self) fn dec_to_sci(neg: bool, mut s: String, p: i64) -> String {
 		}
 	}
 
-	s = format!("{s}{}", "0".repeat(SHOW_SIG - s.len()));
-	// at this point, s is guaranteed to have exactly SHOW_SIG digits.
-
-	let neg = if neg {"-"} else {""};
-
-	if (p.abs() as usize) < MAX_LEN {
-		// Print whole decimal
-
-		if p >= 0 {
-			let q = p as usize;
-
-			let first = &s[0..q+1];
-			let mut rest = &s[q+1..];
-			rest = rest.trim_end_matches('0');
-			if rest == "" {
-				return format!("{neg}{first}");
-			} else {
-				return format!("{neg}{first}.{rest}");
-			}
+	s = format!("{s}{}", "0".repeat(show_sig - s.len()));
+	// at this point, s is guaranteed to have exactly show_sig digits.
+
+	// Don't print -0
+	let neg = if neg && s.contains(|c| c != '0') {"-"} else {""};
+
+	let r = match notation {
+		Notation::Fixed(n) => positional(&s, p, Some(n)),
+		Notation::Scientific => scientific(&s, p),
+		Notation::Engineering => {
+			let e = p.div_euclid(3) * 3;
+			let m = positional(&s, p - e, None);
+			if e == 0 { m } else { format!("{m}e{e}") }
+		},
+		Notation::Auto => {
+			if (p.unsigned_abs() as usize) < MAX_LEN {
+				positional(&s, p, None)
+			} else { scientific(&s, p) }
+		}
+	};
+
+	return format!("{neg}{r}");
+}
+
+// Write the digits `s` as a plain decimal, with the first digit at 10^p.
+// If `decimals` is None, trailing zeros are removed.
+fn positional(s: &str, p: i64, decimals: Option<usize>) -> String {
+	let (int, frac) = if p >= 0 {
+		let q = p as usize + 1;
+		if s.len() > q {
+			(s[0..q].to_string(), s[q..].to_string())
 		} else {
-			let q = p.abs() as usize;
-			let t = format!("0.{}{s}", "0".repeat(q-1));
-			return format!("{neg}{}", t.trim_end_matches('0'));
+			(format!("{s}{}", "0".repeat(q - s.len())), String::new())
 		}
+	} else {
+		(String::from("0"), format!("{}{s}", "0".repeat(p.unsigned_abs() as usize - 1)))
+	};
+
+	let frac = match decimals {
+		Some(n) => format!("{frac:0<n$}")[0..n].to_string(),
+		None => frac.trim_end_matches('0').to_string()
+	};
 
+	let int = group_digits(&int);
+	if frac.is_empty() {
+		return int;
 	} else {
-		// Print full scientific notation
+		return format!("{int}.{frac}");
+	}
+}
+
+// Write the digits `s` as d.ddde{p}
+fn scientific(s: &str, p: i64) -> String {
+	let first = &s[0..1];
+	let rest = s[1..].trim_end_matches('0');
+	if rest == "" {
+		return format!("{first}e{p}");
+	} else {
+		return format!("{first}.{rest}e{p}");
+	}
+}
 
-		let first = &s[0..1];
-		let mut rest = &s[1..];
-		rest = rest.trim_end_matches('0');
-		if rest == "" {
-			return format!("{neg}{first}e{p}");
-		} else {
-			return format!("{neg}{first}.{rest}e{p}");
-		}
+// Separate groups of three digits, like 1,000,000
+fn group_digits(s: &str) -> String {
+	let Some(sep) = DIGIT_SEPARATOR.with(|x| x.get()) else { return s.to_string() };
+
+	let mut r = String::new();
+	for (i, c) in s.chars().enumerate() {
+		if i != 0 && (s.len() - i) % 3 == 0 { r.push(sep); }
+		r.push(c);
 	}
-}
\ No newline at end of file
+	return r;
+}
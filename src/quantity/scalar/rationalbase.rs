@@ -2,6 +2,10 @@ use num::rational::BigRational;
 use num::BigInt;
 use num::Num;
 use num::Signed;
+use num::Zero;
+use num::One;
+use num::Integer;
+use num::ToPrimitive;
 
 use std::ops::{
 	Add, Sub, Mul, Div,
@@ -14,6 +18,10 @@ use std::ops::{
 use std::cmp::Ordering;
 use super::ScalarBase;
 
+// Integer powers of rationals with more bits than this
+// are computed with floats instead.
+const MAX_POW_BITS: u64 = 4096;
+
 
 macro_rules! cant_do {
 	( $x:ident ) => {
@@ -38,10 +46,121 @@ impl RationalBase {
 		let v = BigRational::new_raw(BigInt::from(t), BigInt::from(b));
 		return Some(RationalBase{ val: v });
 	}
+
+	// Parse a prefixed binary, octal, or hex literal.
+	// These may have a fractional part, like 0x1.8
+	fn from_radix_string(s: &str) -> Option<RationalBase> {
+		let radix = match &s[0..2].to_ascii_lowercase()[..] {
+			"0b" => 2,
+			"0o" => 8,
+			"0x" => 16,
+			_ => return None
+		};
+
+		let mut dec = s[2..].split(".");
+		let a = dec.next().unwrap();
+		let b = dec.next().unwrap_or("");
+
+		if {
+			dec.next().is_some() || // We should have at most one `.`
+			(a.len() == 0 && b.len() == 0) || // We need some digits
+			!a.chars().chain(b.chars()).all(|c| c.is_digit(radix))
+		} { return None; }
+
+		let n = BigInt::from_str_radix(&format!("{a}{b}"), radix).ok()?;
+		let d = num::pow(BigInt::from(radix), b.len());
+
+		return Some(RationalBase{ val: BigRational::new(n, d) });
+	}
+
+	// Write this number in the given base.
+	// Shows at most `digits` digits after the point.
+	pub fn to_radix_string(&self, radix: u32, digits: usize) -> String {
+		let prefix = match radix {
+			2 => "0b",
+			8 => "0o",
+			16 => "0x",
+			_ => ""
+		};
+
+		let neg = if self.val.is_negative() {"-"} else {""};
+		let v = self.val.abs();
+		let mut s = format!(
+			"{neg}{prefix}{}",
+			v.trunc().to_integer().to_str_radix(radix).to_uppercase()
+		);
+
+		let mut f = v.fract();
+		if f.is_zero() { return s; }
+
+		s.push('.');
+		let r = BigRational::from_integer(BigInt::from(radix));
+		let mut i = 0;
+		while !f.is_zero() && i < digits {
+			f *= r.clone();
+			s.push_str(&f.trunc().to_integer().to_str_radix(radix).to_uppercase());
+			f = f.fract();
+			i += 1;
+		}
+
+		return s;
+	}
+
+	// Write this number as a fraction, like `4/3`.
+	// If `mixed` is true, write a mixed number like `1 1/3` instead.
+	pub fn to_fraction_string(&self, mixed: bool) -> String {
+		if !mixed || self.val.abs() < BigRational::one() {
+			return format!("{}/{}", self.val.numer(), self.val.denom());
+		}
+
+		let neg = if self.val.is_negative() {"-"} else {""};
+		let v = self.val.abs();
+		let f = v.fract();
+		return format!("{neg}{} {}/{}", v.trunc().to_integer(), f.numer(), f.denom());
+	}
+
+	// The closest fraction to this number with a denominator
+	// of at most `maxden`. maxden must be positive.
+	//
+	// Each convergent of the continued fraction is a best approximation,
+	// so we stop at the last one that fits. The answer is either that
+	// convergent or the largest semiconvergent after it.
+	pub fn limit_denominator(&self, maxden: &BigInt) -> RationalBase {
+		if self.val.denom() <= maxden { return self.clone(); }
+
+		let (mut p0, mut q0) = (BigInt::zero(), BigInt::one());
+		let (mut p1, mut q1) = (BigInt::one(), BigInt::zero());
+		let mut n = self.val.numer().clone();
+		let mut d = self.val.denom().clone();
+
+		while !d.is_zero() {
+			let a = n.div_floor(&d);
+			let q2 = &q0 + &a * &q1;
+			if &q2 > maxden { break; }
+
+			(p0, p1) = (p1.clone(), &p0 + &a * &p1);
+			(q0, q1) = (q1, q2);
+			(n, d) = (d.clone(), &n - &a * &d);
+		}
+
+		let k = (maxden - &q0).div_floor(&q1);
+		let semi = BigRational::new(&p0 + &k * &p1, &q0 + &k * &q1);
+		let conv = BigRational::new(p1, q1);
+
+		if (&conv - &self.val).abs() <= (&semi - &self.val).abs() {
+			return RationalBase{ val: conv };
+		} else {
+			return RationalBase{ val: semi };
+		}
+	}
 }
 
 impl ScalarBase for RationalBase {
 	fn from_string(s: &str) -> Option<RationalBase> {
+		if s.len() > 2 && ["0b", "0o", "0x"].contains(&&s[0..2].to_ascii_lowercase()[..]) {
+			return RationalBase::from_radix_string(s);
+		}
+
 		// Scientific notation
 		let mut sci = s.split("e");
 		let num = sci.next().unwrap();
@@ -135,7 +254,18 @@ impl ScalarBase for RationalBase {
 	cant_do!(log2);
 
 	fn log(&self, _base: RationalBase) -> Option<RationalBase> { None }
-	fn pow(&self, _base: RationalBase) -> Option<RationalBase> { None }
+
+	// Integer powers are exact, as long as the result isn't huge.
+	fn pow(&self, exp: RationalBase) -> Option<RationalBase> {
+		if !exp.val.is_integer() { return None; }
+		let e = exp.val.to_integer().to_i32()?;
+
+		let bits = self.val.numer().bits().max(self.val.denom().bits());
+		if bits * u64::from(e.unsigned_abs()) > MAX_POW_BITS { return None; }
+		if self.val.is_zero() && e < 0 { return None; }
+
+		return Some(RationalBase{ val: (&self.val).pow(e) });
+	}
 
 }
 
@@ -213,9 +343,10 @@ impl Rem<RationalBase> for RationalBase {
 			*modulus.val.denom() != BigInt::from(1)
 		} { panic!() }
 
+		// Euclidean modulo, the result is never negative.
 		RationalBase{
 			val : BigRational::new_raw(
-				self.val.numer() % modulus.val.numer(),
+				self.val.numer().mod_floor(&modulus.val.numer().abs()),
 				BigInt::from(1)
 			)
 		}
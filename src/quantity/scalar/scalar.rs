@@ -6,9 +6,21 @@ use std::ops::{
 	MulAssign, DivAssign
 };
 use std::cmp::Ordering;
+use num::BigInt;
+use num::Signed;
+use num::One;
+use num::rational::BigRational;
 
 use super::FloatBase as FloatBase;
 use super::rationalbase::RationalBase;
+use super::complexbase::ComplexBase;
+use super::intervalbase::IntervalBase;
+use super::uncertainbase::UncertainBase;
+use super::symbolicbase::SymbolicBase;
+use super::show_sig;
+use super::dec_to_sci;
+use super::special;
+use super::get_float_precision;
 
 
 pub trait ScalarBase:
@@ -69,7 +81,23 @@ pub trait ScalarBase:
 #[derive(Clone)]
 pub enum Scalar {
 	Rational{ v: RationalBase },
-	Float{ v: FloatBase }
+	Float{ v: FloatBase },
+
+	// An exact multiple of a power of pi and a square root, like 2π√3.
+	// Never a plain rational. Use `from_symbolic` to make sure this is true.
+	Symbolic{ v: SymbolicBase },
+
+	// Complex scalars always have a nonzero imaginary part.
+	// Use `from_complex` to make sure this is true.
+	Complex{ v: ComplexBase },
+
+	// A range of real numbers that contains the exact value.
+	// This is boxed, since its ends are scalars.
+	Interval{ v: Box<IntervalBase> },
+
+	// A real number with a standard uncertainty.
+	// Also boxed, for the same reason.
+	Uncertain{ v: Box<UncertainBase> }
 }
 
 
@@ -81,22 +109,161 @@ macro_rules! wrap_float {
 	( $x:expr) => { Scalar::Float{v: $x} }
 }
 
+macro_rules! wrap_interval {
+	( $x:expr) => { Scalar::Interval{v: Box::new($x)} }
+}
+
+macro_rules! wrap_uncertain {
+	( $x:expr) => { Scalar::Uncertain{v: Box::new($x)} }
+}
+
 
 fn to_float(r: Scalar) -> Scalar {
 	match &r {
 		Scalar::Float {..} => r,
-		Scalar::Rational {v} => wrap_float!(
-			FloatBase::from_string(&v.val.numer().to_string()).unwrap() /
-			FloatBase::from_string(&v.val.denom().to_string()).unwrap()
-		)
+		Scalar::Rational {v} => {
+			// Huge numerators and denominators don't fit in a float,
+			// even if their ratio does. We drop bits we can't use anyway
+			// and put the difference back as a power of two.
+			let keep = get_float_precision().unwrap_or(53) as u64 + 64;
+			let sn = v.val.numer().bits().saturating_sub(keep);
+			let sd = v.val.denom().bits().saturating_sub(keep);
+
+			let mut f = FloatBase::from_string(&(v.val.numer() >> sn).to_string()).unwrap() /
+				FloatBase::from_string(&(v.val.denom() >> sd).to_string()).unwrap();
+
+			if sn != sd {
+				let e = sn as i64 - sd as i64;
+				f *= FloatBase::from_string("2").unwrap()
+					.pow(FloatBase::from_string(&e.to_string()).unwrap()).unwrap();
+			}
+
+			wrap_float!(f)
+		},
+		Scalar::Symbolic {v} => {
+			let Scalar::Float{v: mut f} = to_float(wrap_rational!(RationalBase{ val: v.coef.clone() })) else { unreachable!() };
+			if v.pi != 0 {
+				f *= FloatBase::pi().pow(FloatBase::from_string(&v.pi.to_string()).unwrap()).unwrap();
+			}
+			if !v.root.is_one() {
+				f *= FloatBase::from_string(&v.root.to_string()).unwrap()
+					.pow(FloatBase::from_string("0.5").unwrap()).unwrap();
+			}
+			wrap_float!(f)
+		},
+		Scalar::Complex {..} => unreachable!("Complex scalars cannot be made real"),
+
+		// There's no single float that stands for an interval
+		// or an uncertain value
+		Scalar::Interval {..} | Scalar::Uncertain {..} => nan()
+	}
+}
+
+fn to_complex(r: Scalar) -> Scalar {
+	match &r {
+		Scalar::Complex {..} => r,
+		_ => {
+			let Scalar::Float{v} = to_float(r) else { unreachable!() };
+			Scalar::Complex{ v: ComplexBase::from_float(v) }
+		}
+	}
+}
+
+fn to_interval(r: Scalar) -> Scalar {
+	match &r {
+		Scalar::Interval {..} => r,
+		Scalar::Float {..} | Scalar::Rational {..} => wrap_interval!(IntervalBase::from_scalar(r)),
+
+		// Floats are rounded, so we need the floats on either side
+		Scalar::Symbolic {..} => {
+			let Scalar::Float{v} = to_float(r) else { unreachable!() };
+			wrap_interval!(IntervalBase::new(wrap_float!(v.next_down()), wrap_float!(v.next_up())))
+		},
+		Scalar::Complex {..} => unreachable!("Complex scalars cannot be made intervals"),
+		Scalar::Uncertain {..} => unreachable!("Uncertain scalars cannot be made intervals")
+	}
+}
+
+fn to_uncertain(r: Scalar) -> Scalar {
+	match &r {
+		Scalar::Uncertain {..} => r,
+		Scalar::Float {..} | Scalar::Rational {..} | Scalar::Symbolic {..} => wrap_uncertain!(UncertainBase::from_scalar(r)),
+		Scalar::Complex {..} => unreachable!("Complex scalars cannot be made uncertain"),
+		Scalar::Interval {..} => unreachable!("Intervals cannot be made uncertain")
+	}
+}
+
+fn from_uncertain(r: Option<UncertainBase>) -> Scalar {
+	match r {
+		Some(r) => wrap_uncertain!(r),
+		None => nan()
 	}
 }
 
+// Wrap a symbolic value, making it a rational if we can.
+fn from_symbolic(v: SymbolicBase) -> Scalar {
+	if v.is_rational() { return wrap_rational!(RationalBase{ val: v.coef }); }
+	return Scalar::Symbolic{ v };
+}
+
+// Both scalars in symbolic form, if one is symbolic and the other is exact.
+fn as_symbolic(a: &Scalar, b: &Scalar) -> Option<(SymbolicBase, SymbolicBase)> {
+	if !a.is_symbolic() && !b.is_symbolic() { return None; }
+	return Some((SymbolicBase::from_scalar(a)?, SymbolicBase::from_scalar(b)?));
+}
+
+// Wrap a complex number, dropping its imaginary part if it is zero.
+fn from_complex(v: ComplexBase) -> Scalar {
+	if v.is_real() { return wrap_float!(v.re); }
+	return Scalar::Complex{ v };
+}
+
+// Promote two scalars to the same type.
+// Returns None if these scalars can't be combined.
+fn unify(a: Scalar, b: Scalar) -> Option<(Scalar, Scalar)> {
+	return match (&a, &b) {
+		// We don't have complex intervals or complex uncertain values,
+		// and intervals don't mix with uncertain values.
+		(Scalar::Complex{..}, Scalar::Interval{..})
+		| (Scalar::Interval{..}, Scalar::Complex{..})
+		| (Scalar::Uncertain{..}, Scalar::Complex{..} | Scalar::Interval{..})
+		| (Scalar::Complex{..} | Scalar::Interval{..}, Scalar::Uncertain{..}) => None,
+
+		(Scalar::Complex{..}, _) | (_, Scalar::Complex{..}) => Some((to_complex(a), to_complex(b))),
+		(Scalar::Interval{..}, _) | (_, Scalar::Interval{..}) => Some((to_interval(a), to_interval(b))),
+		(Scalar::Uncertain{..}, _) | (_, Scalar::Uncertain{..}) => Some((to_uncertain(a), to_uncertain(b))),
+		(Scalar::Float{..} | Scalar::Symbolic{..}, _)
+		| (_, Scalar::Float{..} | Scalar::Symbolic{..}) => Some((to_float(a), to_float(b))),
+		_ => Some((a, b))
+	};
+}
+
+fn nan() -> Scalar {
+	wrap_float!(FloatBase::nan())
+}
+
 impl ToString for Scalar {
 	fn to_string(&self) -> String {
 		match self {
+			// Integers are printed exactly, since they may be too big for a float.
+			Scalar::Rational{v} if v.val.is_integer() => {
+				let n = v.val.to_integer();
+				let s = n.magnitude().to_string();
+				let t = s.trim_end_matches('0');
+				if t.is_empty() { return String::from("0"); }
+				dec_to_sci(n.is_negative(), t.to_string(), s.len() as i64 - 1)
+			},
 			Scalar::Rational{..} => to_float(self.clone()).to_string(),
-			Scalar::Float{v} => v.to_string()
+			Scalar::Float{v} => v.to_string(),
+			Scalar::Symbolic{v} => {
+				match v.to_symbol_string() {
+					Some(s) => s,
+					None => to_float(self.clone()).to_string()
+				}
+			},
+			Scalar::Complex{v} => v.to_string(),
+			Scalar::Interval{v} => v.to_string(),
+			Scalar::Uncertain{v} => v.to_string()
 		}
 	}
 }
@@ -127,15 +294,61 @@ impl Scalar {
 		return Some(wrap_rational!(r.unwrap()));
 	}
 
+	pub fn new_rational_from_integer(n: BigInt) -> Self {
+		return wrap_rational!(RationalBase{ val: BigRational::from_integer(n) });
+	}
+
 	pub fn new_float_from_string(s: &str) -> Option<Self> {
 		let v = FloatBase::from_string(s);
 		if v.is_none() { return None; }
 		return Some(wrap_float!(v.unwrap()))
 	}
+
+	// The smallest interval containing both scalars.
+	// Both must be real.
+	pub fn new_interval(a: Scalar, b: Scalar) -> Self {
+		if a.is_complex() || b.is_complex() { return nan(); }
+		let Scalar::Interval{v: a} = to_interval(a) else { unreachable!() };
+		let Scalar::Interval{v: b} = to_interval(b) else { unreachable!() };
+		let lo = if b.lo < a.lo { b.lo.clone() } else { a.lo.clone() };
+		let hi = if b.hi > a.hi { b.hi.clone() } else { a.hi.clone() };
+		return wrap_interval!(IntervalBase::new(lo, hi));
+	}
+
+	// t/b·π^k
+	pub fn new_pi_from_frac(t: i64, b: i64, k: i64) -> Option<Self> {
+		if b == 0 { return None; }
+		let v = SymbolicBase{
+			coef: BigRational::new(BigInt::from(t), BigInt::from(b)),
+			pi: k,
+			root: BigInt::from(1)
+		};
+		return Some(from_symbolic(v));
+	}
+
+	// val ± err. Both must be real.
+	// If val is already uncertain, the two uncertainties are combined.
+	pub fn new_uncertain(val: Scalar, err: Scalar) -> Self {
+		if val.is_complex() || err.is_complex() || val.is_interval() || err.is_interval() { return nan(); }
+		let err = match err { Scalar::Uncertain{v} => v.val, _ => err };
+		let Scalar::Uncertain{v} = to_uncertain(val) else { unreachable!() };
+		return wrap_uncertain!(*v + UncertainBase::new(Scalar::new_rational(0f64).unwrap(), err));
+	}
+
+	// re + im·i. Both parts must be real.
+	pub fn new_complex(re: Scalar, im: Scalar) -> Self {
+		let Scalar::Float{v:a} = to_float(re) else { unreachable!() };
+		let Scalar::Float{v:b} = to_float(im) else { unreachable!() };
+		return from_complex(ComplexBase::new(a, b));
+	}
 }
 
 
 // Forwarded functions
+//
+// The second form takes a predicate. If it is true for a
+// real argument, that argument is outside the function's real
+// domain and we compute a complex result instead.
 macro_rules! scalar_foward {
 	( $x:ident ) => {
 		pub fn $x(&self) -> Scalar {
@@ -148,58 +361,248 @@ macro_rules! scalar_foward {
 					} else {wrap_rational!(r.unwrap())}
 				},
 				Scalar::Float{v} => {wrap_float!(v.$x().unwrap())},
+				Scalar::Symbolic{v} => {
+					match v.$x() {
+						Some(r) => from_symbolic(r),
+						None => to_float(self.clone()).$x()
+					}
+				},
+				Scalar::Complex{v} => {
+					match v.$x() {
+						Some(r) => from_complex(r),
+						None => nan()
+					}
+				},
+				Scalar::Interval{v} => {
+					match v.$x() {
+						Some(r) => wrap_interval!(r),
+						None => nan()
+					}
+				},
+				Scalar::Uncertain{v} => from_uncertain(v.$x())
+			}
+		}
+	};
+
+	( $x:ident, $c:expr ) => {
+		pub fn $x(&self) -> Scalar {
+			if !self.is_complex() && !self.is_interval() && !self.is_uncertain() && $c(self) {
+				return to_complex(self.clone()).$x();
+			}
+
+			match self {
+				Scalar::Rational{v} => {
+					let r = v.$x();
+					if r.is_none() {
+						let v = to_float(self.clone());
+						return v.$x();
+					} else {wrap_rational!(r.unwrap())}
+				},
+				Scalar::Float{v} => {wrap_float!(v.$x().unwrap())},
+				Scalar::Symbolic{v} => {
+					match v.$x() {
+						Some(r) => from_symbolic(r),
+						None => to_float(self.clone()).$x()
+					}
+				},
+				Scalar::Complex{v} => {
+					match v.$x() {
+						Some(r) => from_complex(r),
+						None => nan()
+					}
+				},
+				Scalar::Interval{v} => {
+					match v.$x() {
+						Some(r) => wrap_interval!(r),
+						None => nan()
+					}
+				},
+				Scalar::Uncertain{v} => from_uncertain(v.$x())
 			}
 		}
 	}
 }
 
+// Domain checks for scalar_foward
+fn outside_unit(x: &Scalar) -> bool { x.clone().abs() > Scalar::new_rational(1f64).unwrap() }
+fn below_one(x: &Scalar) -> bool { *x < Scalar::new_rational(1f64).unwrap() }
+
 impl Scalar {
 	pub fn is_zero(&self) -> bool {
 		match self {
 			Scalar::Rational{v} => v.is_zero(),
+			Scalar::Symbolic{..} => false,
 			Scalar::Float{v} => v.is_zero(),
+			Scalar::Complex{v} => v.is_zero(),
+			Scalar::Interval{v} => v.is_zero(),
+			Scalar::Uncertain{v} => v.is_zero(),
+		}
+	}
+
+	// True if this scalar is zero or is an interval that contains zero.
+	pub fn contains_zero(&self) -> bool {
+		match self {
+			Scalar::Interval{v} => v.contains_zero(),
+			_ => self.is_zero()
 		}
 	}
 
+	// True if arithmetic on these two scalars is defined.
+	// There are no complex intervals or complex uncertain values,
+	// and intervals don't mix with uncertain values.
+	pub fn combines_with(&self, other: &Scalar) -> bool {
+		return unify(self.clone(), other.clone()).is_some();
+	}
+
 	pub fn is_one(&self) -> bool {
 		match self {
 			Scalar::Rational{v} => v.is_one(),
+			Scalar::Symbolic{..} => false,
 			Scalar::Float{v} => v.is_one(),
+			Scalar::Complex{v} => v.is_one(),
+			Scalar::Interval{v} => v.is_one(),
+			Scalar::Uncertain{v} => v.is_one(),
 		}
 	}
 
 	pub fn is_negative(&self) -> bool {
 		match self {
 			Scalar::Rational{v} => v.is_negative(),
+			Scalar::Symbolic{v} => v.coef.is_negative(),
 			Scalar::Float{v} => v.is_negative(),
+			Scalar::Complex{v} => v.is_negative(),
+			Scalar::Interval{v} => v.is_negative(),
+			Scalar::Uncertain{v} => v.is_negative(),
 		}
 	}
 
 	pub fn is_positive(&self) -> bool {
 		match self {
 			Scalar::Rational{v} => v.is_positive(),
+			Scalar::Symbolic{v} => v.coef.is_positive(),
 			Scalar::Float{v} => v.is_positive(),
+			Scalar::Complex{v} => v.is_positive(),
+			Scalar::Interval{v} => v.is_positive(),
+			Scalar::Uncertain{v} => v.is_positive(),
 		}
 	}
 
 	pub fn is_nan(&self) -> bool {
 		match self {
-			Scalar::Float{ v } => {v.val.is_nan()},
-			Scalar::Rational {..} => {false}
+			Scalar::Float{ v } => {v.is_nan()},
+			Scalar::Rational {..} | Scalar::Symbolic {..} => {false},
+			Scalar::Complex{ v } => {v.re.is_nan() || v.im.is_nan()},
+			Scalar::Interval{ v } => {v.is_nan()},
+			Scalar::Uncertain{ v } => {v.is_nan()}
 		}
 	}
 
 	pub fn is_rational(&self) -> bool {
 		match self {
 			Scalar::Float { .. } => false,
-			Scalar::Rational {..} => true
+			Scalar::Rational {..} => true,
+			Scalar::Symbolic { .. } => false,
+			Scalar::Complex { .. } => false,
+			Scalar::Interval { .. } => false,
+			Scalar::Uncertain { .. } => false
+		}
+	}
+
+	// Rationals with huge denominators are slow.
+	// This converts rationals with more than `bits` bits in their denominator to floats.
+	// Integers are never converted.
+	pub fn limit_bits(&self, bits: u64) -> Scalar {
+		match self {
+			Scalar::Rational{v} if v.val.denom().bits() > bits => to_float(self.clone()),
+			_ => self.clone()
+		}
+	}
+
+	pub fn is_complex(&self) -> bool {
+		match self {
+			Scalar::Complex { .. } => true,
+			_ => false
+		}
+	}
+
+	pub fn is_interval(&self) -> bool {
+		match self {
+			Scalar::Interval { .. } => true,
+			_ => false
+		}
+	}
+
+	pub fn is_symbolic(&self) -> bool {
+		match self {
+			Scalar::Symbolic { .. } => true,
+			_ => false
 		}
 	}
 
+	pub fn is_uncertain(&self) -> bool {
+		match self {
+			Scalar::Uncertain { .. } => true,
+			_ => false
+		}
+	}
+
+	// The value of this scalar, if it is an exact integer.
+	pub fn to_integer(&self) -> Option<BigInt> {
+		match self {
+			Scalar::Rational{v} if v.val.is_integer() => Some(v.val.to_integer()),
+			_ => None
+		}
+	}
+
+	// The exact value of a real scalar, as a rational.
+	// None for complex numbers, NaN, and infinity.
+	pub fn to_rational(&self) -> Option<Scalar> {
+		match self {
+			Scalar::Rational{..} => Some(self.clone()),
+			Scalar::Float{v} => Some(wrap_rational!(RationalBase{ val: v.to_rational()? })),
+			Scalar::Symbolic{..} => to_float(self.clone()).to_rational(),
+			Scalar::Complex{..} | Scalar::Interval{..} | Scalar::Uncertain{..} => None
+		}
+	}
+
+	// This scalar as a plain number, like 3.1416 for π.
+	// Symbolic values are evaluated, everything else is unchanged.
+	pub fn to_numeric(&self) -> Scalar {
+		match self {
+			Scalar::Symbolic{..} => to_float(self.clone()),
+			_ => self.clone()
+		}
+	}
+
+	// The power of ten of this number's first digit, like 2 for 450.
+	// None for zero, and anything to_rational() can't handle.
+	pub fn magnitude(&self) -> Option<i64> {
+		let x = self.to_rational()?.abs();
+		if x.is_zero() { return None; }
+
+		let ten = |p: i64| Scalar::new_rational_from_integer(BigInt::from(10)).pow(
+			Scalar::new_rational_from_integer(BigInt::from(p))
+		);
+
+		let mut p = x.log10().floor().to_rational()
+			.and_then(|p| p.to_integer())
+			.and_then(|p| i64::try_from(p).ok())
+			.unwrap_or(0);
+
+		// log10 is a float, so it may be off by one
+		while ten(p) > x { p -= 1; }
+		while ten(p + 1) <= x { p += 1; }
+		return Some(p);
+	}
+
 	pub fn is_int(&self) -> bool {
 		match self {
 			Scalar::Rational{v} => v.is_int(),
+			Scalar::Symbolic{..} => false,
 			Scalar::Float{v} => v.is_int(),
+			Scalar::Complex{v} => v.is_int(),
+			Scalar::Interval{v} => v.is_int(),
+			Scalar::Uncertain{v} => v.is_int(),
 		}
 	}
 
@@ -214,8 +617,8 @@ impl Scalar {
 	scalar_foward!(csc);
 	scalar_foward!(sec);
 	scalar_foward!(cot);
-	scalar_foward!(asin);
-	scalar_foward!(acos);
+	scalar_foward!(asin, outside_unit);
+	scalar_foward!(acos, outside_unit);
 	scalar_foward!(atan);
 	scalar_foward!(sinh);
 	scalar_foward!(cosh);
@@ -224,49 +627,221 @@ impl Scalar {
 	scalar_foward!(sech);
 	scalar_foward!(coth);
 	scalar_foward!(asinh);
-	scalar_foward!(acosh);
-	scalar_foward!(atanh);
+	scalar_foward!(acosh, below_one);
+	scalar_foward!(atanh, outside_unit);
 	scalar_foward!(exp);
-	scalar_foward!(ln);
-	scalar_foward!(log10);
-	scalar_foward!(log2);
+	scalar_foward!(ln, Scalar::is_negative);
+	scalar_foward!(log10, Scalar::is_negative);
+	scalar_foward!(log2, Scalar::is_negative);
 
 	pub fn log(&self, base: Scalar) -> Scalar {
+		if self.is_interval() || base.is_interval() {
+			return match unify(self.clone(), base) {
+				Some((Scalar::Interval{v:a}, Scalar::Interval{v:b})) => {
+					match a.log(*b) { Some(r) => wrap_interval!(r), None => nan() }
+				},
+				_ => nan()
+			};
+		}
+
+		if self.is_uncertain() || base.is_uncertain() {
+			return match unify(self.clone(), base) {
+				Some((Scalar::Uncertain{v:a}, Scalar::Uncertain{v:b})) => from_uncertain(a.log(*b)),
+				_ => nan()
+			};
+		}
+
+		if self.is_negative() || base.is_negative() {
+			let Some((Scalar::Complex{v:a}, Scalar::Complex{v:b})) = unify(to_complex(self.clone()), base) else { return nan() };
+			return match a.log(b) { Some(r) => from_complex(r), None => nan() };
+		}
+
+		match unify(to_float(self.clone()), base) {
+			Some((Scalar::Float{v:a}, Scalar::Float{v:b})) => { wrap_float!(a.log(b).unwrap()) },
+			Some((Scalar::Complex{v:a}, Scalar::Complex{v:b})) => {
+				match a.log(b) { Some(r) => from_complex(r), None => nan() }
+			},
+			_ => unreachable!()
+		}
+	}
+
+	pub fn pow(&self, exp: Scalar) -> Scalar {
+		if self.is_interval() || exp.is_interval() {
+			return match unify(self.clone(), exp) {
+				Some((Scalar::Interval{v:a}, Scalar::Interval{v:b})) => {
+					match a.pow(*b) { Some(r) => wrap_interval!(r), None => nan() }
+				},
+				_ => nan()
+			};
+		}
+
+		if self.is_uncertain() || exp.is_uncertain() {
+			return match unify(self.clone(), exp) {
+				Some((Scalar::Uncertain{v:a}, Scalar::Uncertain{v:b})) => from_uncertain(a.pow(*b)),
+				_ => nan()
+			};
+		}
+
+		// Complex arguments and fractional powers of
+		// negative numbers give complex results.
+		if self.is_complex() || exp.is_complex() || (self.is_negative() && !exp.is_int()) {
+			let Scalar::Complex{v:a} = to_complex(self.clone()) else { unreachable!() };
+			let Scalar::Complex{v:b} = to_complex(exp) else { unreachable!() };
+			return match a.pow(b) { Some(r) => from_complex(r), None => nan() };
+		}
+
+		if let (Scalar::Rational{v:a}, Scalar::Rational{v:b}) = (self, &exp) {
+			if let Some(r) = a.pow(b.clone()) { return wrap_rational!(r); }
+		}
+
+		// Exact roots, like sqrt(8) = 2√2, and powers of symbolic values
+		if let (Some(a), Scalar::Rational{v:b}) = (SymbolicBase::from_scalar(self), &exp) {
+			if let Some(r) = a.pow(b) { return from_symbolic(r); }
+		}
+
+		let Scalar::Float{v:a} = to_float(self.clone()) else { unreachable!() };
+		let Scalar::Float{v:b} = to_float(exp) else { unreachable!() };
+		wrap_float!(a.pow(b).unwrap())
+	}
+
+	// Real part. Real scalars are returned as-is.
+	pub fn re(&self) -> Scalar {
 		match self {
-			Scalar::Rational{..} => { to_float(self.clone()).log(to_float(base)) },
-			Scalar::Float{..} => { to_float(self.clone()).log(to_float(base)) },
+			Scalar::Complex{v} => wrap_float!(v.re.clone()),
+			_ => self.clone()
 		}
 	}
 
-	pub fn pow(&self, base: Scalar) -> Scalar {
+	// Imaginary part. Always zero for real scalars.
+	pub fn im(&self) -> Scalar {
 		match self {
-			Scalar::Rational{..} => {
-				let a = match to_float(self.clone()) {
-					Scalar::Rational{..} => panic!(),
-					Scalar::Float{v} => v,
-				};
-
-				let b = match to_float(base) {
-					Scalar::Rational{..} => panic!(),
-					Scalar::Float{v} => v,
-				};
-
-				wrap_float!(a.pow(b).unwrap())
-			},
-			Scalar::Float{..} => {
-				let a = match to_float(self.clone()) {
-					Scalar::Rational{..} => panic!(),
-					Scalar::Float{v} => v,
-				};
+			Scalar::Complex{v} => wrap_float!(v.im.clone()),
+			_ => Scalar::new_rational(0f64).unwrap()
+		}
+	}
 
-				let b = match to_float(base) {
-					Scalar::Rational{..} => panic!(),
-					Scalar::Float{v} => v,
-				};
+	// Complex argument, in radians.
+	pub fn arg(&self) -> Scalar {
+		match self {
+			Scalar::Complex{v} => wrap_float!(v.arg()),
+			_ => {
+				let Scalar::Complex{v} = to_complex(self.clone()) else { unreachable!() };
+				if v.is_negative() { wrap_float!(v.arg()) }
+				else { Scalar::new_rational(0f64).unwrap() }
+			}
+		}
+	}
+
+	// The angle of the point (x, self), like atan(self / x).
+	// Both scalars must be real.
+	pub fn atan2(&self, x: Scalar) -> Scalar {
+		if self.is_uncertain() || x.is_uncertain() {
+			return match unify(self.clone(), x) {
+				Some((Scalar::Uncertain{v:a}, Scalar::Uncertain{v:b})) => {
+					from_uncertain(UncertainBase::map2(&a, &b, |y, x| y.atan2(x.clone())))
+				},
+				_ => nan()
+			};
+		}
+
+		let Scalar::Float{v: y} = to_float(self.clone()) else { return nan() };
+		let Scalar::Float{v: x} = to_float(x) else { return nan() };
+		return wrap_float!(ComplexBase::new(x, y).arg());
+	}
+
+	// Special functions.
+	// These are only defined for real scalars.
+	pub fn gamma(&self) -> Scalar {
+		if self.is_complex() { return nan(); }
+		if let Scalar::Uncertain{v} = self { return from_uncertain(v.map(Scalar::gamma)); }
+		let Scalar::Float{v} = to_float(self.clone()) else { unreachable!() };
+		return match special::gamma(&v) { Some(r) => wrap_float!(r), None => nan() };
+	}
+
+	// ln(|gamma(x)|)
+	pub fn lgamma(&self) -> Scalar {
+		if self.is_complex() { return nan(); }
+		if let Scalar::Uncertain{v} = self { return from_uncertain(v.map(Scalar::lgamma)); }
+		let Scalar::Float{v} = to_float(self.clone()) else { unreachable!() };
+		return match special::lgamma(&v) { Some((r, _)) => wrap_float!(r), None => nan() };
+	}
+
+	pub fn beta(&self, b: Scalar) -> Scalar {
+		if self.is_complex() || b.is_complex() { return nan(); }
+		if self.is_uncertain() || b.is_uncertain() {
+			return match unify(self.clone(), b) {
+				Some((Scalar::Uncertain{v:a}, Scalar::Uncertain{v:b})) => {
+					from_uncertain(UncertainBase::map2(&a, &b, |a, b| a.beta(b.clone())))
+				},
+				_ => nan()
+			};
+		}
+		let Scalar::Float{v: a} = to_float(self.clone()) else { unreachable!() };
+		let Scalar::Float{v: b} = to_float(b) else { unreachable!() };
+		return match special::beta(&a, &b) { Some(r) => wrap_float!(r), None => nan() };
+	}
+
+	pub fn erf(&self) -> Scalar {
+		if self.is_complex() { return nan(); }
+		if let Scalar::Uncertain{v} = self { return from_uncertain(v.map(Scalar::erf)); }
+		let Scalar::Float{v} = to_float(self.clone()) else { unreachable!() };
+		return match special::erf(&v) { Some(r) => wrap_float!(r), None => nan() };
+	}
+
+	pub fn erfc(&self) -> Scalar {
+		if self.is_complex() { return nan(); }
+		if let Scalar::Uncertain{v} = self { return from_uncertain(v.map(Scalar::erfc)); }
+		let Scalar::Float{v} = to_float(self.clone()) else { unreachable!() };
+		return match special::erfc(&v) { Some(r) => wrap_float!(r), None => nan() };
+	}
 
-				wrap_float!(a.pow(b).unwrap())
+	pub fn conj(&self) -> Scalar {
+		match self {
+			Scalar::Complex{v} => from_complex(v.conj()),
+			_ => self.clone()
+		}
+	}
 
+	// Write this scalar in another base.
+	// Complex numbers are always written in decimal.
+	pub fn to_radix_string(&self, radix: u32) -> String {
+		// Show about as much precision as we would in decimal
+		let digits = (show_sig() as f64 * std::f64::consts::LN_10 / (radix as f64).ln()).ceil() as usize;
+
+		match self {
+			Scalar::Rational{v} => v.to_radix_string(radix, digits),
+			Scalar::Float{v} => {
+				match v.to_rational() {
+					Some(r) => RationalBase{ val: r }.to_radix_string(radix, digits),
+					None => v.to_string()
+				}
 			},
+			Scalar::Symbolic{..} => to_float(self.clone()).to_radix_string(radix),
+			Scalar::Complex{..} | Scalar::Interval{..} | Scalar::Uncertain{..} => self.to_string()
+		}
+	}
+
+	// Write this scalar as a fraction or mixed number.
+	// None if this isn't a rational with a denominator.
+	pub fn to_fraction_string(&self, mixed: bool) -> Option<String> {
+		match self {
+			Scalar::Rational{v} if !v.val.is_integer() => Some(v.to_fraction_string(mixed)),
+			_ => None
+		}
+	}
+
+	// The closest fraction to this scalar with a denominator of at most `maxden`.
+	// None if this isn't real.
+	pub fn rationalize(&self, maxden: &BigInt) -> Option<Scalar> {
+		let Scalar::Rational{v} = self.to_rational()? else { unreachable!() };
+		return Some(wrap_rational!(v.limit_denominator(maxden)));
+	}
+
+	// Same as to_string, but writes complex numbers in polar form.
+	pub fn to_polar_string(&self) -> String {
+		match self {
+			Scalar::Complex{v} => v.to_polar_string(),
+			_ => self.to_string()
 		}
 	}
 }
@@ -278,6 +853,10 @@ impl Neg for Scalar where {
 		match self {
 			Scalar::Float { v } => {wrap_float!(-v)},
 			Scalar::Rational { v } => {wrap_rational!(-v)},
+			Scalar::Symbolic { v } => {from_symbolic(-v)},
+			Scalar::Complex { v } => {from_complex(-v)},
+			Scalar::Interval { v } => {wrap_interval!(-*v)},
+			Scalar::Uncertain { v } => {wrap_uncertain!(-*v)},
 		}
 	}
 }
@@ -286,23 +865,25 @@ impl Add for Scalar {
 	type Output = Self;
 
 	fn add(self, other: Self) -> Self::Output {
-		match (&self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va.clone()+vb.clone())},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {self + to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {to_float(self) + other},
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va.clone()+vb.clone())},
+		if let Some((a, b)) = as_symbolic(&self, &other) {
+			if let Some(r) = a.add(&b) { return from_symbolic(r); }
+		}
+
+		let Some(u) = unify(self, other) else { return nan() };
+		match u {
+			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va+vb)},
+			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va+vb)},
+			(Scalar::Complex{v:va}, Scalar::Complex{v:vb}) => {from_complex(va+vb)},
+			(Scalar::Interval{v:va}, Scalar::Interval{v:vb}) => {wrap_interval!(*va + *vb)},
+			(Scalar::Uncertain{v:va}, Scalar::Uncertain{v:vb}) => {wrap_uncertain!(*va + *vb)},
+			_ => unreachable!()
 		}
 	}
 }
 
 impl AddAssign for Scalar where {
 	fn add_assign(&mut self, other: Self) {
-		match (&mut *self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {*va += vb.clone()},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {*self += to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {*self = to_float(self.clone()) + other },
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {*va += vb.clone()},
-		}
+		*self = self.clone() + other;
 	}
 }
 
@@ -310,23 +891,25 @@ impl Sub for Scalar {
 	type Output = Self;
 
 	fn sub(self, other: Self) -> Self::Output {
-		match (&self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va.clone()-vb.clone())},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {self - to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {to_float(self) - other},
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va.clone()-vb.clone())},
+		if let Some((a, b)) = as_symbolic(&self, &other) {
+			if let Some(r) = a.add(&-b) { return from_symbolic(r); }
+		}
+
+		let Some(u) = unify(self, other) else { return nan() };
+		match u {
+			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va-vb)},
+			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va-vb)},
+			(Scalar::Complex{v:va}, Scalar::Complex{v:vb}) => {from_complex(va-vb)},
+			(Scalar::Interval{v:va}, Scalar::Interval{v:vb}) => {wrap_interval!(*va - *vb)},
+			(Scalar::Uncertain{v:va}, Scalar::Uncertain{v:vb}) => {wrap_uncertain!(*va - *vb)},
+			_ => unreachable!()
 		}
 	}
 }
 
 impl SubAssign for Scalar where {
 	fn sub_assign(&mut self, other: Self) {
-		match (&mut *self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {*va -= vb.clone()},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {*self -= to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {*self = to_float(self.clone()) - other },
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {*va -= vb.clone()},
-		}
+		*self = self.clone() - other;
 	}
 }
 
@@ -334,23 +917,25 @@ impl Mul for Scalar {
 	type Output = Self;
 
 	fn mul(self, other: Self) -> Self::Output {
-		match (&self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va.clone()*vb.clone())},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {self * to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {to_float(self) * other},
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va.clone()*vb.clone())},
+		if let Some((a, b)) = as_symbolic(&self, &other) {
+			if let Some(r) = Some(a.mul(&b)) { return from_symbolic(r); }
+		}
+
+		let Some(u) = unify(self, other) else { return nan() };
+		match u {
+			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va*vb)},
+			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va*vb)},
+			(Scalar::Complex{v:va}, Scalar::Complex{v:vb}) => {from_complex(va*vb)},
+			(Scalar::Interval{v:va}, Scalar::Interval{v:vb}) => {wrap_interval!(*va * *vb)},
+			(Scalar::Uncertain{v:va}, Scalar::Uncertain{v:vb}) => {wrap_uncertain!(*va * *vb)},
+			_ => unreachable!()
 		}
 	}
 }
 
 impl MulAssign for Scalar where {
 	fn mul_assign(&mut self, other: Self) {
-		match (&mut *self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {*va *= vb.clone()},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {*self *= to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {*self = to_float(self.clone()) * other },
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {*va *= vb.clone()},
-		}
+		*self = self.clone() * other;
 	}
 }
 
@@ -358,23 +943,25 @@ impl Div for Scalar {
 	type Output = Self;
 
 	fn div(self, other: Self) -> Self::Output {
-		match (&self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va.clone()/vb.clone())},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {self / to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {to_float(self) / other},
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va.clone()/vb.clone())},
+		if let Some((a, b)) = as_symbolic(&self, &other) {
+			if let Some(r) = a.div(&b) { return from_symbolic(r); }
+		}
+
+		let Some(u) = unify(self, other) else { return nan() };
+		match u {
+			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va/vb)},
+			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va/vb)},
+			(Scalar::Complex{v:va}, Scalar::Complex{v:vb}) => {from_complex(va/vb)},
+			(Scalar::Interval{v:va}, Scalar::Interval{v:vb}) => {wrap_interval!(*va / *vb)},
+			(Scalar::Uncertain{v:va}, Scalar::Uncertain{v:vb}) => {wrap_uncertain!(*va / *vb)},
+			_ => unreachable!()
 		}
 	}
 }
 
 impl DivAssign for Scalar where {
 	fn div_assign(&mut self, other: Self) {
-		match (&mut *self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {*va /= vb.clone()},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {*self /= to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {*self = to_float(self.clone()) / other },
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {*va /= vb.clone()},
-		}
+		*self = self.clone() / other;
 	}
 }
 
@@ -382,33 +969,47 @@ impl Rem<Scalar> for Scalar {
 	type Output = Self;
 
 	fn rem(self, other: Scalar) -> Self::Output {
-		match (&self, &other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va.clone()%vb.clone())},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {self % to_float(other)},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {to_float(self) % other},
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va.clone()%vb.clone())},
+		let Some(u) = unify(self, other) else { return nan() };
+		match u {
+			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {wrap_float!(va%vb)},
+			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {wrap_rational!(va%vb)},
+			(Scalar::Complex{v:va}, Scalar::Complex{v:vb}) => {from_complex(va%vb)},
+			(Scalar::Interval{v:va}, Scalar::Interval{v:vb}) => {wrap_interval!(*va % *vb)},
+			(Scalar::Uncertain{v:va}, Scalar::Uncertain{v:vb}) => {wrap_uncertain!(*va % *vb)},
+			_ => unreachable!()
 		}
 	}
 }
 
 impl PartialEq for Scalar {
 	fn eq(&self, other: &Self) -> bool {
-		match (self, other) {
+		// Symbolic forms are unique
+		if let Some((a, b)) = as_symbolic(self, other) { return a == b; }
+
+		let Some(u) = unify(self.clone(), other.clone()) else { return false };
+		match u {
 			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => { va == vb },
-			(Scalar::Float{..}, Scalar::Rational{..}) => {*self == to_float(other.clone())},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {to_float(self.clone()) == *other},
 			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => { va == vb },
+			(Scalar::Complex{v:va}, Scalar::Complex{v:vb}) => { va == vb },
+			(Scalar::Interval{v:va}, Scalar::Interval{v:vb}) => { va == vb },
+			(Scalar::Uncertain{v:va}, Scalar::Uncertain{v:vb}) => { va == vb },
+			_ => unreachable!()
 		}
 	}
 }
 
 impl PartialOrd for Scalar {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		match (self, other) {
-			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => {va.partial_cmp(vb)},
-			(Scalar::Float{..}, Scalar::Rational{..}) => {(*self).partial_cmp(&to_float(other.clone()))},
-			(Scalar::Rational{..}, Scalar::Float{..}) => {to_float(self.clone()).partial_cmp(other)},
-			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => {va.partial_cmp(vb)},
+		if self.is_symbolic() && self == other { return Some(Ordering::Equal); }
+
+		let Some(u) = unify(self.clone(), other.clone()) else { return None };
+		match u {
+			(Scalar::Float{v:va}, Scalar::Float{v:vb}) => { va.partial_cmp(&vb) },
+			(Scalar::Rational{v:va}, Scalar::Rational{v:vb}) => { va.partial_cmp(&vb) },
+			(Scalar::Complex{v:va}, Scalar::Complex{v:vb}) => { va.partial_cmp(&vb) },
+			(Scalar::Interval{v:va}, Scalar::Interval{v:vb}) => { va.partial_cmp(&vb) },
+			(Scalar::Uncertain{v:va}, Scalar::Uncertain{v:vb}) => { va.partial_cmp(&vb) },
+			_ => unreachable!()
 		}
 	}
-}
\ No newline at end of file
+}
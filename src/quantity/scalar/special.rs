@@ -0,0 +1,234 @@
+use std::cell::RefCell;
+use num::BigInt;
+use num::One;
+use num::Zero;
+use num::rational::BigRational;
+
+use super::ScalarBase;
+use super::FloatBase;
+use super::float_digits;
+use super::get_float_precision;
+
+// Special functions on real floats.
+// These work at any float precision, so we don't use
+// fixed-size approximations like Lanczos.
+
+
+thread_local!(
+	// Bernoulli numbers B_2, B_4, ... we've already computed.
+	// These don't depend on precision, so we only ever compute them once.
+	static BERNOULLI: RefCell<Vec<BigRational>> = const { RefCell::new(Vec::new()) };
+
+	// The same numbers as floats, and the precision they were made at.
+	// Converting them is slow at high precision, so we keep these too.
+	static BERNOULLI_FLOAT: RefCell<(Option<usize>, Vec<FloatBase>)> = const { RefCell::new((None, Vec::new())) }
+);
+
+// The Bernoulli number B_2k, for k >= 1.
+//
+// These come from the tangent numbers T_k, which only need integer math:
+// B_2k = (-1)^(k-1) * 2k * T_k / (4^k * (4^k - 1))
+// See Brent and Harvey, "Fast computation of Bernoulli, Tangent and Secant numbers".
+fn bernoulli(k: usize) -> BigRational {
+	BERNOULLI.with(|b| {
+		let mut b = b.borrow_mut();
+
+		// Tangent numbers can't be extended one at a time,
+		// so we find more than we need.
+		if b.len() < k {
+			let n = (2 * k).max(16);
+
+			let mut t = vec![BigInt::zero(); n + 1];
+			t[1] = BigInt::one();
+			for j in 2..=n { t[j] = &t[j - 1] * BigInt::from(j - 1); }
+			for i in 2..=n {
+				for j in i..=n {
+					t[j] = &t[j - 1] * BigInt::from(j - i) + &t[j] * BigInt::from(j - i + 2);
+				}
+			}
+
+			b.clear();
+			for i in 1..=n {
+				let f = BigInt::one() << (2 * i);
+				let r = BigRational::new(BigInt::from(2 * i) * &t[i], &f * (&f - BigInt::one()));
+				b.push(if i % 2 == 0 { -r } else { r });
+			}
+		}
+
+		return b[k - 1].clone();
+	})
+}
+
+// B_2k as a float at the current precision
+fn bernoulli_float(k: usize) -> FloatBase {
+	let p = get_float_precision();
+	BERNOULLI_FLOAT.with(|b| {
+		let mut b = b.borrow_mut();
+		if b.0 != p { *b = (p, Vec::new()); }
+
+		while b.1.len() < k {
+			let r = from_rational(&bernoulli(b.1.len() + 1));
+			b.1.push(r);
+		}
+
+		return b.1[k - 1].clone();
+	})
+}
+
+fn float(n: usize) -> FloatBase {
+	return FloatBase::from_string(&n.to_string()).unwrap();
+}
+
+fn from_rational(r: &BigRational) -> FloatBase {
+	return FloatBase::from_string(&r.numer().to_string()).unwrap() /
+		FloatBase::from_string(&r.denom().to_string()).unwrap();
+}
+
+// A little less than the smallest difference our floats can see.
+// This is 10^-(digits + 3), found by squaring. Parsing a string
+// like "1e-600" is very slow at high precision.
+fn epsilon() -> FloatBase {
+	let mut n = float_digits() + 3;
+	let mut b = FloatBase::from_string("0.1").unwrap();
+	let mut r = float(1);
+	while n > 0 {
+		if n % 2 == 1 { r *= b.clone(); }
+		b = b.clone() * b;
+		n /= 2;
+	}
+	return r;
+}
+
+
+// ln(gamma(x)) for x >= 0.5, using Stirling's series.
+//
+// We shift x up until the series converges quickly,
+// then shift the result back down.
+fn stirling(x: &FloatBase) -> Option<FloatBase> {
+	let d = float_digits();
+	let n = float(4 * d + 10);
+
+	let mut x = x.clone();
+	let mut shift = float(1);
+	while x < n {
+		shift *= x.clone();
+		x += float(1);
+	}
+
+	let half = FloatBase::from_string("0.5").unwrap();
+	let mut r = (x.clone() - half.clone()) * x.ln()? - x.clone()
+		+ (FloatBase::pi() * float(2)).ln()? * half
+		- shift.ln()?;
+
+	// Since x is large, the terms shrink quickly
+	// and we stop long before this bound.
+	let eps = epsilon();
+	let x2 = x.clone() * x.clone();
+	let mut xp = x;
+	for k in 1..=(d / 4 + 5) {
+		let t = bernoulli_float(k) / (float(2 * k * (2 * k - 1)) * xp.clone());
+		r += t.clone();
+		if t.abs()? < eps { break; }
+		xp *= x2.clone();
+	}
+
+	return Some(r);
+}
+
+// ln(|gamma(x)|), and true if gamma(x) is negative.
+// None at poles (non-positive integers).
+pub fn lgamma(x: &FloatBase) -> Option<(FloatBase, bool)> {
+	if x.is_nan() { return None; }
+	if x.is_int() && (x.is_zero() || x.is_negative()) { return None; }
+
+	if *x >= FloatBase::from_string("0.5").unwrap() {
+		return Some((stirling(x)?, false));
+	}
+
+	// Reflection formula:
+	// gamma(x) * gamma(1 - x) = pi / sin(pi x)
+	let s = (FloatBase::pi() * x.clone()).sin()?;
+	let r = FloatBase::pi().ln()? - s.abs()?.ln()? - stirling(&(float(1) - x.clone()))?;
+	return Some((r, s.is_negative()));
+}
+
+pub fn gamma(x: &FloatBase) -> Option<FloatBase> {
+	let (r, neg) = lgamma(x)?;
+	let r = r.exp()?;
+	return Some(if neg { -r } else { r });
+}
+
+pub fn beta(a: &FloatBase, b: &FloatBase) -> Option<FloatBase> {
+	let (la, na) = lgamma(a)?;
+	let (lb, nb) = lgamma(b)?;
+
+	// gamma(a + b) is infinite, so beta is zero.
+	let Some((lab, nab)) = lgamma(&(a.clone() + b.clone())) else {
+		return Some(float(0));
+	};
+
+	let r = (la + lb - lab).exp()?;
+	return Some(if na ^ nb ^ nab { -r } else { r });
+}
+
+
+// erf(x) for small x, using a series with no cancellation:
+// erf(x) = 2/sqrt(pi) * exp(-x^2) * sum 2^n x^(2n+1) / (1*3*...*(2n+1))
+fn erf_series(x: &FloatBase) -> Option<FloatBase> {
+	let eps = epsilon();
+	let x2 = x.clone() * x.clone();
+
+	let mut t = x.clone();
+	let mut s = x.clone();
+	let mut n = 0;
+	loop {
+		n += 1;
+		t = t * x2.clone() * float(2) / float(2 * n + 1);
+		s += t.clone();
+		if (t.clone() / s.clone()).abs()? < eps { break; }
+	}
+
+	return Some(s * float(2) / FloatBase::pi().pow(FloatBase::from_string("0.5").unwrap())? * (-x2).exp()?);
+}
+
+// erfc(x) for large positive x, using a continued fraction:
+// erfc(x) = exp(-x^2) / sqrt(pi) / (x + (1/2) / (x + 1 / (x + (3/2) / (x + ...))))
+fn erfc_fraction(x: &FloatBase) -> Option<FloatBase> {
+	let eps = epsilon();
+	let half = FloatBase::from_string("0.5").unwrap();
+
+	// Modified Lentz's method
+	let mut f = x.clone();
+	let mut c = x.clone();
+	let mut d = float(0);
+	let mut n = 0;
+	loop {
+		n += 1;
+		let a = float(n) * half.clone();
+		d = float(1) / (x.clone() + a.clone() * d);
+		c = x.clone() + a / c;
+		let delta = c.clone() * d.clone();
+		f *= delta.clone();
+		if (delta - float(1)).abs()? < eps { break; }
+	}
+
+	return Some((-(x.clone() * x.clone())).exp()? / FloatBase::pi().pow(half)? / f);
+}
+
+pub fn erf(x: &FloatBase) -> Option<FloatBase> {
+	if x.is_nan() { return None; }
+	if x.is_zero() { return Some(float(0)); }
+	if x.is_negative() { return Some(-erf(&-x.clone())?); }
+
+	if *x < float(2) { return erf_series(x); }
+	return Some(float(1) - erfc_fraction(x)?);
+}
+
+pub fn erfc(x: &FloatBase) -> Option<FloatBase> {
+	if x.is_nan() { return None; }
+	if x.is_zero() { return Some(float(1)); }
+	if x.is_negative() { return Some(float(2) - erfc(&-x.clone())?); }
+
+	if *x < float(2) { return Some(float(1) - erf_series(x)?); }
+	return erfc_fraction(x);
+}
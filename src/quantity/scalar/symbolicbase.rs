@@ -0,0 +1,260 @@
+use num::rational::BigRational;
+use num::BigInt;
+use num::Signed;
+use num::Zero;
+use num::One;
+use num::Integer;
+use num::ToPrimitive;
+
+use std::ops::Neg;
+
+use super::Scalar;
+use super::rationalbase::RationalBase;
+
+
+// We only look for square factors up to this size.
+// See `squarefree`.
+const MAX_FACTOR: u32 = 1000;
+
+// Integer powers with more bits than this
+// are computed with floats instead.
+const MAX_POW_BITS: u64 = 4096;
+
+// Coefficients with more digits than this
+// are hard to read, so we print a decimal instead.
+const MAX_SHOW_DIGITS: usize = 6;
+
+
+macro_rules! cant_do {
+	( $( $x:ident ),* ) => {
+		$( pub fn $x(&self) -> Option<SymbolicBase> { None } )*
+	}
+}
+
+// Write n as a²·b, with b squarefree.
+// We only trial-divide by small factors, so this returns None
+// if what's left might still have a large square factor.
+fn squarefree(n: &BigInt) -> Option<(BigInt, BigInt)> {
+	let mut a = BigInt::one();
+	let mut b = BigInt::one();
+	let mut m = n.clone();
+
+	let mut p = 2u32;
+	while p <= MAX_FACTOR && BigInt::from(p) * BigInt::from(p) <= m {
+		let mut k = 0;
+		while (&m % p).is_zero() { m /= p; k += 1; }
+		a *= BigInt::from(p).pow(k / 2);
+		if k % 2 == 1 { b *= p; }
+		p += 1;
+	}
+
+	// If we stopped because p² > m,
+	// m has no small factors and must be 1 or prime.
+	if BigInt::from(p) * BigInt::from(p) > m { return Some((a, b * m)); }
+
+	// Otherwise m is only safe if it is a perfect square
+	let s = m.sqrt();
+	if &s * &s == m { return Some((a * s, b)); }
+	return None;
+}
+
+
+// An exact real number of the form c·π^k·√r,
+// where c is rational, k is an integer, and r is a squarefree
+// positive integer. These forms are unique, so two values are
+// equal exactly when their parts are.
+//
+// Rationals are the special case k = 0, r = 1.
+// Scalar keeps those as Scalar::Rational.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct SymbolicBase where {
+	pub coef: BigRational,
+	pub pi: i64,
+	pub root: BigInt
+}
+
+impl SymbolicBase {
+	pub fn from_rational(coef: BigRational) -> SymbolicBase {
+		return SymbolicBase{ coef, pi: 0, root: BigInt::one() };
+	}
+
+	// The symbolic form of an exact scalar
+	pub fn from_scalar(x: &Scalar) -> Option<SymbolicBase> {
+		match x {
+			Scalar::Rational{v} => Some(SymbolicBase::from_rational(v.val.clone())),
+			Scalar::Symbolic{v} => Some(v.clone()),
+			_ => None
+		}
+	}
+
+	// True if this is a plain rational
+	pub fn is_rational(&self) -> bool { self.pi == 0 && self.root.is_one() }
+	pub fn is_zero(&self) -> bool { self.coef.is_zero() }
+
+	fn rational(t: i64, b: i64) -> SymbolicBase {
+		return SymbolicBase::from_rational(BigRational::new(BigInt::from(t), BigInt::from(b)));
+	}
+
+	// Only like terms can be added exactly
+	pub fn add(&self, other: &SymbolicBase) -> Option<SymbolicBase> {
+		if self.is_zero() { return Some(other.clone()); }
+		if other.is_zero() { return Some(self.clone()); }
+		if self.pi != other.pi || self.root != other.root { return None; }
+
+		let coef = &self.coef + &other.coef;
+		if coef.is_zero() { return Some(SymbolicBase::rational(0, 1)); }
+		return Some(SymbolicBase{ coef, pi: self.pi, root: self.root.clone() });
+	}
+
+	// Both roots are squarefree, so √a·√b = g·√((a/g)·(b/g)),
+	// where g = gcd(a, b). (a/g)·(b/g) is also squarefree.
+	pub fn mul(&self, other: &SymbolicBase) -> SymbolicBase {
+		if self.is_zero() || other.is_zero() { return SymbolicBase::rational(0, 1); }
+
+		let g = self.root.gcd(&other.root);
+		return SymbolicBase{
+			coef: &self.coef * &other.coef * BigRational::from_integer(g.clone()),
+			pi: self.pi + other.pi,
+			root: (&self.root / &g) * (&other.root / &g)
+		};
+	}
+
+	// 1/(c·π^k·√r) = (1/(cr))·π^-k·√r
+	pub fn recip(&self) -> Option<SymbolicBase> {
+		if self.is_zero() { return None; }
+		return Some(SymbolicBase{
+			coef: (&self.coef * BigRational::from_integer(self.root.clone())).recip(),
+			pi: -self.pi,
+			root: self.root.clone()
+		});
+	}
+
+	pub fn div(&self, other: &SymbolicBase) -> Option<SymbolicBase> {
+		return Some(self.mul(&other.recip()?));
+	}
+
+	// Square roots of rationals and even powers of pi
+	fn sqrt(&self) -> Option<SymbolicBase> {
+		if self.is_zero() { return Some(self.clone()); }
+		if !self.root.is_one() || self.pi % 2 != 0 || self.coef.is_negative() { return None; }
+
+		// √(p/q) = √(pq)/q
+		let (a, b) = squarefree(&(self.coef.numer() * self.coef.denom()))?;
+		return Some(SymbolicBase{
+			coef: BigRational::new(a, self.coef.denom().clone()),
+			pi: self.pi / 2,
+			root: b
+		});
+	}
+
+	fn powi(&self, n: i64) -> Option<SymbolicBase> {
+		if n < 0 { return self.powi(-n)?.recip(); }
+
+		let bits = self.coef.numer().bits().max(self.coef.denom().bits()) + self.root.bits();
+		if bits * n.unsigned_abs() > MAX_POW_BITS { return None; }
+
+		let e = i32::try_from(n).ok()?;
+		let r = BigRational::from_integer(self.root.clone());
+		return Some(SymbolicBase{
+			coef: self.coef.pow(e) * r.pow(e / 2),
+			pi: self.pi.checked_mul(n)?,
+			root: if n % 2 == 1 { self.root.clone() } else { BigInt::one() }
+		});
+	}
+
+	// Integer and half-integer powers
+	pub fn pow(&self, exp: &RationalBase) -> Option<SymbolicBase> {
+		let n = exp.val.numer().to_i64()?;
+		let b = match exp.val.denom().to_i64()? {
+			1 => self.clone(),
+			2 => self.sqrt()?,
+			_ => return None
+		};
+		return b.powi(n);
+	}
+
+	// If this is a whole number of twelfths of pi, return that number.
+	// 24 twelfths make a full turn, so we reduce mod 24.
+	fn pi_twelfths(&self) -> Option<i64> {
+		if self.pi != 1 || !self.root.is_one() { return None; }
+		let t = &self.coef * BigRational::from_integer(BigInt::from(12));
+		if !t.is_integer() { return None; }
+		return t.to_integer().mod_floor(&BigInt::from(24)).to_i64();
+	}
+
+	// sin(nπ/12), for multiples of π/6 and π/4
+	fn sin_twelfths(n: i64) -> Option<SymbolicBase> {
+		let n = n.rem_euclid(24);
+		let (neg, n) = if n >= 12 { (true, n - 12) } else { (false, n) };
+		let n = if n > 6 { 12 - n } else { n };
+
+		let r = match n {
+			0 => SymbolicBase::rational(0, 1),
+			2 => SymbolicBase::rational(1, 2),
+			3 => SymbolicBase{ coef: BigRational::new(1.into(), 2.into()), pi: 0, root: BigInt::from(2) },
+			4 => SymbolicBase{ coef: BigRational::new(1.into(), 2.into()), pi: 0, root: BigInt::from(3) },
+			6 => SymbolicBase::rational(1, 1),
+			_ => return None
+		};
+		return Some(if neg { -r } else { r });
+	}
+
+	pub fn abs(&self) -> Option<SymbolicBase> {
+		return Some(SymbolicBase{ coef: self.coef.abs(), pi: self.pi, root: self.root.clone() });
+	}
+
+	pub fn sin(&self) -> Option<SymbolicBase> { SymbolicBase::sin_twelfths(self.pi_twelfths()?) }
+	pub fn cos(&self) -> Option<SymbolicBase> { SymbolicBase::sin_twelfths(self.pi_twelfths()? + 6) }
+	pub fn tan(&self) -> Option<SymbolicBase> { self.sin()?.div(&self.cos()?) }
+	pub fn csc(&self) -> Option<SymbolicBase> { self.sin()?.recip() }
+	pub fn sec(&self) -> Option<SymbolicBase> { self.cos()?.recip() }
+	pub fn cot(&self) -> Option<SymbolicBase> { self.cos()?.div(&self.sin()?) }
+
+	// Everything else is computed with floats
+	cant_do!(
+		fract, floor, ceil, round, exp,
+		asin, acos, atan,
+		sinh, cosh, tanh, csch, sech, coth,
+		asinh, acosh, atanh,
+		ln, log10, log2
+	);
+}
+
+impl Neg for SymbolicBase where {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		SymbolicBase{ coef: -self.coef, pi: self.pi, root: self.root }
+	}
+}
+
+// Written so that daisy parses it back to the same value,
+// like `2π√3`, `√2/2`, or `1/(2π)`.
+// Returns None if the coefficient is too long to be readable.
+impl SymbolicBase {
+	pub fn to_symbol_string(&self) -> Option<String> {
+		let p = self.coef.numer();
+		let q = self.coef.denom();
+		if p.magnitude().to_string().len() > MAX_SHOW_DIGITS { return None; }
+		if q.to_string().len() > MAX_SHOW_DIGITS { return None; }
+
+		let power = |k: i64| if k == 1 { String::from("π") } else { format!("π^{k}") };
+
+		let mut top = String::new();
+		if !p.magnitude().is_one() { top.push_str(&p.magnitude().to_string()); }
+		if self.pi > 0 { top.push_str(&power(self.pi)); }
+		if !self.root.is_one() { top.push_str(&format!("√{}", self.root)); }
+		if top.is_empty() { top.push('1'); }
+
+		let mut bottom = String::new();
+		if !q.is_one() { bottom.push_str(&q.to_string()); }
+		if self.pi < 0 { bottom.push_str(&power(-self.pi)); }
+
+		let neg = if p.is_negative() { "-" } else { "" };
+		if bottom.is_empty() { return Some(format!("{neg}{top}")); }
+		if !q.is_one() && self.pi < 0 { return Some(format!("{neg}{top}/({bottom})")); }
+		return Some(format!("{neg}{top}/{bottom}"));
+	}
+}
@@ -0,0 +1,362 @@
+use std::ops::{
+	Add, Sub, Mul, Div,
+	Neg, Rem,
+
+	AddAssign, SubAssign,
+	MulAssign, DivAssign
+};
+
+use std::cmp::Ordering;
+use num::BigInt;
+use num::Signed;
+use super::ScalarBase;
+use super::Scalar;
+use super::MAX_LEN;
+
+
+fn rational(s: &str) -> Scalar { Scalar::new_rational_from_string(s).unwrap() }
+fn float(f: f64) -> Scalar { Scalar::new_float(f).unwrap() }
+
+fn max(a: Scalar, b: Scalar) -> Scalar { if b > a { b } else { a } }
+
+// Rationals can't be divided by zero,
+// and derivatives are often undefined there anyway.
+fn recip(x: Scalar) -> Scalar {
+	if x.is_zero() { return Scalar::new_float_from_string("NaN").unwrap(); }
+	return rational("1") / x;
+}
+
+// Scalar functions give NaN or complex numbers outside their real domain
+fn real(x: Scalar) -> Option<Scalar> {
+	if x.is_nan() || x.is_complex() { return None; }
+	return Some(x);
+}
+
+// Uncorrelated errors add in quadrature
+fn hypot(a: Scalar, b: Scalar) -> Scalar {
+	if a.is_zero() { return b.abs(); }
+	if b.is_zero() { return a.abs(); }
+	return (a.clone() * a + b.clone() * b).pow(rational("0.5"));
+}
+
+fn ten(p: i64) -> Scalar { rational("10").pow(Scalar::new_rational_from_integer(BigInt::from(p))) }
+
+// Write n·10^d as a decimal
+fn place(n: &BigInt, d: i64) -> String {
+	let neg = if n.is_negative() { "-" } else { "" };
+	let s = n.magnitude().to_string();
+	if d >= 0 { return format!("{neg}{s}{}", "0".repeat(d as usize)); }
+
+	let k = d.unsigned_abs() as usize;
+	let s = format!("{}{s}", "0".repeat((k + 1).saturating_sub(s.len())));
+	let (a, b) = s.split_at(s.len() - k);
+	return format!("{neg}{a}.{b}");
+}
+
+// The step we use for numerical derivatives
+fn step(x: &Scalar) -> Scalar { float(1e-6) * max(x.abs(), rational("1")) }
+
+
+// A real value with a standard uncertainty, like 9.81 ± 0.02.
+// Uncertainties are propagated to first order, and every
+// input is assumed to be independent of every other.
+// This means that x - x isn't exactly zero.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct UncertainBase where {
+	pub val: Scalar,
+
+	// Always positive or zero
+	pub err: Scalar
+}
+
+impl UncertainBase {
+	pub fn new(val: Scalar, err: Scalar) -> UncertainBase {
+		return UncertainBase{ val, err: err.abs() };
+	}
+
+	pub fn from_scalar(x: Scalar) -> UncertainBase {
+		return UncertainBase{ val: x, err: rational("0") };
+	}
+
+	pub fn is_nan(&self) -> bool { self.val.is_nan() || self.err.is_nan() }
+
+	// f(x ± e) is f(x) ± |f'(x)|·e
+	fn chain(&self, fx: Scalar, dfx: Scalar) -> Option<UncertainBase> {
+		let val = real(fx)?;
+		if self.err.is_zero() { return Some(UncertainBase::from_scalar(val)); }
+		let d = real(dfx)?;
+		return Some(UncertainBase::new(val, d * self.err.clone()));
+	}
+
+	// Propagate through a function we don't have a derivative for.
+	// We estimate f' with a central difference.
+	pub fn map(&self, f: impl Fn(&Scalar) -> Scalar) -> Option<UncertainBase> {
+		let h = step(&self.val);
+		let d = (
+			f(&(self.val.clone() + h.clone())) -
+			f(&(self.val.clone() - h.clone()))
+		) / (rational("2") * h);
+		return self.chain(f(&self.val), d);
+	}
+
+	// Same as map, for functions of two arguments
+	pub fn map2(a: &UncertainBase, b: &UncertainBase, f: impl Fn(&Scalar, &Scalar) -> Scalar) -> Option<UncertainBase> {
+		let val = real(f(&a.val, &b.val))?;
+
+		let mut ea = rational("0");
+		if !a.err.is_zero() {
+			let h = step(&a.val);
+			let d = (
+				f(&(a.val.clone() + h.clone()), &b.val) -
+				f(&(a.val.clone() - h.clone()), &b.val)
+			) / (rational("2") * h);
+			ea = real(d)? * a.err.clone();
+		}
+
+		let mut eb = rational("0");
+		if !b.err.is_zero() {
+			let h = step(&b.val);
+			let d = (
+				f(&a.val, &(b.val.clone() + h.clone())) -
+				f(&a.val, &(b.val.clone() - h.clone()))
+			) / (rational("2") * h);
+			eb = real(d)? * b.err.clone();
+		}
+
+		return Some(UncertainBase{ val, err: hypot(ea, eb) });
+	}
+}
+
+// The uncertainty is rounded to one significant digit,
+// or two if it starts with a 1. The value is then
+// rounded to the same decimal place.
+impl ToString for UncertainBase {
+	fn to_string(&self) -> String {
+		if self.err.is_zero() { return self.val.to_string(); }
+		if self.is_nan() { return String::from("NaN"); }
+
+		let (Some(v), Some(e)) = (self.val.to_rational(), self.err.to_rational()) else {
+			return format!("{} ± {}", self.val.to_string(), self.err.to_string());
+		};
+
+		let mut d = e.magnitude().unwrap();
+		if (e.clone() / ten(d)).floor().is_one() { d -= 1; }
+
+		let (Some(vn), Some(en)) = (
+			(v.clone() / ten(d)).round().to_integer(),
+			(e.clone() / ten(d)).round().to_integer()
+		) else { unreachable!() };
+
+		let p = max(v.abs(), e).magnitude().unwrap();
+		if (p.unsigned_abs() as usize) < MAX_LEN {
+			return format!("{} ± {}", place(&vn, d), place(&en, d));
+		} else {
+			return format!("({} ± {})e{p}", place(&vn, d - p), place(&en, d - p));
+		}
+	}
+}
+
+impl ScalarBase for UncertainBase {
+	fn from_string(s: &str) -> Option<UncertainBase> {
+		return Some(UncertainBase::from_scalar(Scalar::new_rational_from_string(s)?));
+	}
+
+	fn fract(&self) -> Option<UncertainBase> { Some(UncertainBase::new(self.val.fract(), self.err.clone())) }
+
+	fn is_zero(&self) -> bool { self.val.is_zero() && self.err.is_zero() }
+	fn is_one(&self) -> bool { self.val.is_one() && self.err.is_zero() }
+	fn is_int(&self) -> bool { self.val.is_int() && self.err.is_zero() }
+	fn is_negative(&self) -> bool { self.val.is_negative() }
+	fn is_positive(&self) -> bool { self.val.is_positive() }
+
+	// Rounding gives exact integers
+	fn floor(&self) -> Option<UncertainBase> { Some(UncertainBase::from_scalar(self.val.floor())) }
+	fn ceil(&self) -> Option<UncertainBase> { Some(UncertainBase::from_scalar(self.val.ceil())) }
+	fn round(&self) -> Option<UncertainBase> { Some(UncertainBase::from_scalar(self.val.round())) }
+
+	fn abs(&self) -> Option<UncertainBase> { Some(UncertainBase::new(self.val.abs(), self.err.clone())) }
+
+	fn exp(&self) -> Option<UncertainBase> { self.chain(self.val.exp(), self.val.exp()) }
+
+	fn sin(&self) -> Option<UncertainBase> { self.chain(self.val.sin(), self.val.cos()) }
+	fn cos(&self) -> Option<UncertainBase> { self.chain(self.val.cos(), self.val.sin()) }
+	fn tan(&self) -> Option<UncertainBase> { self.chain(self.val.tan(), self.val.sec().pow(rational("2"))) }
+	fn csc(&self) -> Option<UncertainBase> { self.chain(self.val.csc(), self.val.csc() * self.val.cot()) }
+	fn sec(&self) -> Option<UncertainBase> { self.chain(self.val.sec(), self.val.sec() * self.val.tan()) }
+	fn cot(&self) -> Option<UncertainBase> { self.chain(self.val.cot(), self.val.csc().pow(rational("2"))) }
+
+	fn asin(&self) -> Option<UncertainBase> {
+		let d = (rational("1") - self.val.pow(rational("2"))).pow(rational("-0.5"));
+		self.chain(self.val.asin(), d)
+	}
+
+	fn acos(&self) -> Option<UncertainBase> {
+		let d = (rational("1") - self.val.pow(rational("2"))).pow(rational("-0.5"));
+		self.chain(self.val.acos(), d)
+	}
+
+	fn atan(&self) -> Option<UncertainBase> {
+		let d = rational("1") / (rational("1") + self.val.pow(rational("2")));
+		self.chain(self.val.atan(), d)
+	}
+
+	fn sinh(&self) -> Option<UncertainBase> { self.chain(self.val.sinh(), self.val.cosh()) }
+	fn cosh(&self) -> Option<UncertainBase> { self.chain(self.val.cosh(), self.val.sinh()) }
+	fn tanh(&self) -> Option<UncertainBase> { self.chain(self.val.tanh(), self.val.sech().pow(rational("2"))) }
+	fn csch(&self) -> Option<UncertainBase> { self.chain(self.val.csch(), self.val.csch() * self.val.coth()) }
+	fn sech(&self) -> Option<UncertainBase> { self.chain(self.val.sech(), self.val.sech() * self.val.tanh()) }
+	fn coth(&self) -> Option<UncertainBase> { self.chain(self.val.coth(), self.val.csch().pow(rational("2"))) }
+
+	fn asinh(&self) -> Option<UncertainBase> {
+		let d = (self.val.pow(rational("2")) + rational("1")).pow(rational("-0.5"));
+		self.chain(self.val.asinh(), d)
+	}
+
+	fn acosh(&self) -> Option<UncertainBase> {
+		let d = (self.val.pow(rational("2")) - rational("1")).pow(rational("-0.5"));
+		self.chain(self.val.acosh(), d)
+	}
+
+	fn atanh(&self) -> Option<UncertainBase> {
+		let d = recip(rational("1") - self.val.pow(rational("2")));
+		self.chain(self.val.atanh(), d)
+	}
+
+	fn ln(&self) -> Option<UncertainBase> {
+		self.chain(self.val.ln(), recip(self.val.clone()))
+	}
+
+	fn log10(&self) -> Option<UncertainBase> {
+		self.chain(self.val.log10(), recip(self.val.clone() * rational("10").ln()))
+	}
+
+	fn log2(&self) -> Option<UncertainBase> {
+		self.chain(self.val.log2(), recip(self.val.clone() * rational("2").ln()))
+	}
+
+	fn log(&self, base: UncertainBase) -> Option<UncertainBase> {
+		let b = base.ln()?;
+		if b.val.is_zero() { return None; }
+		Some(self.ln()? / b)
+	}
+
+	// d(x^y) = y·x^(y-1)·dx + x^y·ln(x)·dy
+	fn pow(&self, exp: UncertainBase) -> Option<UncertainBase> {
+		let val = real(self.val.pow(exp.val.clone()))?;
+
+		let mut ex = rational("0");
+		if !self.err.is_zero() {
+			let d = exp.val.clone() * self.val.pow(exp.val.clone() - rational("1"));
+			ex = real(d)? * self.err.clone();
+		}
+
+		let mut ey = rational("0");
+		if !exp.err.is_zero() {
+			let d = val.clone() * self.val.ln();
+			ey = real(d)? * exp.err.clone();
+		}
+
+		return Some(UncertainBase{ val, err: hypot(ex, ey) });
+	}
+}
+
+
+impl Add for UncertainBase where {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self::Output {
+		UncertainBase{
+			val: self.val + other.val,
+			err: hypot(self.err, other.err)
+		}
+	}
+}
+
+impl AddAssign for UncertainBase where {
+	fn add_assign(&mut self, other: Self) {
+		*self = self.clone() + other;
+	}
+}
+
+impl Sub for UncertainBase {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self::Output {
+		self + (-other)
+	}
+}
+
+impl SubAssign for UncertainBase where {
+	fn sub_assign(&mut self, other: Self) {
+		*self = self.clone() - other;
+	}
+}
+
+impl Mul for UncertainBase {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self::Output {
+		UncertainBase{
+			val: self.val.clone() * other.val.clone(),
+			err: hypot(other.val * self.err, self.val * other.err)
+		}
+	}
+}
+
+impl MulAssign for UncertainBase where {
+	fn mul_assign(&mut self, other: Self) {
+		*self = self.clone() * other;
+	}
+}
+
+impl Div for UncertainBase {
+	type Output = Self;
+
+	fn div(self, other: Self) -> Self::Output {
+		let r = recip(other.val);
+		let val = self.val * r.clone();
+		UncertainBase{
+			err: hypot(self.err * r.clone(), val.clone() * other.err * r),
+			val
+		}
+	}
+}
+
+impl DivAssign for UncertainBase where {
+	fn div_assign(&mut self, other: Self) {
+		*self = self.clone() / other;
+	}
+}
+
+impl Neg for UncertainBase where {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		UncertainBase{ val: -self.val, err: self.err }
+	}
+}
+
+impl Rem<UncertainBase> for UncertainBase {
+	type Output = Self;
+
+	// x % m has slope one wherever it is continuous
+	fn rem(self, modulus: UncertainBase) -> Self::Output {
+		if modulus.val.is_zero() { return UncertainBase::from_scalar(recip(modulus.val)); }
+		UncertainBase{ val: self.val % modulus.val, err: self.err }
+	}
+}
+
+impl PartialEq for UncertainBase {
+	fn eq(&self, other: &Self) -> bool {
+		self.val == other.val && self.err == other.err
+	}
+}
+
+// Uncertain values are ordered by their central values
+impl PartialOrd for UncertainBase {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		if self == other { return Some(Ordering::Equal); }
+		return self.val.partial_cmp(&other.val);
+	}
+}
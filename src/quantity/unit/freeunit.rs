@@ -4,6 +4,7 @@ use crate::quantity::Scalar;
 use crate::quantity::Quantity;
 use super::WholeUnit;
 use super::Prefix;
+use super::freeunit_from_string;
 
 
 #[derive(Debug)]
@@ -34,6 +35,27 @@ impl FreeUnit {
 	pub fn from_whole_prefix(whole: WholeUnit, prefix: Prefix) -> FreeUnit { FreeUnit {whole, prefix} }
 	pub fn set_prefix(&mut self, prefix: Prefix) { self.prefix = prefix; }
 	pub fn get_prefix(&self) -> Prefix { self.prefix }
+	pub fn get_whole(&self) -> WholeUnit { self.whole }
+
+	// Pick a prefix so that a value of 10^p in this unit
+	// is between 1 and 1000 in the new unit.
+	// Returns None if that prefix doesn't exist or can't be used with this unit.
+	pub fn engineering_prefix(&self, p: i64) -> Option<FreeUnit> {
+		let e = (p + self.prefix.exponent()?).div_euclid(3) * 3;
+		let prefix = Prefix::from_exponent(e)?;
+		if prefix == self.prefix { return None; }
+
+		// Units only accept some prefixes.
+		// If this one is valid, we can parse it back.
+		let u = FreeUnit::from_whole_prefix(self.whole, prefix);
+		if freeunit_from_string(&u.to_string()) != Some(u) { return None; }
+		return Some(u);
+	}
+
+	// True if this is a base unit without a prefix, like `m`
+	pub fn is_base(&self) -> bool {
+		return self.prefix == Prefix::None && self.whole.base_factor().is_none();
+	}
 
 	/// Returns a quantity q, so that self * q
 	/// gives a quantity in base units.
@@ -0,0 +1,150 @@
+use crate::quantity::Quantity;
+use crate::quantity::Scalar;
+use super::Unit;
+use super::FreeUnit;
+use super::WholeUnit;
+
+
+// Logarithmic units.
+// Ratios, like dB, are dimensionless and convert to each other like normal units.
+// Levels, like dBm, are decibels relative to a reference quantity.
+// These are base units, so they are never converted by a constant factor.
+#[derive(Debug)]
+#[derive(Copy, Clone)]
+#[derive(Eq, PartialEq)]
+pub enum LogScale {
+	Ratio,
+
+	// x dB is 10^(x/10) times the reference, like dBm
+	Power,
+
+	// x dB is 10^(x/20) times the reference, like dBV
+	Field
+}
+
+impl LogScale {
+	// Decibels per factor of ten.
+	fn decibels(&self) -> Scalar {
+		return match self {
+			LogScale::Ratio | LogScale::Power => Scalar::new_rational(10f64).unwrap(),
+			LogScale::Field => Scalar::new_rational(20f64).unwrap()
+		};
+	}
+}
+
+
+impl Unit {
+	// If this unit is exactly one logarithmic unit, like `dBm`, return it.
+	fn as_log(&self) -> Option<(WholeUnit, LogScale)> {
+		if self.get_val().len() != 1 { return None; }
+		let (u, p) = self.get_val().iter().next().unwrap();
+		if *p != Scalar::new_rational(1f64).unwrap() { return None; }
+		return Some((u.whole, u.whole.log_scale()?));
+	}
+
+	// True if this unit contains a logarithmic unit anywhere
+	pub fn has_log(&self) -> bool {
+		return self.get_val().keys().any(|u| u.whole.log_scale().is_some());
+	}
+
+	pub fn is_log_ratio(&self) -> bool {
+		return matches!(self.as_log(), Some((_, LogScale::Ratio)));
+	}
+
+	pub fn is_log_level(&self) -> bool {
+		return matches!(self.as_log(), Some((_, LogScale::Power | LogScale::Field)));
+	}
+}
+
+
+impl Quantity {
+	// Turn a level like `20 dBm` into a linear quantity in base units
+	fn level_to_linear(&self) -> Option<Quantity> {
+		let (w, s) = self.unit.as_log()?;
+		let r = w.log_reference()?;
+
+		let f = Scalar::new_rational(10f64).unwrap().pow(self.scalar.clone() / s.decibels());
+		return Some(r.mul_no_convert(Quantity::from_scalar(f)));
+	}
+
+	// Turn a linear quantity into the level `u`
+	fn linear_to_level(&self, u: &Unit) -> Option<Quantity> {
+		let (w, s) = u.as_log()?;
+		let r = w.log_reference()?;
+		if !self.unit.compatible_with(&r.unit) { return None; }
+
+		// Levels of zero, negative, or complex quantities don't exist
+		let x = self.convert_to(r.clone())?.scalar / r.scalar;
+		if x.is_complex() || !x.is_positive() { return None; }
+
+		let mut q = Quantity::from_scalar(x.log10() * s.decibels());
+		q.set_unit(u.clone());
+		return Some(q);
+	}
+
+	// Called by convert_to() if either unit is a level.
+	// Levels are converted through their linear value.
+	pub(in crate::quantity) fn convert_level(&self, other: &Unit) -> Option<Quantity> {
+		if self.unit == *other { return Some(self.clone()); }
+
+		let a = if self.unit.is_log_level() { self.level_to_linear()? } else { self.clone() };
+		if other.is_log_level() { return a.linear_to_level(other); }
+		return a.convert_to_unit(other);
+	}
+
+	// This ratio in decibels
+	fn to_decibels(&self) -> Scalar {
+		let d = Unit::from_free(FreeUnit::from_whole(WholeUnit::Decibel));
+		return self.convert_to_unit(&d).unwrap().scalar;
+	}
+
+	fn convert_to_unit(&self, u: &Unit) -> Option<Quantity> {
+		let mut b = Quantity::new_rational(1f64).unwrap();
+		b.set_unit(u.clone());
+		return self.convert_to(b);
+	}
+
+	// Add two quantities, at least one of which has a logarithmic unit.
+	// Ratios may be added to each other or to a level (`10 dBm + 3 dB`),
+	// but adding two levels doesn't make sense.
+	// Returns None if these can't be added.
+	pub fn add_log(&self, other: &Quantity) -> Option<Quantity> {
+		if self.unit.is_log_ratio() && other.unit.is_log_ratio() {
+			return Some(self.clone() + other.clone());
+		}
+
+		if self.unit.is_log_level() && other.unit.is_log_ratio() {
+			let mut q = self.clone();
+			q.scalar = q.scalar + other.to_decibels();
+			return Some(q);
+		}
+
+		if self.unit.is_log_ratio() && other.unit.is_log_level() {
+			return other.add_log(self);
+		}
+
+		return None;
+	}
+
+	// Subtract two quantities, at least one of which has a logarithmic unit.
+	// The difference of two levels is a ratio in dB.
+	// Returns None if these can't be subtracted.
+	pub fn sub_log(&self, other: &Quantity) -> Option<Quantity> {
+		if self.unit.is_log_ratio() && other.unit.is_log_ratio() {
+			return Some(self.clone() - other.clone());
+		}
+
+		if self.unit.is_log_level() && other.unit.is_log_ratio() {
+			return self.add_log(&-other.clone());
+		}
+
+		if self.unit.is_log_level() && other.unit.is_log_level() {
+			let o = other.convert_level(&self.unit)?;
+			let mut q = Quantity::from_scalar(self.scalar.clone() - o.scalar);
+			q.insert_unit(FreeUnit::from_whole(WholeUnit::Decibel), Scalar::new_rational(1f64).unwrap());
+			return Some(q);
+		}
+
+		return None;
+	}
+}
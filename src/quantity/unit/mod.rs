@@ -3,13 +3,28 @@ use std::hash::Hash;
 mod freeunit;
 mod prefix;
 mod unit;
+mod userunit;
+mod unitset;
+mod logunit;
 
 pub use prefix::Prefix;
 pub use unit::Unit;
 pub use freeunit::FreeUnit;
+pub use userunit::UserUnit;
+pub use userunit::set_user_units;
+pub use unitset::UnitSet;
+pub use unitset::set_disabled_unit_sets;
+pub use unitset::with_all_unit_sets;
+pub use logunit::LogScale;
 
 use crate::quantity::Quantity;
 use crate::quantity::Scalar;
 
 use prefix::str_to_prefix;
-include!(concat!(env!("OUT_DIR"), "/units.rs"));
\ No newline at end of file
+use userunit::user_unit_name;
+use userunit::user_unit_base_factor;
+use userunit::user_unit_no_space;
+use userunit::user_unit_strings;
+use userunit::user_unit_set;
+use userunit::user_freeunit_from_string;
+include!(concat!(env!("OUT_DIR"), "/units.rs"));
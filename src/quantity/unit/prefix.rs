@@ -44,7 +44,64 @@ pub enum Prefix {
 }
 
 
+// Prefixes that are powers of 1000, from 10^-30 to 10^30.
+// We pick from these when choosing a prefix automatically.
+const ENGINEERING_PREFIXES: [Prefix; 21] = [
+	Prefix::Quecto, Prefix::Ronto, Prefix::Yocto, Prefix::Zepto,
+	Prefix::Atto, Prefix::Femto, Prefix::Pico, Prefix::Nano,
+	Prefix::Micro, Prefix::Milli, Prefix::None, Prefix::Kilo,
+	Prefix::Mega, Prefix::Giga, Prefix::Tera, Prefix::Peta,
+	Prefix::Exa, Prefix::Zetta, Prefix::Yotta, Prefix::Ronna,
+	Prefix::Quetta
+];
+
+const BINARY_PREFIXES: [Prefix; 6] = [
+	Prefix::BinKilo, Prefix::BinMega, Prefix::BinGiga,
+	Prefix::BinTera, Prefix::BinPeta, Prefix::BinExa
+];
+
 impl Prefix {
+	// The power of ten this prefix stands for.
+	// None for binary prefixes.
+	pub fn exponent(&self) -> Option<i64> {
+		Some(match self {
+			Prefix::Hecto => 2,
+			Prefix::Deka => 1,
+			Prefix::Deci => -1,
+			Prefix::Centi => -2,
+			Prefix::BinExa | Prefix::BinPeta | Prefix::BinTera
+			| Prefix::BinGiga | Prefix::BinMega | Prefix::BinKilo => return None,
+			_ => {
+				let i = ENGINEERING_PREFIXES.iter().position(|p| p == self).unwrap();
+				(i as i64 - 10) * 3
+			}
+		})
+	}
+
+	// The prefix for 10^e, if e is a multiple of 3
+	// and we have a prefix that large.
+	pub fn from_exponent(e: i64) -> Option<Prefix> {
+		if e % 3 != 0 { return None; }
+		let i = usize::try_from(e / 3 + 10).ok()?;
+		return ENGINEERING_PREFIXES.get(i).copied();
+	}
+
+	// Decimal SI prefixes, including Prefix::None.
+	// Units defined with `prefixed unit` take these.
+	pub fn si_prefixes() -> Vec<Prefix> {
+		let mut v = vec![Prefix::None, Prefix::Hecto, Prefix::Deka, Prefix::Deci, Prefix::Centi];
+		v.extend(ENGINEERING_PREFIXES.iter().filter(|p| **p != Prefix::None));
+		return v;
+	}
+
+	// Parse a prefix, like `k` or `Ki`.
+	// The empty string is Prefix::None.
+	pub fn from_string(s: &str) -> Option<Prefix> {
+		return Prefix::si_prefixes().into_iter()
+			.chain(BINARY_PREFIXES)
+			.find(|p| p.to_string() == s);
+	}
+
 	pub fn to_ratio(&self) -> Quantity {
 		let q = Quantity::new_rational_from_string(match self {
 			Prefix::Quetta => "1e30",
@@ -23,9 +23,13 @@ impl Unit {
 		if self.unitless() { return String::new(); };
 
 
-		// Sort units by power
+		// Sort units by power, then by name.
+		// HashMap order isn't stable, so we need the second key.
 		let mut v: Vec<(&FreeUnit, &Scalar)> = self.get_val().iter().collect();
-		v.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap());
+		v.sort_by(|a, b| {
+			b.1.partial_cmp(a.1).unwrap()
+				.then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+		});
 
 		let mut i = v.iter();
 		let Some((mut u, mut p)) = i.next() else { panic!() };
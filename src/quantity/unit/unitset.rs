@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+
+use super::WholeUnit;
+
+
+// Groups of units that can be turned off,
+// so that their names can be used for variables.
+// Units that aren't in a set are always on.
+#[derive(Debug)]
+#[derive(Copy, Clone)]
+#[derive(Eq, PartialEq)]
+pub enum UnitSet {
+	Imperial,
+	USCustomary,
+	Nautical,
+	Data,
+	Obscure
+}
+
+impl UnitSet {
+	pub fn all() -> &'static [UnitSet] {
+		return &[
+			UnitSet::Imperial,
+			UnitSet::USCustomary,
+			UnitSet::Nautical,
+			UnitSet::Data,
+			UnitSet::Obscure
+		];
+	}
+
+	pub fn from_string(s: &str) -> Option<UnitSet> {
+		return UnitSet::all().iter().find(|x| x.to_string() == s).copied();
+	}
+}
+
+impl ToString for UnitSet {
+	fn to_string(&self) -> String {
+		String::from(match self {
+			UnitSet::Imperial => "imperial",
+			UnitSet::USCustomary => "us",
+			UnitSet::Nautical => "nautical",
+			UnitSet::Data => "data",
+			UnitSet::Obscure => "obscure"
+		})
+	}
+}
+
+
+thread_local!(
+	// Unit sets we won't parse.
+	// This is kept in sync with Config by Config::check().
+	static DISABLED_SETS: RefCell<Vec<UnitSet>> = const { RefCell::new(Vec::new()) }
+);
+
+pub fn set_disabled_unit_sets(v: &[UnitSet]) { DISABLED_SETS.with(|x| *x.borrow_mut() = v.to_vec()); }
+
+// Run `f` with every unit set turned on.
+// Built-in constants are written in terms of units that may be turned off,
+// but we should always be able to get their values.
+pub fn with_all_unit_sets<T>(f: impl FnOnce() -> T) -> T {
+	let old = DISABLED_SETS.with(|x| x.replace(Vec::new()));
+	let r = f();
+	DISABLED_SETS.with(|x| *x.borrow_mut() = old);
+	return r;
+}
+
+impl WholeUnit {
+	// Can we parse this unit?
+	pub fn is_enabled(&self) -> bool {
+		return match self.unit_set() {
+			None => true,
+			Some(s) => DISABLED_SETS.with(|x| !x.borrow().contains(&s))
+		};
+	}
+}
@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+
+use crate::quantity::Quantity;
+use crate::quantity::Scalar;
+use super::Unit;
+use super::FreeUnit;
+use super::WholeUnit;
+use super::Prefix;
+use super::UnitSet;
+
+
+// A unit defined at runtime, like `unit smoot = 1.7018 m`,
+// or loaded from a definition file.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct UserUnit {
+	// The string we print for this unit
+	pub name: String,
+
+	// The value of one of this unit, as it was defined
+	pub value: Quantity,
+
+	// Strings that are parsed as this unit, and the prefixes each may take.
+	// As in units.toml, a string may only be used without
+	// a prefix if Prefix::None is in its list.
+	pub strings: Vec<(String, Vec<Prefix>)>,
+
+	// If true, don't put a space between this unit and its number
+	pub no_space: bool,
+
+	// The unit set this unit belongs to, if any
+	pub set: Option<UnitSet>,
+
+	// `value` in base units.
+	// We keep this so that redefining a unit
+	// doesn't change units defined in terms of it.
+	base: Quantity
+}
+
+impl UserUnit {
+	pub fn new(name: String, value: Quantity, prefixes: bool) -> UserUnit {
+		let p = if prefixes { Prefix::si_prefixes() } else { vec![Prefix::None] };
+		return UserUnit::from_strings(vec![(name, p)], value, false);
+	}
+
+	// The first string in `strings` is this unit's name.
+	// `strings` must not be empty.
+	pub fn from_strings(strings: Vec<(String, Vec<Prefix>)>, value: Quantity, no_space: bool) -> UserUnit {
+		let name = strings[0].0.clone();
+		let base = value.convert_to_base();
+		return UserUnit { name, value, strings, no_space, set: None, base };
+	}
+
+	pub fn takes_prefixes(&self) -> bool {
+		return self.strings.iter().any(|(_, p)| p.iter().any(|x| *x != Prefix::None));
+	}
+}
+
+
+thread_local!(
+	// Units defined at runtime.
+	// `WholeUnit::User(i)` is the ith unit in this list.
+	//
+	// This is kept in sync with Context by Context::push_unit().
+	static USER_UNITS: RefCell<Vec<UserUnit>> = const { RefCell::new(Vec::new()) }
+);
+
+pub fn set_user_units(v: &[UserUnit]) { USER_UNITS.with(|x| *x.borrow_mut() = v.to_vec()); }
+
+// Get something from the ith user unit.
+// Returns None if there is no such unit, which happens if
+// a quantity was made in a different context.
+fn with_user_unit<T>(i: usize, f: impl FnOnce(&UserUnit) -> T) -> Option<T> {
+	return USER_UNITS.with(|x| x.borrow().get(i).map(f));
+}
+
+pub(super) fn user_unit_name(i: usize) -> String {
+	return with_user_unit(i, |u| u.name.clone()).unwrap_or(String::from("?"));
+}
+
+// See WholeUnit::base_factor().
+// Units we don't know are treated as base units,
+// so they can't be converted to anything else.
+pub(super) fn user_unit_base_factor(i: usize) -> Option<Quantity> {
+	let mut q = with_user_unit(i, |u| u.base.clone())?;
+	q.insert_unit(FreeUnit::from_whole(WholeUnit::User(i)), Scalar::new_rational(-1f64).unwrap());
+	return Some(q);
+}
+
+pub(super) fn user_unit_strings(i: usize) -> Vec<(String, Vec<Prefix>)> {
+	return with_user_unit(i, |u| u.strings.clone()).unwrap_or_default();
+}
+
+pub(super) fn user_unit_set(i: usize) -> Option<UnitSet> {
+	return with_user_unit(i, |u| u.set).flatten();
+}
+
+pub(super) fn user_unit_no_space(i: usize) -> bool {
+	return with_user_unit(i, |u| u.no_space).unwrap_or(false);
+}
+
+impl Unit {
+	// False if this unit contains a user unit that isn't defined
+	pub fn is_defined(&self) -> bool {
+		return self.get_val().keys().all(|u| match u.whole {
+			WholeUnit::User(i) => with_user_unit(i, |_| ()).is_some(),
+			_ => true
+		});
+	}
+}
+
+// Called by freeunit_from_string() if `s` isn't a built-in unit
+pub(super) fn user_freeunit_from_string(s: &str) -> Option<FreeUnit> {
+	return USER_UNITS.with(|x| {
+		for (i, u) in x.borrow().iter().enumerate() {
+			for (n, ps) in &u.strings {
+				let Some(p) = s.strip_suffix(&n[..]) else { continue };
+				let Some(p) = Prefix::from_string(p) else { continue };
+				if ps.contains(&p) {
+					return Some(FreeUnit::from_whole_prefix(WholeUnit::User(i), p));
+				}
+			}
+		}
+
+		return None;
+	});
+}
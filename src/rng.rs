@@ -0,0 +1,59 @@
+// A small, seedable random number generator (SplitMix64).
+// This isn't cryptographically secure, but it's fast,
+// has good statistical properties, and gives the same
+// sequence for the same seed on every platform.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Rng {
+	seed: u64,
+	state: u64
+}
+
+impl Rng {
+	pub fn new(seed: u64) -> Rng {
+		return Rng { seed, state: seed };
+	}
+
+	// Make a new generator with an unpredictable seed
+	pub fn from_time() -> Rng {
+		cfg_if::cfg_if! {
+			if #[cfg(target_arch = "wasm32")] {
+				// There is no system clock in wasm
+				return Rng::new(0);
+			} else {
+				let t = std::time::SystemTime::now()
+					.duration_since(std::time::UNIX_EPOCH)
+					.map(|d| d.as_nanos() as u64)
+					.unwrap_or(0);
+				return Rng::new(t);
+			}
+		}
+	}
+
+	pub fn get_seed(&self) -> u64 { self.seed }
+
+	pub fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+		return z ^ (z >> 31);
+	}
+
+	// A uniform float in [0, 1)
+	pub fn next_f64(&mut self) -> f64 {
+		return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+	}
+
+	// A uniform integer in [0, n).
+	// n must not be zero.
+	pub fn below(&mut self, n: u64) -> u64 {
+		// Reject values in the incomplete last block,
+		// so that every result is equally likely.
+		let zone = u64::MAX - (u64::MAX % n);
+		loop {
+			let x = self.next_u64();
+			if x < zone { return x % n; }
+		}
+	}
+}
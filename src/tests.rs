@@ -23,6 +23,13 @@ fn good_expr(r: &str, s: &str) {
 	assert_eq!(r, out);
 }
 
+// Like good_expr, but evaluated in the given context
+fn good_in(c: &mut Context, r: &str, s: &str) {
+	let g = parser::parse(c, &String::from(s)).unwrap();
+	let out = evaluate(c, &g).unwrap();
+	assert_eq!(r, out.display_outer(c));
+}
+
 fn bad_expr(s: &str) {
 	let out = eval_to_str(s);
 
@@ -137,9 +144,9 @@ fn operators() {
 	good_expr("1.1529e18", "2^3^4^5");
 
 	// Should parse as 1/(2pi)
-	good_expr("0.15915", "1/2pi");
+	good_expr("1/(2π)", "1/2pi");
 	// Should parse as (1/2)*pi
-	good_expr("1.5708", "1/2*pi");
+	good_expr("π/2", "1/2*pi");
 
 
 
@@ -180,12 +187,22 @@ fn operators() {
 	good_expr("18", "3!3");
 	good_expr("9.3326e157", "1e2!");
 
-	bad_expr("3.1!");
+	good_expr("6.8126", "3.1!");
+	good_expr("7.1881", "pi!");
 	bad_expr("1e10!");
 	bad_expr("1e5!");
 	bad_expr("0^(-1)");
-	bad_expr("pi!");
 	bad_expr("2.5 mod 8");
+
+	// Factorials are echoed with the parenthesis they need,
+	// and the echo evaluates to the same value.
+	let c = Context::new();
+	for (e, s) in [("(3!)!", "(3!)!"), ("3!!", "3!!"), ("(3!!)!", "(3!!)!"), ("(2 + 1)!", "(2+1)!")] {
+		let g = parser::parse(&c, &String::from(s)).unwrap();
+		assert_eq!(e, g.display(&c));
+		assert_eq!(eval_to_str(s), eval_to_str(e));
+	}
+	good_expr("720", "(3!)!");
 }
 
 #[test]
@@ -227,16 +244,996 @@ fn complex_units() {
 fn functions() {
 	good_expr("1", "sin(90 deg)");
 	good_expr("-1", "cos(180 deg)");
-	good_expr("0.70711", "sin(pi/4)");
-	good_expr("0.70711", "sin((pi/4) r)");
+	good_expr("√2/2", "sin(pi/4)");
+	good_expr("√2/2", "sin((pi/4) r)");
 
 	good_expr("2", "nounit(2 mm)");
 	good_expr("2", "nounit(2 meter * second)");
 
 	good_expr("37.778", "FtoC(100)");
-	good_expr("73.399", "CtoF(23)");
+	good_expr("73.4", "CtoF(23)");
 	good_expr("-17.778", "FtoC(0)");
-	good_expr("31.999", "CtoF(0)");
+	good_expr("32", "CtoF(0)");
 
 	//good_expr("5000 m²·g/(s²·A²)", "tobase(5H)");
-}
\ No newline at end of file
+}
+
+#[test]
+fn complex() {
+	good_expr("i", "i");
+	good_expr("i", "j");
+	good_expr("i", "sqrt(-1)");
+	good_expr("-1", "i^2");
+	good_expr("2i", "sqrt(-4)");
+	good_expr("-i", "1/i");
+	good_expr("4 + 4i", "3 + 4i + 1");
+	good_expr("11 - 2i", "(3 + 4i) * (1 - 2i)");
+	good_expr("-1 + 2i", "(3 + 4i) / (1 - 2i)");
+	good_expr("-46 + 9i", "(2 + 3i)^3");
+	good_expr("-1", "e^(i*pi)");
+	good_expr("0.20788", "i^i");
+	good_expr("1 + 1.7321i", "(-8)^(1/3)");
+
+	good_expr("5", "abs(3 + 4i)");
+	good_expr("3", "re(3 + 4i)");
+	good_expr("4", "im(3 + 4i)");
+	good_expr("3 - 4i", "conj(3 + 4i)");
+	good_expr("1.5708", "arg(i)");
+
+	good_expr("0.69315 + 3.1416i", "ln(-2)");
+	good_expr("1.1752i", "sin(i)");
+	good_expr("1.0472i", "acosh(0.5)");
+
+	good_expr("(3 + 4i) m", "(3 + 4i) m");
+	good_expr("(2i) m", "sqrt(-4 m^2)");
+
+	bad_expr("m^i");
+	bad_expr("i!");
+	bad_expr("0^i");
+	bad_expr("0^(-1+i)");
+	good_expr("0", "0^(1+i)");
+	bad_expr("2i mod 3");
+
+	// `j` may be redefined, `i` may not
+	let mut c = Context::new();
+	assert!(daisycalc::do_string(&mut c, &String::from("i = 1")).is_err());
+	daisycalc::do_string(&mut c, &String::from("j = 2")).unwrap();
+	let g = parser::parse(&c, &String::from("3j + i")).unwrap();
+	assert_eq!("6 + i", evaluate(&mut c, &g).unwrap().display_outer(&c));
+}
+
+#[test]
+fn precision() {
+	let mut c = Context::new();
+	c.config.float_precision = Some(128);
+	c.config.check();
+
+	good_in(&mut c, "π", "pi * 1.0");
+	good_in(&mut c, "√2", "sqrt(2)");
+	good_in(&mut c, "1.41421356237309504880168872420969808", "(sqrt(2) + 1) - 1");
+	good_in(&mut c, "2.71828182845904523536028747135266249", "e^1");
+	good_in(&mut c, "0.693147180559945309417232121458176568", "ln(2)");
+	good_in(&mut c, "0.841470984807896506652502321630298999", "sin(1)");
+	good_in(&mut c, "3.14159265358979323846264338327950288", "atan(1) * 4");
+	good_in(&mut c, "0.549306144334054845697622618461262852", "atanh(0.5)");
+	good_in(&mut c, "0.333333333333333333333333333333333333", "1/3");
+	good_in(&mut c, "3.14159265358979323846264338327950288i", "ln(-1)");
+	good_in(&mut c, "3π m", "3 m * pi");
+	good_in(&mut c, "0.842700792949714869341220635082609259", "erf(1)");
+	good_in(&mut c, "0.572364942924700087071713675676529358", "lgamma(0.5)");
+}
+
+#[test]
+fn radix() {
+	good_expr("255", "0xFF");
+	good_expr("255", "0xff");
+	good_expr("14", "0xe");
+	good_expr("485", "0x1e5");
+	good_expr("10", "0b1010");
+	good_expr("15", "0o17");
+	good_expr("1.5", "0x1.8");
+	good_expr("256", "0xFF + 1");
+	good_expr("0.5", "0b1 / 0b10");
+	good_expr("255", "0XFF");
+	good_expr("10", "0B1010");
+	good_expr("15", "0O17");
+
+	good_expr("0xFF", "255 to hex");
+	good_expr("-0xFF", "-255 to hex");
+	good_expr("0b1010", "10 to bin");
+	good_expr("0o17", "15 to oct");
+	good_expr("255", "0xFF to dec");
+	good_expr("0x1.8", "1.5 to hex");
+	good_expr("0b0.01010101010101010", "1/3 to bin");
+	good_expr("0x5 m", "5 m to hex");
+	good_expr("16", "2^(4 to hex)");
+
+	bad_expr("0b102");
+	bad_expr("0o8");
+	bad_expr("0x");
+	bad_expr("1 + 0X");
+	bad_expr("hex");
+	bad_expr("1 + hex");
+	bad_expr("sin(hex)");
+}
+
+#[test]
+fn bitwise() {
+	good_expr("8", "12 and 10");
+	good_expr("14", "12 or 10");
+	good_expr("6", "12 xor 10");
+	good_expr("-6", "not 5");
+	good_expr("4", "not -5");
+	good_expr("16", "1 << 4");
+	good_expr("16", "256 >> 4");
+	good_expr("-4", "-7 >> 1");
+	good_expr("0x30", "0xF0 and 0x3C to hex");
+
+	// Precedence
+	good_expr("24", "1 + 2 << 3");
+	good_expr("6", "2 * 3 and 7");
+	good_expr("10", "6 and 3 or 8");
+	good_expr("6", "6 or 3 and 8");
+	good_expr("-12", "not 5 * 2");
+
+	bad_expr("1.5 and 2");
+	bad_expr("3 m and 2");
+	bad_expr("not 0.5");
+	bad_expr("1 << -1");
+	bad_expr("1 << 100000");
+}
+
+#[test]
+fn multi_arg_functions() {
+	good_expr("3", "log(8, 2)");
+	good_expr("0.13926 - 0.63118i", "log(8, -2)");
+	good_expr("2", "log(100)");
+	good_expr("2", "root(8, 3)");
+	good_expr("-2", "root(-8, 3)");
+	good_expr("3 m", "root(9 m^2, 2)");
+	good_expr("0.78539", "atan2(1, 1)");
+	good_expr("2.3562", "atan2(1, -1)");
+	good_expr("5", "hypot(3, 4)");
+	good_expr("5 m", "hypot(3 m, 400 cm)");
+
+	good_expr("1", "min(3, 1, 2)");
+	good_expr("3", "max(3, 1, 2)");
+	good_expr("5", "min(5)");
+	good_expr("0.5 m", "min(1 m, 50 cm)");
+	good_expr("5 min", "5 min");
+	good_expr("3", "clamp(5, 1, 3)");
+	good_expr("1", "clamp(0, 1, 3)");
+	good_expr("0.3 m", "clamp(2 m, 1 cm, 30 cm)");
+
+	bad_expr("log(8, 1)");
+	bad_expr("log(8, 0)");
+	bad_expr("log(8, 2 m)");
+	bad_expr("root(2, 0)");
+	bad_expr("atan2(1 m, 1 s)");
+	bad_expr("min(1 m, 2 s)");
+	bad_expr("clamp(2, 3, 1)");
+
+	// Wrong number of arguments
+	bad_expr("sin(1, 2)");
+	bad_expr("atan2(1)");
+	bad_expr("atan2(1, 2, 3)");
+	bad_expr("clamp(1, 2)");
+}
+
+#[test]
+fn statistics() {
+	good_expr("2.3333 m", "mean(1 m, 2 m, 4 m)");
+	good_expr("1.5 m", "mean(1 m, 200 cm)");
+	good_expr("1", "mean(1/3, 1/3, 1/3) * 3");
+	good_expr("2", "mean((1, 2), 3)");
+	good_expr("3", "median(3, 1, 4, 1, 5)");
+	good_expr("2.5", "median(1, 2, 3, 4)");
+	good_expr("1", "mode(3, 1, 4, 1, 5)");
+	good_expr("14", "sum(3, 1, 4, 1, 5)");
+	good_expr("1.5 m", "sum(1 m, 50 cm)");
+	good_expr("60", "product(3, 1, 4, 1, 5)");
+	good_expr("6 m·s", "product(2 m, 3 s)");
+	good_expr("3 m²", "product(1 m, 300 cm)");
+	good_expr("4", "range(3, 1, 4, 1, 5)");
+	good_expr("3.2", "variance(3, 1, 4, 1, 5)");
+	good_expr("2 m²", "variance(1 m, 3 m)");
+	good_expr("√2 m", "stdev(1 m, 300 cm)");
+	good_expr("3", "percentile(50, 3, 1, 4, 1, 5)");
+	good_expr("1.75", "percentile(25, 1, 2, 3, 4)");
+	good_expr("5", "percentile(100, 3, 1, 4, 1, 5)");
+
+	bad_expr("sum(1 m, 1 s)");
+	bad_expr("median(1, 2i)");
+	bad_expr("variance(1)");
+	bad_expr("percentile(101, 1, 2)");
+	bad_expr("percentile(50 m, 1, 2)");
+}
+
+#[test]
+fn number_theory() {
+	good_expr("1", "-5 % 3");
+	good_expr("2", "5 % -3");
+	good_expr("1", "-7 mod 4");
+
+	good_expr("6", "gcd(12, 18)");
+	good_expr("2", "gcd(12, 18, 8)");
+	good_expr("12", "lcm(-4, 6)");
+	good_expr("5", "modinv(3, 7)");
+	good_expr("24", "modpow(2, 10, 1000)");
+	good_expr("5", "modpow(3, -1, 7)");
+	good_expr("1", "isprime(97)");
+	good_expr("0", "isprime(561)");
+	good_expr("1", "isprime(2^61 - 1)");
+	good_expr("(2, 2, 2, 3, 3, 5)", "factor(360)");
+	good_expr("(-1, 2, 2, 3)", "factor(-12)");
+	good_expr("97", "factor(97)");
+	good_expr("(71, 839, 1471, 6857)", "factor(600851475143)");
+	good_expr("12", "totient(36)");
+	good_expr("101", "nextprime(100)");
+
+	bad_expr("gcd(1.5, 3)");
+	bad_expr("gcd(3 m, 6)");
+	bad_expr("modinv(2, 4)");
+	bad_expr("modpow(2, 3, 0)");
+	bad_expr("factor(0)");
+	bad_expr("isprime(2.5)");
+}
+
+#[test]
+fn special_functions() {
+	good_expr("120", "5!");
+	good_expr("1", "0!");
+	good_expr("0.88623", "0.5!");
+	good_expr("1.8192e77337", "20000!");
+	good_expr("3840", "10!!");
+	good_expr("945", "9!!");
+	good_expr("1", "(-1)!!");
+
+	good_expr("24", "gamma(5)");
+	good_expr("3.1416", "gamma(0.5)^2");
+	good_expr("-3.5449", "gamma(-0.5)");
+	good_expr("359.13", "lgamma(100)");
+	good_expr("0.083333", "beta(2, 3)");
+	good_expr("0.8427", "erf(1)");
+	good_expr("-0.8427", "erf(-1)");
+	good_expr("0.15729", "erfc(1)");
+	good_expr("2.0885e-45", "erfc(10)");
+
+	bad_expr("(-1)!");
+	bad_expr("1.5!!");
+	bad_expr("gamma(0)");
+	bad_expr("gamma(-2)");
+	bad_expr("gamma(2 m)");
+	bad_expr("erf(i)");
+
+	// These should stay fast and accurate at high precision
+	use daisycalc::quantity::Quantity;
+	let mut c = Context::new();
+	c.config.float_precision = Some(2048);
+	c.config.check();
+
+	let t = std::time::Instant::now();
+	let g = parser::parse(&c, &String::from("gamma(0.5)^2 / pi - 1")).unwrap();
+	let r = evaluate(&mut c, &g).unwrap();
+	assert!(t.elapsed().as_secs() < 10);
+
+	let parser::Expression::Quantity(_, r) = r else { panic!() };
+	assert!(r.abs() < Quantity::new_rational_from_string("1e-600").unwrap());
+}
+
+#[test]
+fn combinatorics() {
+	good_expr("10", "nCr(5, 2)");
+	good_expr("0", "nCr(5, 7)");
+	good_expr("15", "nCr(-5, 2)");
+	good_expr("20", "nPr(5, 2)");
+	good_expr("1260", "multinomial(2, 3, 4)");
+	good_expr("0.3125", "binompmf(2, 5, 0.5)");
+	good_expr("0.5", "binomcdf(2, 5, 0.5)");
+	good_expr("1", "binomcdf(10, 5, 0.5)");
+	good_expr("0.50399", "binomcdf(5000, 10000, 0.5)");
+	good_expr("0.0079786", "binompmf(5000, 10000, 0.5)");
+	good_expr("1", "binomcdf(9000, 10000, 0.5)");
+	good_expr("0", "binomcdf(1000, 10000, 0.5)");
+	good_expr("0.26475", "binompmf(3, 10, 1/pi)");
+	good_expr("3", "dice(3, 1)");
+	good_expr("0", "0d6");
+	good_expr("18 d", "3d 6");
+
+	bad_expr("nCr(5, -1)");
+	bad_expr("nCr(5.5, 2)");
+	bad_expr("binompmf(2, 5, 1.5)");
+	bad_expr("3d0");
+	bad_expr("1000000d6");
+	bad_expr("rand(1)");
+	bad_expr("sin()");
+}
+
+#[test]
+fn random() {
+	let rolls = |c: &mut Context| -> Vec<String> {
+		return ["3d6", "dice(3, 6)", "rand()"].iter().map(|s| {
+			let g = parser::parse_no_context(&String::from(*s)).unwrap();
+			evaluate(c, &g).unwrap().display_outer(c)
+		}).collect();
+	};
+
+	let mut c = Context::new();
+	for r in &rolls(&mut c)[0..2] {
+		let r = r.parse::<u64>().unwrap();
+		assert!((3..=18).contains(&r));
+	}
+
+	// The same seed gives the same results
+	c.seed(42);
+	let a = rolls(&mut c);
+	c.seed(42);
+	let b = rolls(&mut c);
+	assert_eq!(a, b);
+}
+
+#[test]
+fn logic() {
+	good_expr("true", "1 < 2");
+	good_expr("false", "2 <= 1");
+	good_expr("true", "3 >= 3");
+	good_expr("true", "1 m == 100 cm");
+	good_expr("true", "1 km > 999 m");
+	good_expr("false", "1 m != 100 cm");
+	good_expr("true", "i == i");
+	good_expr("true", "true && !false");
+	good_expr("false", "!(1 < 2) || 5 == 6");
+	good_expr("true", "1 < 2 && 2 < 3");
+	good_expr("2", "if(1 > 2, 1, 2)");
+	good_expr("1", "if(true, 1, 1/0)");
+	good_expr("false", "false && 1/0 > 1");
+	good_expr("true", "true || 1/0 > 1");
+
+	bad_expr("1 m < 1 s");
+	bad_expr("i < 1");
+	bad_expr("true + 1");
+	bad_expr("!1");
+	bad_expr("1 && true");
+	bad_expr("true == 1");
+	bad_expr("if(1, 2, 3)");
+	bad_expr("if(true, 2)");
+	bad_expr("sin(true)");
+}
+
+#[test]
+fn recursion() {
+	use daisycalc::do_string;
+
+	let mut c = Context::new();
+	let mut run = |s: &str| do_string(&mut c, &String::from(s)).map(|x| x.to_string());
+
+	run("fact(n) = if(n <= 1, 1, n * fact(n - 1))").unwrap();
+	assert!(run("fact(5)").unwrap().ends_with("[r]=[n] 120\n\n"));
+
+	run("fib(n) = if(n <= 1, n, fib(n - 1) + fib(n - 2))").unwrap();
+	assert!(run("fib(10)").unwrap().ends_with("[r]=[n] 55\n\n"));
+
+	// Infinite recursion is an error, not a stack overflow
+	run("forever(n) = forever(n + 1)").unwrap();
+	assert!(run("forever(1)").is_err());
+
+	// Failed definitions don't replace old values
+	run("aa = 3").unwrap();
+	assert!(run("aa(x) = aa(x) +").is_err());
+	assert!(run("aa").unwrap().ends_with("[r]=[n] 3\n\n"));
+}
+
+#[test]
+fn ranges() {
+	good_expr("385", "sum(k, 1, 10, k^2)");
+	good_expr("120", "prod(k, 1, 5, k)");
+	good_expr("120", "product(k, 1, 5, k)");
+	good_expr("1.5962", "sum(k, 1, 20, 1/k^2)");
+	good_expr("2.7183", "sum(k, 0, 20, 1/k!)");
+	good_expr("6 m", "sum(k, 1, 3, k m)");
+	good_expr("6 m³", "prod(k, 1, 3, k m)");
+	good_expr("10", "sum(n, 1, 3, sum(k, 1, n, k))");
+	good_expr("0", "sum(k, 5, 1, k)");
+	good_expr("1", "prod(k, 5, 1, k)");
+
+	// Without an index variable, these are ordinary statistics functions
+	good_expr("10", "sum(1, 2, 3, 4)");
+
+	bad_expr("sum(k, 1, 3, k m + 1 s)");
+	bad_expr("sum(k, 1, 2, if(k == 1, 1 m, 1 s))");
+	bad_expr("sum(k, 1.5, 3, k)");
+	bad_expr("sum(k, 1, 1000000, k)");
+	bad_expr("sum(k, 1, 3, x)");
+	bad_expr("sum(k, 1, 3, k < 2)");
+	bad_expr("sum(pi, 1, 3, pi)");
+	bad_expr("sum(j, 1, 3, j)");
+	bad_expr("prod(e, 1, 3, e)");
+
+	// A defined variable isn't an index
+	let mut c = Context::new();
+	for s in ["aa = 1", "bb = 2", "cc = 3", "dd = 4"] {
+		daisycalc::do_string(&mut c, &String::from(s)).unwrap();
+	}
+	good_in(&mut c, "10", "sum(aa, bb, cc, dd)");
+	good_in(&mut c, "24", "product(aa, bb, cc, dd)");
+	good_in(&mut c, "10", "sum(k, 1, dd, k)");
+}
+
+#[test]
+fn calculus() {
+	good_expr("0.33333", "integrate(x, 0, 1, x^2)");
+	good_expr("0.66667", "integrate(x, 0, 1, sqrt(x))");
+	good_expr("0", "integrate(x, 0, 2pi, sin(x))");
+	good_expr("-0.5", "integrate(x, 1, 0, x)");
+	good_expr("50 J", "integrate(x, 0 s, 10 s, 5 W) to J");
+	good_expr("50 J", "integrate(x, 0 s, 10 s, 5 W)");
+	good_expr("2", "integrate(x, 0, 1, 1/sqrt(x))");
+	good_expr("-1", "integrate(x, 0, 1, ln(x))");
+	good_expr("3.1416", "integrate(x, -1, 1, 1/sqrt(1 - x^2))");
+	good_expr("5e5 m²", "integrate(x, 0 km, 1 km, x) to m^2");
+	good_expr("1", "derive(x, 0, sin(x))");
+	good_expr("12", "derive(x, 2, x^3)");
+	good_expr("4 m/s", "derive(x, 2 s, x^2 * 1 m/s^2)");
+
+	bad_expr("integrate(x, 0, 1 m, x)");
+	bad_expr("integrate(x, 0, 1, 1/x)");
+	bad_expr("integrate(x, 0, 1, x < 1)");
+	bad_expr("integrate(x, 0, 1, if(x < 0.5, 1 m, 1 s))");
+	bad_expr("integrate(q, 0, 1)");
+	bad_expr("derive(x, 0, y)");
+	bad_expr("integrate()");
+	bad_expr("integrate(1)");
+	bad_expr("integrate(x, 0)");
+	bad_expr("integrate(x, 0, 1, x, 3)");
+	bad_expr("derive(x)");
+	bad_expr("derive(x, 0, x, 3)");
+
+	// User functions
+	let mut c = Context::new();
+	let g = parser::parse(&c, &String::from("x^2")).unwrap();
+	c.push_function(String::from("f"), vec![String::from("x")], g).unwrap();
+
+	good_in(&mut c, "9", "integrate(f, 0, 3)");
+	good_in(&mut c, "6", "derive(f, 3)");
+
+	for s in ["integrate(f, 0)", "integrate(f, 0, 1, 2, 3)", "derive(f)"] {
+		let g = parser::parse(&c, &String::from(s)).unwrap();
+		assert!(evaluate(&mut c, &g).is_err());
+	}
+}
+
+#[test]
+fn solve() {
+	good_expr("1.4142", "solve(x^2 - 2, x, 1)");
+	good_expr("1.4142", "solve(x^2 - 2, x, 0, 5)");
+	good_expr("2", "solve(x^2 - 4, x, 1)");
+	good_expr("0.73909", "solve(cos(x) - x, x, 0, 1)");
+	good_expr("3.1416", "solve(sin(x), x, 3, 4)");
+	good_expr("2 m", "solve(x^2 - 4 m^2, x, 0 m, 10 m)");
+	good_expr("200 cm", "solve(x^2 - 4 m^2, x, 100 cm, 10 m)");
+
+	bad_expr("solve(x^2 + 1, x, 1)");
+	bad_expr("solve(x^2 + 1, x, 0, 1)");
+	bad_expr("solve(x^2 - 4 m^2, x, 0 m, 10 s)");
+	bad_expr("solve(x^2 - 2, x)");
+	bad_expr("solve()");
+	bad_expr("solve(x^2 - 2, x, 0, 1, 2)");
+	bad_expr("solve(x > 1, x, 0)");
+
+	// User functions
+	let mut c = Context::new();
+	let g = parser::parse(&c, &String::from("y^2 - 4 m^2")).unwrap();
+	c.push_function(String::from("f"), vec![String::from("y")], g).unwrap();
+
+	good_in(&mut c, "2 m", "solve(f, 0 m, 10 m)");
+	good_in(&mut c, "2 m", "solve(f, 1 m)");
+
+	// The user function form takes 2 or 3 arguments
+	for s in ["solve(f)", "solve(f, 0 m, 1 m, 10 m)"] {
+		let g = parser::parse(&c, &String::from(s)).unwrap();
+		let Err((_, e)) = evaluate(&mut c, &g) else { panic!() };
+		assert!(e.text().to_string().contains("2 or 3 arguments"));
+	}
+}
+
+#[test]
+fn tuples() {
+	good_expr("(4, 6)", "(1, 2) + (3, 4)");
+	good_expr("(2, 4)", "2(1, 2)");
+	good_expr("(0.5, 1)", "(1, 2) / 2");
+	good_expr("(-1, -2)", "-(1, 2)");
+	good_expr("(1, 4, 9)", "(1, 2, 3) * (1, 2, 3)");
+	good_expr("(100 cm, 200 cm)", "(1 m, 2 m) to cm");
+	good_expr("(0xFF, 0x10)", "(255, 16) to hex");
+
+	bad_expr("(1, 2) + (1, 2, 3)");
+	bad_expr("(1, 2) + 1 m");
+	bad_expr("(1, 2) < 3");
+}
+
+#[test]
+fn matrices() {
+	good_expr("⎡ 1  2 ⎤\n⎣ 3  4 ⎦", "((1, 2), (3, 4))");
+	good_expr("⎡ 2  3 ⎤\n⎣ 4  5 ⎦", "((1, 2), (3, 4)) + ((1, 1), (1, 1))");
+	good_expr("⎡  7  10 ⎤\n⎣ 15  22 ⎦", "((1, 2), (3, 4))^2");
+	good_expr("⎡ 1  0 ⎤\n⎣ 0  1 ⎦", "((1, 2), (3, 4)) * inv(((1, 2), (3, 4)))");
+	good_expr("⎡  -2     1 ⎤\n⎣ 1.5  -0.5 ⎦", "inv(((1, 2), (3, 4)))");
+	good_expr("⎡ 1  3 ⎤\n⎣ 2  4 ⎦", "transpose(((1, 2), (3, 4)))");
+	good_expr("(3, 7)", "((1, 2), (3, 4)) * (1, 1)");
+	good_expr("(4, 6)", "(1, 1) * ((1, 2), (3, 4))");
+	good_expr("-2", "det(((1, 2), (3, 4)))");
+	good_expr("-3", "det(((1, 2, 3), (4, 5, 6), (7, 8, 10)))");
+	good_expr("6 m²", "det(((2 m, 0), (0, 3 m)))");
+	good_expr("32", "dot((1, 2, 3), (4, 5, 6))");
+	good_expr("(0, 0, 1)", "cross((1, 0, 0), (0, 1, 0))");
+	good_expr("5", "norm((3, 4))");
+	good_expr("5 m", "norm((3 m, 4 m))");
+
+	bad_expr("det(((1, 2, 3), (4, 5, 6)))");
+	bad_expr("inv(((1, 2), (2, 4)))");
+	bad_expr("((1, 2), (3, 4))^0.5");
+	bad_expr("((1, 2), (3, 4)) * ((1, 2), (3, 4), (5, 6))");
+	bad_expr("cross((1, 2), (3, 4))");
+	bad_expr("dot((1, 2), (3, 4, 5))");
+
+	// Tuple arguments are echoed as written,
+	// and the echo evaluates to the same value.
+	let c = Context::new();
+	for s in [
+		"det((1, 2), (3, 4))",
+		"det(((1, 2), (3, 4)))",
+		"inv(((1, 2), (3, 4)))",
+		"transpose((1, 2))",
+		"norm((3, 4))"
+	] {
+		let g = parser::parse(&c, &String::from(s)).unwrap();
+		let e = g.display(&c);
+		assert_eq!(s, e);
+		assert_eq!(eval_to_str(s), eval_to_str(&e));
+	}
+	good_expr("-2", "det((1, 2), (3, 4))");
+}
+
+#[test]
+fn polynomials() {
+	good_expr("(1, 2)", "polyroots(1, -3, 2)");
+	good_expr("(1, 2)", "polyroots((1, -3, 2))");
+	good_expr("(1, 2, 3)", "polyroots(1, -6, 11, -6)");
+	good_expr("(1, 1, 1)", "polyroots(1, -3, 3, -1)");
+	good_expr("(-1.5, 1, 3)", "polyroots(6, -5, -2, 1) * 3");
+	good_expr("(-1 - 2i, -1 + 2i)", "polyroots(1, 2, 5)");
+	good_expr("(0, 0)", "polyroots(1, 0, 0)");
+	good_expr("1.2599", "dot(polyroots(1, 0, 0, -2), (0, 0, 1))");
+	good_expr("-2", "polyroots(0, 1, 2)");
+	good_expr("(-1e20, -1)", "polyroots(1e-20, 1, 1)");
+	good_expr("(-1e10, -1e-10)", "polyroots(1, 1e10, 1)");
+	good_expr("(1e-10, 1e10)", "polyroots(1, -1e10, 1)");
+	good_expr("12", "polyval((1, -3, 2), 5)");
+	good_expr("5 m", "polyval((1 m, 2 m), 3)");
+	good_expr("(1, 0, -1)", "polymul((1, 1), (1, -1))");
+	good_expr("(3, 6)", "polymul((1, 2), 3)");
+	good_expr("(1, -1)", "polydiv((1, 0, 0, 1), (1, 1, 1))");
+	good_expr("2", "polyrem((1, 0, 0, 1), (1, 1, 1))");
+	good_expr("0", "polyrem((1, 0, -1), (1, -1))");
+
+	bad_expr("polyroots(5)");
+	bad_expr("polyroots(1 m, 2)");
+	bad_expr("polyval((1, 2), 3 m)");
+	bad_expr("polydiv((1, 2), 0)");
+}
+
+#[test]
+fn intervals() {
+	good_expr("[19.6, 20.4] V", "[9.8, 10.2] ohm * 2 A to V");
+	good_expr("[9.8, 10.2]", "[9.8, 10.2]");
+	good_expr("[1, 2]", "interval(2, 1)");
+	good_expr("[4, 6]", "[1, 2] + [3, 4]");
+	good_expr("[-3, -1]", "[1, 2] - [3, 4]");
+	good_expr("[-4, 8]", "[-1, 2] * [3, 4]");
+	good_expr("[0.25, 0.66667]", "[1, 2] / [3, 4]");
+	good_expr("[0, 4]", "[-1, 2]^2");
+	good_expr("[-8, 1]", "[-2, 1]^3");
+	good_expr("[2, 3]", "sqrt([4, 9])");
+	good_expr("[0, 3]", "abs([-3, 2])");
+	good_expr("[0.84147, 1]", "sin([1, 2])");
+	good_expr("[-1, -0.65364]", "cos([3, 4])");
+	good_expr("[1, 2] m", "[1 m, 200 cm]");
+	good_expr("[100, 200] cm", "[1, 2] m to cm");
+	good_expr("[0.3048, 0.6096] m", "[1, 2] ft to m");
+	good_expr("true", "[1, 2] < [3, 4]");
+
+	bad_expr("[1 m, 2 s]");
+	bad_expr("[1, 2] < [1.5, 4]");
+	bad_expr("[1, 2] / [-1, 1]");
+	bad_expr("1 / [0, 1]");
+	bad_expr("[1, 2] * i");
+	bad_expr("[1, 2] + 2i");
+}
+
+#[test]
+fn uncertainty() {
+	good_expr("9.81 ± 0.02", "9.81 ± 0.02");
+	good_expr("9.81 ± 0.02", "9.81 +- 0.02");
+	good_expr("9.81 ± 0.02", "9.81 +/- 0.02");
+	good_expr("9.81 ± 0.02", "9.81+/-0.02");
+	good_expr("2", "5+-3");
+	good_expr("2", "5 +-3");
+	good_expr("8", "5--3");
+	good_expr("9.81 ± 0.02 m/s²", "9.81 ± 0.02 m/s^2");
+	good_expr("1.00 ± 0.02 m", "1 m ± 2 cm");
+	good_expr("15.0 ± 0.5", "(10 ± 0.3) + (5 ± 0.4)");
+	good_expr("5.0 ± 0.5", "(10 ± 0.3) - (5 ± 0.4)");
+	good_expr("50 ± 4", "(10 ± 0.3) * (5 ± 0.4)");
+	good_expr("2.00 ± 0.17", "(10 ± 0.3) / (5 ± 0.4)");
+	good_expr("4.0 ± 0.4", "(2 ± 0.1)^2");
+	good_expr("2.00 ± 0.10", "sqrt(4 ± 0.4)");
+	good_expr("0.841 ± 0.005", "sin(1 ± 0.01)");
+	good_expr("2.30 ± 0.10", "ln(10 ± 1)");
+	good_expr("6.0 ± 1.0", "2 * (3 ± 0.5)");
+	good_expr("3.00 ± 0.10", "1 + 2 ± 0.1");
+	good_expr("(1.2346 ± 0.0008)e5", "123456 ± 78");
+	good_expr("0.000123 ± 0.000004", "0.000123 ± 0.000004");
+	good_expr("2", "2 ± 0");
+
+	bad_expr("1 m ± 2 s");
+	bad_expr("2i ± 1");
+	bad_expr("(1 ± 0.1) * [1, 2]");
+	bad_expr("(1 ± 0.1) * i");
+	bad_expr("i - (1 ± 0.1)");
+}
+
+#[test]
+fn exact() {
+	good_expr("π", "pi");
+	good_expr("2π", "2pi");
+	good_expr("π/6", "pi/6");
+	good_expr("0.5", "sin(pi/6)");
+	good_expr("-0.5", "cos(2pi/3)");
+	good_expr("√3", "tan(pi/3)");
+	good_expr("2", "csc(pi/6)");
+	good_expr("0", "sin(pi)");
+	good_expr("2√2", "sqrt(8)");
+	good_expr("√2/2", "sqrt(1/2)");
+	good_expr("√2/2", "1/sqrt(2)");
+	good_expr("√6", "sqrt(2) * sqrt(3)");
+	good_expr("2", "sqrt(2)^2");
+	good_expr("2", "sqrt(4)");
+	good_expr("2π√3", "2π√3");
+	good_expr("π^2", "pi^2");
+	good_expr("π", "sqrt(pi^2)");
+	good_expr("0", "pi - pi");
+	good_expr("4.1416", "pi + 1");
+	good_expr("π r", "180 deg to r");
+	good_expr("(π/6) r", "30° to r");
+	good_expr("180°", "pi r to deg");
+	good_expr("(180/π)°", "1 r to deg");
+	good_expr("1", "sin(90 deg)");
+	good_expr("(-√2, √2)", "polyroots(1, 0, -2)");
+	good_expr("true", "pi == 2pi/2");
+}
+
+#[test]
+fn fractions() {
+	good_expr("3/4", "0.75 to frac");
+	good_expr("1/3", "1/3 to fraction");
+	good_expr("-4/3", "-4/3 to frac");
+	good_expr("1 1/3", "4/3 to mixed");
+	good_expr("-1 1/3", "-4/3 to mixed");
+	good_expr("2/3", "2/3 to mixed");
+	good_expr("5", "5 to frac");
+	good_expr("(1/2) m", "0.5 m to frac");
+	good_expr("π/2", "pi/2 to frac");
+	good_expr("0.33333", "1/3 to dec");
+	good_expr("3.1416", "pi to dec");
+	good_expr("1.4142", "sqrt(2) to dec");
+	good_expr("2.8284", "sqrt(8) to dec");
+	good_expr("57.296°", "1 r to deg to dec");
+	good_expr("3.1416 m", "pi m to dec");
+
+	good_expr("355/113", "rationalize(pi, 1000)");
+	good_expr("311/99", "rationalize(pi, 100)");
+	good_expr("22/7", "rationalize(pi, 10)");
+	good_expr("-1/3", "rationalize(-0.333333, 100)");
+	good_expr("1/10", "rationalize(0.1, 1000000)");
+	good_expr("(3/2) m", "rationalize(1.5 m, 10)");
+	good_expr("3", "rationalize(3, 7)");
+
+	bad_expr("rationalize(pi, 0)");
+	bad_expr("rationalize(pi, 2.5)");
+	bad_expr("rationalize(2i, 10)");
+	bad_expr("frac");
+
+	let mut c = Context::new();
+	c.config.output_format = daisycalc::quantity::DisplayFormat::Mixed;
+
+	good_in(&mut c, "2 1/2", "5/2");
+	good_in(&mut c, "0.69315", "ln(2)");
+	good_in(&mut c, "2.5", "5/2 to dec");
+	good_in(&mut c, "0xA", "10 to hex");
+}
+
+#[test]
+fn notation() {
+	use daisycalc::quantity::Notation;
+
+	let mut c = Context::new();
+
+	let mut good = |r: &str, s: &str, f: &dyn Fn(&mut Context)| {
+		f(&mut c);
+		c.config.check();
+		good_in(&mut c, r, s);
+	};
+
+	good("3.1416", "atan(1) * 4", &|_| {});
+
+	good("3.1415927", "atan(1) * 4", &|c| { c.config.sig_digits = Some(8) });
+	good("120", "123", &|c| { c.config.sig_digits = Some(2) });
+	good("3.1416", "atan(1) * 4", &|c| { c.config.sig_digits = None });
+
+	good("5e0", "5", &|c| { c.config.notation = Notation::Scientific });
+	good("1.23e-3", "0.00123", &|_| {});
+	good("1.2346e4", "12345.67", &|_| {});
+
+	good("5", "5", &|c| { c.config.notation = Notation::Engineering });
+	good("12.346e3", "12345.67", &|_| {});
+	good("1.23e-3", "0.00123", &|_| {});
+	good("470e-6", "0.00047", &|_| {});
+
+	good("0.33", "1/3", &|c| { c.config.notation = Notation::Fixed(2) });
+	good("100.00", "100", &|_| {});
+	good("0.01", "0.006", &|_| {});
+	good("0.00", "-0.004", &|_| {});
+	good("3", "3.14", &|c| { c.config.notation = Notation::Fixed(0) });
+
+	good("12,345", "12345", &|c| {
+		c.config.notation = Notation::Auto;
+		c.config.digit_separator = Some(',');
+	});
+	good("1,234.6", "1234.5678", &|_| {});
+	good("999", "999", &|_| {});
+	good("1 234 567.89", "1234567.891", &|c| {
+		c.config.notation = Notation::Fixed(2);
+		c.config.digit_separator = Some(' ');
+	});
+
+	good("4.7 mF", "0.0047 F", &|c| {
+		c.config.notation = Notation::Auto;
+		c.config.digit_separator = None;
+		c.config.enable_si_prefix = true;
+	});
+	good("4.7 km", "4700 m", &|_| {});
+	good("1.234 kg", "1234 g", &|_| {});
+	good("150 ns", "1.5e-7 s", &|_| {});
+	good("1 m", "1 m", &|_| {});
+	good("12 ft", "12 ft", &|_| {});
+	good("2 MiB", "2 MiB", &|_| {});
+	good("0.5 m²", "0.5 m^2", &|_| {});
+
+	// Output settings don't change how input is printed
+	c.config.notation = Notation::Fixed(2);
+	c.config.output_format = daisycalc::quantity::DisplayFormat::Fraction;
+	c.config.check();
+	let out = daisycalc::do_string(&mut c, &String::from("0.0047 F + 2.5 F")).unwrap().to_string();
+	assert!(out.contains("=>[n] 0.0047 F + 2.5 F\n"));
+	assert!(out.contains("=[n] (25047/10000) F\n"));
+}
+
+#[test]
+fn user_units() {
+	let mut c = Context::new();
+
+	let mut define = |s: &str, ok: bool| {
+		let out = daisycalc::do_string(&mut c, &String::from(s));
+		assert_eq!(ok, out.is_ok());
+	};
+
+	define("unit smoot = 1.7018 m", true);
+	define("prefixed unit beard_second = 5 nm", true);
+	define("unit dozen = 12", true);
+	define("unit m = 5", false);
+	define("unit sin = 5", false);
+	define("unit bad = -2 m", false);
+	define("unit bad = 2i", false);
+	define("smoot = 5", false);
+
+	good_in(&mut c, "5.1054 m", "3 smoot to m");
+	good_in(&mut c, "3.7018 m", "2 m + 1 smoot");
+	good_in(&mut c, "2e5 kbeard_second", "1 m to kbeard_second");
+	good_in(&mut c, "36", "3 dozen to 1");
+
+	// Units from another context are errors, not panics
+	let g = parser::parse(&c, &String::from("3 smoot")).unwrap();
+	let mut d = Context::new();
+	assert!(evaluate(&mut d, &g).is_err());
+}
+
+#[test]
+fn separate_contexts() {
+	use daisycalc::do_string;
+	use daisycalc::quantity::get_float_precision;
+
+	let mut a = Context::new();
+	do_string(&mut a, &String::from("precision 128")).unwrap();
+	do_string(&mut a, &String::from("unit smoot = 1.7018 m")).unwrap();
+	assert_eq!(get_float_precision(), Some(128));
+
+	// A new context doesn't see the state of `a`
+	let mut b = Context::new();
+	assert_eq!(get_float_precision(), None);
+	assert!(do_string(&mut b, &String::from("1 smoot")).is_err());
+	assert!(do_string(&mut b, &String::from("smoot = 5")).is_ok());
+
+	// ...and `a` still has it
+	let g = parser::parse(&a, &String::from("3 smoot to m")).unwrap();
+	assert_eq!("5.1054 m", evaluate(&mut a, &g).unwrap().display_outer(&a));
+	assert_eq!(get_float_precision(), Some(128));
+}
+
+#[test]
+fn definition_files() {
+	use daisycalc::load_definitions;
+
+	let mut c = Context::new();
+	load_definitions(&mut c, concat!(
+		"[[unit]]\n",
+		"enum_name = \"Smoot\"\n",
+		"strings = [[\"smoot\", \"\", \"k\"], [\"smoots\"]]\n",
+		"base_value_type = \"exact\"\n",
+		"base_value = \"1.7018\"\n",
+		"base_units = [{u = \"Meter\", p = 1}]\n",
+		"\n",
+		"[[unit]]\n",
+		"enum_name = \"Grad\"\n",
+		"strings = [[\"grad\"]]\n",
+		"base_value_type = \"fract\"\n",
+		"base_value = [9, 10]\n",
+		"base_units = [{u = \"Degree\", p = 1}]\n",
+		"\n",
+		"[[constant]]\n",
+		"enum_name = \"SmootSpeed\"\n",
+		"strings = [\"smootspeed\", \"sms\"]\n",
+		"value = \"3 smoot / s\"\n",
+	)).unwrap();
+
+	good_in(&mut c, "5.1054 m", "3 smoots to m");
+	good_in(&mut c, "0.58761 ksmoot", "1 km to ksmoot");
+	good_in(&mut c, "90°", "100 grad to deg");
+	good_in(&mut c, "5.1054 m", "sms * 1 s to m");
+
+	let bad = |r: &str, s: &str| {
+		let mut c = Context::new();
+		assert_eq!(Err(r.to_string()), load_definitions(&mut c, s));
+	};
+
+	bad("unknown table `units`, expected `unit` or `constant`", "[[units]]\nenum_name = \"A\"");
+	bad("unit 1 has no `enum_name`", "[[unit]]\nstrings = [[\"a\"]]");
+	bad("unit `A`: unknown key `bsae`", "[[unit]]\nenum_name = \"A\"\nbsae = true");
+	bad("unit `A`: new base units can't be defined, use `base_units` instead", "[[unit]]\nenum_name = \"A\"\nbase = true");
+	bad("unit `A`: `m` is already defined", "[[unit]]\nenum_name = \"A\"\nstrings = [[\"m\"]]");
+	bad("unit `A`: `x` is not a prefix (in strings for `a`)", "[[unit]]\nenum_name = \"A\"\nstrings = [[\"a\", \"x\"]]");
+	bad("unit `A`: missing `base_value_type`", "[[unit]]\nenum_name = \"A\"\nstrings = [[\"a\"]]");
+	bad(
+		"unit `A`: `Foot` is not a base unit",
+		"[[unit]]\nenum_name = \"A\"\nstrings = [[\"a\"]]\nbase_value_type = \"exact\"\nbase_value = \"2\"\nbase_units = [{u = \"Foot\", p = 1}]"
+	);
+	bad("constant `A`: `value` must be a string", "[[constant]]\nenum_name = \"A\"\nstrings = \"a\"\nvalue = 2");
+
+	// Units are loaded first, even if a constant comes before them
+	let mut c = Context::new();
+	load_definitions(&mut c, concat!(
+		"[[constant]]\n",
+		"enum_name = \"TwoWugs\"\n",
+		"strings = [\"twowugs\"]\n",
+		"value = \"2 wug\"\n",
+		"\n",
+		"[[unit]]\n",
+		"enum_name = \"Wug\"\n",
+		"strings = [[\"wug\"]]\n",
+		"base_value_type = \"exact\"\n",
+		"base_value = \"1.5\"\n",
+		"base_units = [{u = \"Meter\", p = 1}]\n",
+	)).unwrap();
+	good_in(&mut c, "3 m", "twowugs to m");
+}
+
+#[test]
+fn units_command() {
+	use daisycalc::command::do_command;
+
+	let mut c = Context::new();
+	let mut units = |s: &str| do_command(&mut c, &String::from(s)).to_string();
+
+	let t = units("units");
+	assert!(t.contains("[t]Length[n]"));
+	assert!(t.contains("[t]Energy[n]"));
+
+	let t = units("units length");
+	assert!(t.contains("  [c]m[n]       base unit     meter, meters (SI prefixes)\n"));
+	assert!(t.contains("  [c]ft[n]      0.3048 m      foot, feet\n"));
+	assert!(t.contains("  [c]thou[n]    2.54e-5 m\n"));
+	assert!(!t.contains("[t]Time[n]"));
+
+	let t = units("units magnetic field");
+	assert!(t.contains("  [c]T[n] 1000 g/(A·s²)  tesla (SI prefixes)\n"));
+
+	let t = units("units B");
+	assert!(t.contains("[t]Information[n]"));
+	assert!(t.contains("(SI and binary prefixes)"));
+
+	assert_eq!(units("units foo"), "[c]foo[n] [e]isn't a dimension or a unit.[n]\n\n");
+}
+
+#[test]
+fn unit_sets() {
+	use daisycalc::quantity::UnitSet;
+
+	let mut c = Context::new();
+	c.config.disabled_unit_sets = vec![UnitSet::Imperial, UnitSet::Obscure];
+	c.config.check();
+
+	let g = parser::parse(&c, &String::from("2 ft")).unwrap();
+	assert!(evaluate(&mut c, &g).is_err());
+	assert!(c.valid_varible("ft"));
+	assert!(c.valid_varible("bar"));
+	assert!(!c.valid_varible("cup"));
+
+	// Constants still work if their units are off
+	let g = parser::parse(&c, &String::from("2 mph")).unwrap();
+	assert_eq!("2 mi/h", evaluate(&mut c, &g).unwrap().display_outer(&c));
+
+	c.config.disabled_unit_sets = Vec::new();
+	c.config.check();
+	assert!(!c.valid_varible("ft"));
+	good_expr("0.6096 m", "2 ft to m");
+
+	// Only newly hidden variables are listed
+	let mut c = Context::new();
+	let mut run = |s: &str| daisycalc::do_string(&mut c, &String::from(s)).unwrap().to_string();
+
+	run("unitset imperial off");
+	run("ft = 3");
+	assert!(run("unitset imperial on").contains("hidden by units:[n] [c]ft[n]"));
+	assert!(!run("unitset obscure on").contains("hidden"));
+	assert!(!run("unitset imperial off").contains("hidden"));
+	assert!(!run("unitset obscure on").contains("hidden"));
+}
+
+#[test]
+fn log_units() {
+	good_expr("100 mW", "20 dBm to mW");
+	good_expr("30 dBm", "1 W to dBm");
+	good_expr("0 dBW", "30 dBm to dBW");
+	good_expr("1.9953 V", "6 dBV to V");
+	good_expr("93.979 dBSPL", "1 Pa to dBSPL");
+	good_expr("100 dB", "10 bel to dB");
+	good_expr("8.6859 dB", "1 Np to dB");
+
+	good_expr("13 dBm", "10 dBm + 3 dB");
+	good_expr("13 dBm", "3 dB + 10 dBm");
+	good_expr("7 dBm", "10 dBm - 3 dB");
+	good_expr("-20 dB", "10 dBm - 0 dBW");
+	good_expr("6 dB", "3 dB * 2");
+	good_expr("5 dB", "2 dB + 3 dB");
+	good_expr("13 dBm", "sum(10 dBm, 3 dB)");
+	good_expr("true", "10 dBm == 10 mW");
+	good_expr("true", "20 dBm > 50 mW");
+
+	bad_expr("10 dBm + 10 dBm");
+	bad_expr("10 dBm - 3 dBV");
+	bad_expr("3 dB - 10 dBm");
+	bad_expr("3 dB * 3 dB");
+	bad_expr("2 / 3 dB");
+	bad_expr("10 dBm * 1 m");
+	bad_expr("3 dB + 1");
+	bad_expr("(3 dB)^2");
+	bad_expr("20 dBm to dB");
+	bad_expr("10 dBm to V");
+	bad_expr("-1 W to dBm");
+	bad_expr("0 W to dBm");
+	bad_expr("10 dBm * 2");
+	bad_expr("2 * 10 dBm");
+	bad_expr("10 dBm / 2");
+	bad_expr("sum(10 dBm, 20 dBm)");
+	bad_expr("mean(10 dB, 20 dB)");
+	bad_expr("sum(k, 1, 3, 10 dBm)");
+}